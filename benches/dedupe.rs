@@ -0,0 +1,103 @@
+//! Benchmarks deduplication on a synthetic corpus resembling a database export.
+//!
+//! Exports merged from several databases repeat many records verbatim. The
+//! `exact_copies` corpus has as many citations as `distinct`, but only a third
+//! as many distinct records, so the gap between the two shows what the hashing
+//! pass for exact duplicates saves. Run with:
+//!
+//! ```text
+//! cargo bench --bench dedupe
+//! ```
+
+use biblib::dedupe::Deduplicator;
+use biblib::{Author, Citation};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const WORDS: [&str; 24] = [
+    "effects",
+    "exercise",
+    "sleep",
+    "quality",
+    "adolescents",
+    "randomized",
+    "trial",
+    "cognitive",
+    "performance",
+    "cohort",
+    "mortality",
+    "risk",
+    "diabetes",
+    "children",
+    "intervention",
+    "outcomes",
+    "systematic",
+    "review",
+    "analysis",
+    "patients",
+    "therapy",
+    "chronic",
+    "pain",
+    "depression",
+];
+
+const FAMILY_NAMES: [&str; 8] = [
+    "Smith", "Jones", "Garcia", "Chen", "Müller", "Okafor", "Tanaka", "Rossi",
+];
+
+/// A citation built from `seed`, the same for the same seed
+fn citation(seed: usize, id: usize) -> Citation {
+    // A linear congruential generator keeps the corpus deterministic
+    let mut state = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    let mut next = |n: usize| {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 33) % n
+    };
+    let title = (0..8)
+        .map(|_| WORDS[next(WORDS.len())])
+        .collect::<Vec<_>>()
+        .join(" ");
+    Citation {
+        id: id.to_string(),
+        title,
+        authors: vec![Author {
+            family_name: FAMILY_NAMES[next(FAMILY_NAMES.len())].to_string(),
+            given_name: "A".to_string(),
+            ..Default::default()
+        }],
+        journal: Some(format!("Journal of {}", WORDS[next(WORDS.len())])),
+        year: Some(2015 + next(8) as i32),
+        volume: Some((1 + next(60)).to_string()),
+        pages: Some(format!("{}-{}", 100 + seed % 900, 110 + seed % 900)),
+        ..Default::default()
+    }
+}
+
+/// `len` citations, each of the `len / copies` records repeated `copies` times
+fn corpus(len: usize, copies: usize) -> Vec<Citation> {
+    (0..len).map(|i| citation(i / copies, i)).collect()
+}
+
+fn bench_find_duplicates(c: &mut Criterion) {
+    let mut group = c.benchmark_group("find_duplicates");
+    group.sample_size(10);
+    for len in [300, 900] {
+        for (name, copies) in [("distinct", 1), ("exact_copies", 3)] {
+            let citations = corpus(len, copies);
+            group.bench_with_input(BenchmarkId::new(name, len), &citations, |b, citations| {
+                b.iter(|| {
+                    Deduplicator::new()
+                        .find_duplicates(black_box(citations))
+                        .unwrap()
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_find_duplicates);
+criterion_main!(benches);
@@ -0,0 +1,43 @@
+//! Parses several citation files concurrently with a single shared parser.
+//!
+//! Parsers hold only their configuration, so one instance can be borrowed by
+//! many threads at once. Run with:
+//!
+//! ```text
+//! cargo run --example concurrent_parsing -- refs1.ris refs2.ris refs3.ris
+//! ```
+
+use biblib::{CitationParser, RisParser};
+use std::{env, fs, thread};
+
+fn main() {
+    let paths: Vec<String> = env::args().skip(1).collect();
+    if paths.is_empty() {
+        eprintln!("usage: concurrent_parsing <file.ris>...");
+        return;
+    }
+
+    let parser = RisParser::new().with_source("Local files");
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = paths
+            .iter()
+            .map(|path| {
+                let parser = &parser;
+                scope.spawn(move || {
+                    let content = fs::read_to_string(path)?;
+                    parser
+                        .parse(&content)
+                        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+                })
+            })
+            .collect();
+
+        for (path, handle) in paths.iter().zip(handles) {
+            match handle.join().expect("parser thread panicked") {
+                Ok(citations) => println!("{}: {} citations", path, citations.len()),
+                Err(e) => eprintln!("{}: {}", path, e),
+            }
+        }
+    });
+}
@@ -0,0 +1,287 @@
+//! BibTeX output for citations.
+//!
+//! # Example
+//!
+//! ```
+//! use biblib::bibtex::write_bibtex;
+//! use biblib::{Author, Citation};
+//!
+//! let citation = Citation {
+//!     citation_type: vec!["Journal Article".to_string()],
+//!     title: "Example Title".to_string(),
+//!     authors: vec![Author {
+//!         family_name: "Smith".to_string(),
+//!         given_name: "John".to_string(),
+//!         ..Default::default()
+//!     }],
+//!     year: Some(2023),
+//!     pages: Some("1-10".to_string()),
+//!     ..Default::default()
+//! };
+//!
+//! let mut output = Vec::new();
+//! write_bibtex([&citation], &mut output).unwrap();
+//! assert_eq!(
+//!     String::from_utf8(output).unwrap(),
+//!     "@article{smith2023,\n  title = {Example Title},\n  author = {Smith, John},\n  year = {2023},\n  pages = {1--10},\n}\n\n"
+//! );
+//! ```
+
+use std::collections::HashSet;
+use std::io::Write;
+
+use crate::{Author, Citation, CitationType, Result};
+
+/// Writes citations as BibTeX entries.
+///
+/// The entry type is derived from the citation's types, e.g. `"JOUR"` or
+/// `"Journal Article"` become `@article`, falling back to `@misc`. Keys are the
+/// first author's family name followed by the year, e.g. `smith2023`, with
+/// letters appended when a key repeats: `a` to `z`, then `aa`, `ab` and so on. Citations without authors are keyed by
+/// their id. An `arxiv_id` is written as `eprint` with `archiveprefix = {arXiv}`,
+/// as biblatex and INSPIRE-HEP expect. `extra_fields` are not written.
+///
+/// # Arguments
+///
+/// * `citations` - The citations to write
+/// * `writer` - Destination for the BibTeX output
+///
+/// # Errors
+///
+/// Returns `CitationError::Io` if writing fails.
+pub fn write_bibtex<'a, W: Write>(
+    citations: impl IntoIterator<Item = &'a Citation>,
+    mut writer: W,
+) -> Result<()> {
+    let mut keys = HashSet::new();
+    for citation in citations {
+        let base = base_key(citation);
+        let mut key = base.clone();
+        let mut repeat = 0;
+        while !keys.insert(key.clone()) {
+            key = format!("{}{}", base, key_suffix(repeat));
+            repeat += 1;
+        }
+        write_entry(citation, &key, &mut writer)?;
+    }
+    Ok(())
+}
+
+/// Writes a single citation as a BibTeX entry
+fn write_entry<W: Write>(citation: &Citation, key: &str, writer: &mut W) -> Result<()> {
+    let entry_type = entry_type(&citation.citation_type);
+    writeln!(writer, "@{}{{{},", entry_type, key)?;
+    let mut field = |name: &str, value: &str| -> Result<()> {
+        let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !value.is_empty() {
+            writeln!(writer, "  {} = {{{}}},", name, escape(&value))?;
+        }
+        Ok(())
+    };
+
+    field("title", &citation.title)?;
+    field(
+        "author",
+        &names(citation.authors.iter().filter(|a| a.is_author())),
+    )?;
+    field("editor", &names(citation.editors()))?;
+    let container = match entry_type {
+        "inproceedings" => ("booktitle", citation.conference_name.as_ref()),
+        "incollection" => ("booktitle", None),
+        _ => ("journal", None),
+    };
+    if let Some(name) = container.1.or(citation.journal.as_ref()) {
+        field(container.0, name)?;
+    }
+    if let Some(year) = citation.year {
+        field("year", &year.to_string())?;
+    }
+    for (name, value) in [("volume", &citation.volume), ("number", &citation.issue)] {
+        if let Some(value) = value {
+            field(name, value)?;
+        }
+    }
+    if let Some(pages) = &citation.pages {
+        field("pages", &pages.replacen('-', "--", 1))?;
+    }
+    for (name, value) in [
+        ("doi", &citation.doi),
+        ("abstract", &citation.abstract_text),
+        ("publisher", &citation.publisher),
+        ("series", &citation.series),
+        ("address", &citation.place),
+        ("language", &citation.language),
+    ] {
+        if let Some(value) = value {
+            field(name, value)?;
+        }
+    }
+    if let Some(arxiv_id) = &citation.arxiv_id {
+        field("eprint", arxiv_id)?;
+        field("archiveprefix", "arXiv")?;
+    }
+    field("issn", &citation.issn.join(", "))?;
+    field("isbn", &citation.isbn.join(", "))?;
+    field("keywords", &citation.keywords.join(", "))?;
+    if let Some(url) = citation.urls.first() {
+        field("url", url)?;
+    }
+    writeln!(writer, "}}")?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// Joins names as `Family, Given` with `and`
+fn names<'a>(authors: impl Iterator<Item = &'a Author>) -> String {
+    authors
+        .map(|author| match author.given_name.trim() {
+            "" => author.family_name.trim().to_string(),
+            given => format!("{}, {}", author.family_name.trim(), given),
+        })
+        .collect::<Vec<_>>()
+        .join(" and ")
+}
+
+/// The letters appended to the `n`th repeat of a key: `a` to `z`, `aa` to
+/// `az`, `ba` and so on
+fn key_suffix(mut n: usize) -> String {
+    let mut letters = Vec::new();
+    loop {
+        letters.push(b'a' + (n % 26) as u8);
+        if n < 26 {
+            break;
+        }
+        n = n / 26 - 1;
+    }
+    letters.iter().rev().map(|&letter| letter as char).collect()
+}
+
+/// Builds the entry key before any suffix for repeated keys
+fn base_key(citation: &Citation) -> String {
+    let family: String = citation
+        .authors
+        .iter()
+        .find(|author| author.is_author())
+        .map(|author| {
+            author
+                .family_name
+                .chars()
+                .filter(char::is_ascii_alphanumeric)
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .unwrap_or_default();
+    match (family.is_empty(), citation.year) {
+        (false, Some(year)) => format!("{}{}", family, year),
+        (false, None) => family,
+        (true, _) if !citation.id.is_empty() => citation.id.clone(),
+        (true, _) => "citation".to_string(),
+    }
+}
+
+/// Picks the BibTeX entry type for a citation's types
+fn entry_type(types: &[String]) -> &'static str {
+    match CitationType::from_types(types) {
+        CitationType::JournalArticle => "article",
+        CitationType::Book => "book",
+        CitationType::BookChapter => "incollection",
+        CitationType::ConferencePaper => "inproceedings",
+        CitationType::Thesis => "phdthesis",
+        CitationType::Report => "techreport",
+        CitationType::Other => "misc",
+    }
+}
+
+/// Escapes braces so every value stays balanced inside its `{}` delimiters
+fn escape(value: &str) -> String {
+    value.replace('{', "\\{").replace('}', "\\}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Author;
+
+    #[test]
+    fn test_arxiv_eprint() {
+        let citation = Citation {
+            title: "Large N limit".to_string(),
+            arxiv_id: Some("hep-th/9711200".to_string()),
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        write_bibtex([&citation], &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("  eprint = {hep-th/9711200},\n  archiveprefix = {arXiv},\n"));
+    }
+
+    #[test]
+    fn test_repeated_keys_and_entry_types() {
+        let citation = |citation_type: &str, title: &str| Citation {
+            citation_type: vec![citation_type.to_string()],
+            title: title.to_string(),
+            authors: vec![Author {
+                family_name: "O'Brien".to_string(),
+                given_name: "Anne".to_string(),
+                ..Default::default()
+            }],
+            year: Some(2020),
+            conference_name: Some("Annual Meeting".to_string()),
+            series: Some("Lecture Notes".to_string()),
+            ..Default::default()
+        };
+        let citations = [
+            citation("CONF", "Talk {draft}"),
+            citation("Thesis", "Dissertation"),
+        ];
+
+        let mut output = Vec::new();
+        write_bibtex(&citations, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert!(output.contains("@inproceedings{obrien2020,\n  title = {Talk \\{draft\\}},"));
+        assert!(output.contains("  booktitle = {Annual Meeting},"));
+        assert!(output.contains("  series = {Lecture Notes},"));
+        assert!(output.contains("@phdthesis{obrien2020a,"));
+    }
+
+    #[test]
+    fn test_many_repeated_keys() {
+        let citation = Citation {
+            title: "Title".to_string(),
+            authors: vec![Author {
+                family_name: "Wang".to_string(),
+                ..Default::default()
+            }],
+            year: Some(2020),
+            ..Default::default()
+        };
+        let citations = vec![citation; 300];
+
+        let mut output = Vec::new();
+        write_bibtex(&citations, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let keys: Vec<&str> = output
+            .lines()
+            .filter_map(|line| line.strip_prefix("@misc{"))
+            .map(|line| line.trim_end_matches(','))
+            .collect();
+
+        assert_eq!(keys.len(), 300);
+        assert_eq!(keys.iter().collect::<HashSet<_>>().len(), 300);
+        assert!(keys
+            .iter()
+            .all(|key| key.chars().all(|c| c.is_ascii_alphanumeric())));
+        assert_eq!(
+            [keys[0], keys[1], keys[26], keys[27], keys[28], keys[299]],
+            [
+                "wang2020",
+                "wang2020a",
+                "wang2020z",
+                "wang2020aa",
+                "wang2020ab",
+                "wang2020km"
+            ]
+        );
+    }
+}
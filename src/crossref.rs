@@ -0,0 +1,480 @@
+//! Crossref JSON parser for works returned by the Crossref REST API.
+//!
+//! Accepts the response of the `/works/{doi}` route, whose `message` is a single
+//! work, and of the `/works` search route, whose `message` holds an `items` list,
+//! with built-in source tracking. A bare work object or an array of works is
+//! accepted too.
+//!
+//! # Example
+//!
+//! ```
+//! use biblib::{CitationParser, CrossrefParser};
+//!
+//! let input = r#"{
+//!   "status": "ok",
+//!   "message-type": "work",
+//!   "message": {
+//!     "DOI": "10.1000/example",
+//!     "type": "journal-article",
+//!     "title": ["Example Title"],
+//!     "author": [{"given": "John", "family": "Smith", "sequence": "first"}],
+//!     "container-title": ["Example Journal"],
+//!     "issued": {"date-parts": [[2020, 5, 1]]}
+//!   }
+//! }"#;
+//!
+//! let parser = CrossrefParser::new().with_source("Crossref");
+//!
+//! let citations = parser.parse(input).unwrap();
+//! assert_eq!(citations[0].title, "Example Title");
+//! assert_eq!(citations[0].year, Some(2020));
+//! assert_eq!(citations[0].doi.as_deref(), Some("10.1000/example"));
+//! assert_eq!(citations[0].source.as_deref(), Some("Crossref"));
+//! ```
+
+use nanoid::nanoid;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::Value;
+
+use crate::utils::{
+    clean_input, format_page_numbers, normalize_orcid, strip_markup, ParserOptions, WarningHandler,
+};
+use crate::{Author, AuthorRole, Citation, CitationError, CitationParser, Grant, Result};
+
+/// JATS section titles, such as `<jats:title>Abstract</jats:title>`, dropped
+/// with their content
+static JATS_TITLE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<jats:title>.*?</jats:title>").unwrap());
+
+/// Any other JATS tag, dropped keeping its content
+static JATS_TAG_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"</?jats:[^>]*>").unwrap());
+
+/// Parser for Crossref JSON works.
+///
+/// # Field handling
+///
+/// | Key                                           | Field             |
+/// |-----------------------------------------------|-------------------|
+/// | `title[0]`, `subtitle[0]`                     | `title`           |
+/// | `author` (`family`, `given`, `ORCID`)         | `authors`         |
+/// | `editor`, `translator`                        | `authors`         |
+/// | `container-title[0]`                          | `journal`         |
+/// | `short-container-title[0]`                    | `journal_abbr`    |
+/// | `issued.date-parts`                           | `year`            |
+/// | `volume`                                      | `volume`          |
+/// | `issue`                                       | `issue`           |
+/// | `page`                                        | `pages`           |
+/// | `DOI`                                         | `doi`             |
+/// | `ISSN`                                        | `issn`            |
+/// | `ISBN`                                        | `isbn`            |
+/// | `type`                                        | `citation_type`   |
+/// | `abstract`                                    | `abstract_text`   |
+/// | `subject`                                     | `keywords`        |
+/// | `URL`                                         | `urls`            |
+/// | `language`                                    | `language`        |
+/// | `publisher`                                   | `publisher`       |
+/// | `publisher-location`                          | `place`           |
+/// | `event.name`                                  | `conference_name` |
+/// | `is-referenced-by-count`                      | `cited_by_count`  |
+/// | `reference-count`, `references-count`         | `reference_count` |
+/// | `funder` (`name`, `award`)                    | `grants`          |
+///
+/// A subtitle is appended to the title after a colon. The year falls back to
+/// `published-print`, `published-online` and `published` when `issued` has
+/// none. JATS markup is removed from abstracts and inline markup from titles.
+/// Editors and translators follow the authors, with their [`AuthorRole`].
+/// Authors without a family name, such as consortia, take their `name` as the
+/// family name. Works without a title are skipped.
+#[derive(Debug, Default, Clone)]
+pub struct CrossrefParser {
+    options: ParserOptions,
+}
+
+impl CrossrefParser {
+    /// Creates a new Crossref parser instance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::CrossrefParser;
+    /// let parser = CrossrefParser::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the source of the parsed citations.
+    ///
+    /// Each citation's record is also named under
+    /// [`SOURCE_RECORD_KEY`](crate::SOURCE_RECORD_KEY).
+    #[must_use]
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.options.source = Some(source.to_string());
+        self
+    }
+
+    /// Stops parsing once `limit` citations have been read.
+    ///
+    /// Useful for previewing large inputs. A limit of `0` returns no citations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::CrossrefParser;
+    /// let parser = CrossrefParser::new().with_limit(10);
+    /// ```
+    #[must_use]
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.options.limit = Some(limit);
+        self
+    }
+
+    /// Sets the longest field, in bytes, kept from the input.
+    ///
+    /// Longer fields are truncated at a character boundary, as they are read,
+    /// and a warning is reported through [`CrossrefParser::with_warning_handler`].
+    /// Defaults to [`DEFAULT_MAX_FIELD_LENGTH`](crate::DEFAULT_MAX_FIELD_LENGTH).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::CrossrefParser;
+    /// let parser = CrossrefParser::new().with_max_field_length(64 * 1024);
+    /// ```
+    #[must_use]
+    pub fn with_max_field_length(mut self, max: usize) -> Self {
+        self.options.max_field_length = Some(max);
+        self
+    }
+
+    /// Sets the function receiving warnings, which are ignored otherwise.
+    ///
+    /// Warnings are reported when input is altered, for example when a field
+    /// exceeds the [maximum length](CrossrefParser::with_max_field_length).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::CrossrefParser;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let warnings = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = Arc::clone(&warnings);
+    /// let parser = CrossrefParser::new()
+    ///     .with_warning_handler(move |message| sink.lock().unwrap().push(message.to_string()));
+    /// ```
+    #[must_use]
+    pub fn with_warning_handler(mut self, handler: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.options.warning_handler = WarningHandler::new(handler);
+        self
+    }
+
+    /// Sets whether whitespace is normalized in every field.
+    ///
+    /// When enabled, runs of whitespace, including non-breaking spaces and
+    /// line breaks, become a single space and zero-width characters are
+    /// removed, as by
+    /// [`normalize_whitespace`](crate::transform::normalize_whitespace). Off by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::CrossrefParser;
+    /// let parser = CrossrefParser::new().with_whitespace_normalization(true);
+    /// ```
+    #[must_use]
+    pub fn with_whitespace_normalization(mut self, enabled: bool) -> Self {
+        self.options.normalize_whitespace = enabled;
+        self
+    }
+
+    /// Finds the works in a response, a single work or an array of works
+    fn works(value: &Value) -> Vec<&Value> {
+        let value = value.get("message").unwrap_or(value);
+        match value.get("items").unwrap_or(value) {
+            Value::Array(items) => items.iter().collect(),
+            work @ Value::Object(_) => vec![work],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns a string value, or the first string of an array value
+    fn text<'a>(work: &'a Value, key: &str) -> Option<&'a str> {
+        let value = match work.get(key)? {
+            Value::Array(values) => values.first()?,
+            value => value,
+        };
+        value
+            .as_str()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+    }
+
+    /// Returns every string of an array value, or a lone string value
+    fn texts(work: &Value, key: &str) -> Vec<String> {
+        match work.get(key) {
+            Some(Value::Array(values)) => values
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|text| text.trim().to_string())
+                .filter(|text| !text.is_empty())
+                .collect(),
+            Some(Value::String(text)) if !text.trim().is_empty() => vec![text.trim().to_string()],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns a count, which Crossref writes as a number
+    fn count(work: &Value, key: &str) -> Option<u32> {
+        work.get(key)?
+            .as_u64()
+            .and_then(|count| u32::try_from(count).ok())
+    }
+
+    /// Returns the year of the first date found under `keys`
+    fn year(work: &Value, keys: &[&str]) -> Option<i32> {
+        keys.iter().find_map(|key| {
+            work.get(key)?
+                .get("date-parts")?
+                .get(0)?
+                .get(0)?
+                .as_i64()
+                .and_then(|year| i32::try_from(year).ok())
+        })
+    }
+
+    /// Removes JATS markup from an abstract
+    fn clean_abstract(text: &str) -> String {
+        let text = JATS_TITLE_REGEX.replace_all(text, " ");
+        let text = JATS_TAG_REGEX.replace_all(&text, " ");
+        strip_markup(&text)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Reads a contributor object
+    fn author(value: &Value, role: Option<AuthorRole>) -> Option<Author> {
+        let family_name = Self::text(value, "family").or_else(|| Self::text(value, "name"))?;
+        Some(Author {
+            family_name: family_name.to_string(),
+            given_name: Self::text(value, "given").unwrap_or_default().to_string(),
+            affiliation: value
+                .get("affiliation")
+                .and_then(|affiliations| affiliations.get(0))
+                .and_then(|affiliation| Self::text(affiliation, "name"))
+                .map(String::from),
+            orcid: Self::text(value, "ORCID").and_then(normalize_orcid),
+            role,
+        })
+    }
+
+    /// Builds a citation from a single work.
+    ///
+    /// Returns `None` if the work has no title.
+    fn parse_work(&self, work: &Value) -> Option<Citation> {
+        let title = strip_markup(Self::text(work, "title")?);
+        let mut citation = Citation {
+            id: nanoid!(),
+            title: match Self::text(work, "subtitle") {
+                Some(subtitle) => format!("{}: {}", title, strip_markup(subtitle)),
+                None => title,
+            },
+            source: self.options.source.clone(),
+            ..Default::default()
+        };
+
+        citation.citation_type = Self::texts(work, "type");
+        for (key, role) in [
+            ("author", None),
+            ("editor", Some(AuthorRole::Editor)),
+            ("translator", Some(AuthorRole::Translator)),
+        ] {
+            if let Some(Value::Array(people)) = work.get(key) {
+                citation.authors.extend(
+                    people
+                        .iter()
+                        .filter_map(|person| Self::author(person, role)),
+                );
+            }
+        }
+        citation.journal = Self::text(work, "container-title").map(String::from);
+        citation.journal_abbr = Self::text(work, "short-container-title").map(String::from);
+        citation.year = Self::year(
+            work,
+            &["issued", "published-print", "published-online", "published"],
+        );
+        citation.volume = Self::text(work, "volume").map(String::from);
+        citation.issue = Self::text(work, "issue").map(String::from);
+        citation.pages = Self::text(work, "page").map(format_page_numbers);
+        if let Some(doi) = Self::text(work, "DOI") {
+            citation.add_doi(doi);
+        }
+        citation.issn = Self::texts(work, "ISSN");
+        citation.isbn = Self::texts(work, "ISBN");
+        citation.abstract_text = Self::text(work, "abstract")
+            .map(Self::clean_abstract)
+            .filter(|text| !text.is_empty());
+        citation.keywords = Self::texts(work, "subject");
+        citation.urls = Self::texts(work, "URL");
+        citation.language = Self::text(work, "language").map(String::from);
+        citation.publisher = Self::text(work, "publisher").map(String::from);
+        citation.place = Self::text(work, "publisher-location").map(String::from);
+        citation.conference_name = work
+            .get("event")
+            .and_then(|event| Self::text(event, "name"))
+            .map(String::from);
+        citation.cited_by_count = Self::count(work, "is-referenced-by-count");
+        citation.reference_count =
+            Self::count(work, "reference-count").or_else(|| Self::count(work, "references-count"));
+        if let Some(Value::Array(funders)) = work.get("funder") {
+            for funder in funders {
+                let agency = Self::text(funder, "name").map(String::from);
+                let awards = Self::texts(funder, "award");
+                if awards.is_empty() {
+                    citation.grants.push(Grant {
+                        agency,
+                        ..Default::default()
+                    });
+                    continue;
+                }
+                for award in awards {
+                    citation.grants.push(Grant {
+                        id: Some(award),
+                        agency: agency.clone(),
+                        country: None,
+                    });
+                }
+            }
+        }
+
+        Some(citation)
+    }
+}
+
+impl CitationParser for CrossrefParser {
+    fn parse(&self, input: &str) -> Result<Vec<Citation>> {
+        let input: &str = &clean_input(input);
+        if input.trim().is_empty() {
+            return Err(CitationError::InvalidFormat("Empty input".into()));
+        }
+
+        let limit = self.options.limit();
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let value: Value = serde_json::from_str(input)?;
+        let mut citations: Vec<Citation> = Self::works(&value)
+            .into_iter()
+            .filter_map(|work| self.parse_work(work))
+            .take(limit)
+            .collect();
+
+        if citations.is_empty() {
+            return Err(CitationError::InvalidFormat(
+                "No valid citations found".into(),
+            ));
+        }
+
+        self.options.finish(&mut citations);
+        Ok(citations)
+    }
+
+    fn format_name(&self) -> &'static str {
+        "Crossref JSON"
+    }
+
+    fn can_parse(&self, input: &str) -> bool {
+        let input: &str = &clean_input(input);
+        let trimmed = input.trim();
+        trimmed.starts_with(['{', '[']) && trimmed.contains("\"DOI\"")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_work() {
+        let input = include_str!("../tests/fixtures/crossref_work.json");
+        let citations = CrossrefParser::new().parse(input).unwrap();
+        assert_eq!(citations.len(), 1);
+        let citation = &citations[0];
+
+        assert_eq!(
+            citation.title,
+            "Effects of aspirin on platelets: A randomized trial"
+        );
+        assert_eq!(citation.citation_type, vec!["journal-article"]);
+        assert_eq!(citation.authors.len(), 3);
+        assert_eq!(citation.authors[0].family_name, "Smith");
+        assert_eq!(citation.authors[0].given_name, "John A.");
+        assert_eq!(
+            citation.authors[0].orcid.as_deref(),
+            Some("0000-0002-1825-0097")
+        );
+        assert_eq!(
+            citation.authors[0].affiliation.as_deref(),
+            Some("University of Testing")
+        );
+        assert_eq!(citation.authors[2].family_name, "The Trial Group");
+        assert_eq!(citation.journal.as_deref(), Some("Journal of Tests"));
+        assert_eq!(citation.journal_abbr.as_deref(), Some("J Tests"));
+        assert_eq!(citation.year, Some(2019));
+        assert_eq!(citation.volume.as_deref(), Some("12"));
+        assert_eq!(citation.issue.as_deref(), Some("3"));
+        assert_eq!(citation.pages.as_deref(), Some("101-109"));
+        assert_eq!(citation.doi.as_deref(), Some("10.1000/example.123"));
+        assert_eq!(citation.issn, vec!["1234-5678", "8765-4321"]);
+        assert_eq!(
+            citation.abstract_text.as_deref(),
+            Some("Aspirin reduced platelet aggregation.")
+        );
+        assert_eq!(citation.keywords, vec!["Hematology"]);
+        assert_eq!(citation.publisher.as_deref(), Some("Test Publisher"));
+        assert_eq!(citation.cited_by_count, Some(42));
+        assert_eq!(citation.reference_count, Some(30));
+        assert_eq!(
+            citation.grants,
+            vec![Grant {
+                id: Some("R01-123".to_string()),
+                agency: Some("National Institutes of Health".to_string()),
+                country: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_items() {
+        let input = r#"{"status": "ok", "message-type": "work-list", "message": {"items": [
+            {"DOI": "10.1000/a", "title": ["First"], "published-online": {"date-parts": [[2021]]}},
+            {"DOI": "10.1000/untitled"},
+            {"DOI": "10.1000/b", "title": ["Second"], "ISBN": ["978-0-306-40615-7"],
+             "editor": [{"given": "Jane", "family": "Doe"}]}
+        ]}}"#;
+
+        let parser = CrossrefParser::new();
+        assert!(parser.can_parse(input));
+        let citations = parser.parse(input).unwrap();
+        let titles: Vec<_> = citations.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, ["First", "Second"]);
+        assert_eq!(citations[0].year, Some(2021));
+        assert_eq!(citations[1].isbn, vec!["978-0-306-40615-7"]);
+        assert_eq!(citations[1].authors[0].role, Some(AuthorRole::Editor));
+
+        let limited = CrossrefParser::new().with_limit(1).parse(input).unwrap();
+        assert_eq!(limited.len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let parser = CrossrefParser::new();
+        assert!(parser.parse("{not json").is_err());
+        assert!(parser.parse(r#"{"message": {"items": []}}"#).is_err());
+        assert!(!parser.can_parse("TY  - JOUR\nER  -"));
+    }
+}
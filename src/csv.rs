@@ -22,7 +22,10 @@ use csv::{ReaderBuilder, StringRecord};
 use nanoid::nanoid;
 use std::collections::HashMap;
 
-use crate::utils::{format_doi, format_page_numbers, parse_author_name, split_issns};
+use crate::utils::{
+    clean_input, extract_year, format_page_numbers, normalize_arxiv_id, parse_author_name,
+    split_serial_numbers, ParserOptions, WarningHandler,
+};
 use crate::{Author, Citation, CitationError, CitationParser, Result};
 
 /// Default header mappings for common CSV column names
@@ -44,11 +47,67 @@ const DEFAULT_HEADERS: &[(&str, &[&str])] = &[
     ("issn", &["issn", "isbn"]),
     ("language", &["language", "lang"]),
     ("publisher", &["publisher"]),
+    (
+        "place",
+        &[
+            "place",
+            "place published",
+            "location",
+            "conference location",
+        ],
+    ),
+    ("conference_name", &["conference", "conference name"]),
+    ("series", &["series", "series title"]),
     ("url", &["url", "link", "web link"]),
+    (
+        "accession_number",
+        &[
+            "accession number",
+            "accession",
+            "an",
+            "eid",
+            "ut (unique wos id)",
+        ],
+    ),
+    ("arxiv_id", &["arxiv", "arxiv id", "eprint"]),
+    (
+        "cited_by_count",
+        &[
+            "cited by",
+            "times cited",
+            "times cited, wos core",
+            "tc",
+            "is-referenced-by-count",
+        ],
+    ),
+    (
+        "reference_count",
+        &[
+            "reference count",
+            "cited reference count",
+            "nr",
+            "references-count",
+        ],
+    ),
     ("label", &["label"]),
     ("duplicate_id", &["duplicateid", "duplicate_id"]),
 ];
 
+/// Header mappings added for Dimensions exports, on top of the defaults
+const DIMENSIONS_HEADERS: &[(&str, &[&str])] = &[
+    ("id", &["publication id"]),
+    ("year", &["pubyear"]),
+    ("pages", &["pagination"]),
+    ("pmid", &["pmid"]),
+    ("pmc_id", &["pmcid"]),
+    ("mesh_terms", &["mesh terms"]),
+    ("citation_type", &["publication type"]),
+    ("url", &["dimensions url"]),
+];
+
+/// First line of Dimensions exports, before the header row
+const DIMENSIONS_PREAMBLE: &str = "About the data:";
+
 /// Configuration for CSV parsing with custom header mappings.
 ///
 /// Allows customization of how CSV columns are mapped to citation fields,
@@ -60,7 +119,7 @@ const DEFAULT_HEADERS: &[(&str, &[&str])] = &[
 /// - "title" → ["title", "article title", "publication title"]
 /// - "authors" → ["author", "authors", "creator", "creators"]
 /// - "year" → ["year", "publication year", "pub year"]
-/// etc.
+/// - etc.
 ///
 /// # Examples
 ///
@@ -79,6 +138,8 @@ pub struct CsvConfig {
     delimiter: u8,
     /// Whether the CSV has headers
     has_header: bool,
+    /// Start of a descriptive line skipped when it precedes the header row
+    preamble: Option<&'static str>,
 }
 
 impl CsvConfig {
@@ -89,11 +150,41 @@ impl CsvConfig {
             header_map: HashMap::new(),
             delimiter: b',',
             has_header: true,
+            preamble: None,
         };
         config.set_default_headers();
         config
     }
 
+    /// Creates a configuration for CSV exports from Dimensions.
+    ///
+    /// Maps columns such as "Publication ID", "PubYear", "Pagination", "PMID",
+    /// "PMCID", "MeSH terms" and "Publication Type" in addition to the default
+    /// mappings, and skips the "About the data" line that precedes the header.
+    /// Authors are read from the "Authors" column as
+    /// `"Surname, Firstname; Surname, Firstname"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::csv::{CsvConfig, CsvParser};
+    ///
+    /// let parser = CsvParser::new().with_config(CsvConfig::dimensions());
+    /// ```
+    #[must_use]
+    pub fn dimensions() -> Self {
+        let mut config = Self::new();
+        for (field, aliases) in DIMENSIONS_HEADERS {
+            config
+                .header_map
+                .entry(field.to_string())
+                .or_default()
+                .extend(aliases.iter().map(|s| s.to_string()));
+        }
+        config.preamble = Some(DIMENSIONS_PREAMBLE);
+        config
+    }
+
     /// Sets the default header mappings
     fn set_default_headers(&mut self) {
         for (field, aliases) in DEFAULT_HEADERS {
@@ -123,15 +214,35 @@ impl CsvConfig {
     }
 
     /// Finds the field name for a given header
-    fn get_field_for_header(&self, header: &str) -> Option<String> {
-        let header_lower = header.to_lowercase();
+    ///
+    /// Headers are compared to the aliases ignoring case, or, when `fuzzy` is
+    /// set, after both are reduced to lowercase letters and digits.
+    fn get_field_for_header(&self, header: &str, fuzzy: bool) -> Option<String> {
+        let normalize = |text: &str| {
+            if fuzzy {
+                normalize_header(text)
+            } else {
+                text.to_lowercase()
+            }
+        };
+        let header = normalize(header);
         self.header_map
             .iter()
-            .find(|(_, aliases)| aliases.iter().any(|a| a.to_lowercase() == header_lower))
+            .find(|(_, aliases)| aliases.iter().any(|a| normalize(a) == header))
             .map(|(field, _)| field.clone())
     }
 }
 
+/// Reduces a header to its lowercase letters and digits, so that
+/// `"Publication_Year"` and `"publication year"` compare equal
+fn normalize_header(header: &str) -> String {
+    header
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
 /// Parser for CSV-formatted citation data with configurable mappings.
 ///
 /// Provides flexible parsing of CSV files containing citation data, with support
@@ -167,7 +278,27 @@ impl CsvConfig {
 #[derive(Debug, Clone)]
 pub struct CsvParser {
     config: CsvConfig,
-    source: Option<String>,
+    options: ParserOptions,
+    id_policy: IdPolicy,
+    fuzzy_headers: bool,
+}
+
+/// How values of the `id` column become citation ids.
+///
+/// Exports often number their rows from 1, so ids from several files collide
+/// when the citations are combined. Citations without an id always get a
+/// generated one.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum IdPolicy {
+    /// Use the id as it appears in the input
+    #[default]
+    Keep,
+    /// Prefix the id with the parser's source, e.g. `"pubmed:1"` for the
+    /// source `"pubmed"`. Ids are kept as they are when no source is set.
+    Namespace,
+    /// Always generate a new unique id, keeping the input's id in
+    /// `extra_fields["id"]`
+    Regenerate,
 }
 
 impl Default for CsvParser {
@@ -182,10 +313,35 @@ impl CsvParser {
     pub fn new() -> Self {
         Self {
             config: CsvConfig::new(),
-            source: None,
+            options: ParserOptions::default(),
+            id_policy: IdPolicy::default(),
+            fuzzy_headers: true,
         }
     }
 
+    /// Creates a CSV parser for Dimensions exports.
+    ///
+    /// See [`CsvConfig::dimensions`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{CitationParser, CsvParser};
+    ///
+    /// let input = "\
+    /// \"About the data: Exported on Jan 01, 2024.\"
+    /// Publication ID,DOI,Title,Source title,PubYear,Authors
+    /// pub.1000001,10.1000/xyz,Example Title,Journal of Tests,2023,\"Smith, John; Doe, Jane\"";
+    ///
+    /// let citations = CsvParser::dimensions().parse(input).unwrap();
+    /// assert_eq!(citations[0].id, "pub.1000001");
+    /// assert_eq!(citations[0].authors[1].family_name, "Doe");
+    /// ```
+    #[must_use]
+    pub fn dimensions() -> Self {
+        Self::new().with_config(CsvConfig::dimensions())
+    }
+
     /// Creates a new CSV parser with custom configuration
     #[must_use]
     pub fn with_config(mut self, config: CsvConfig) -> Self {
@@ -193,23 +349,156 @@ impl CsvParser {
         self
     }
 
+    /// Sets the source of the parsed citations.
+    ///
+    /// Each citation's record is also named under
+    /// [`SOURCE_RECORD_KEY`](crate::SOURCE_RECORD_KEY).
     #[must_use]
     pub fn with_source(mut self, source: &str) -> Self {
-        self.source = Some(source.to_string());
+        self.options.source = Some(source.to_string());
+        self
+    }
+
+    /// Stops parsing once `limit` citations have been read.
+    ///
+    /// Useful for previewing large inputs. A limit of `0` returns no citations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::CsvParser;
+    /// let parser = CsvParser::new().with_limit(10);
+    /// ```
+    #[must_use]
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.options.limit = Some(limit);
+        self
+    }
+
+    /// Sets how values of the `id` column become citation ids.
+    ///
+    /// Defaults to [`IdPolicy::Keep`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::csv::{CsvParser, IdPolicy};
+    /// use biblib::CitationParser;
+    ///
+    /// let input = "ID,Title\n1,Example Title";
+    /// let citations = CsvParser::new()
+    ///     .with_source("pubmed")
+    ///     .with_id_policy(IdPolicy::Namespace)
+    ///     .parse(input)
+    ///     .unwrap();
+    /// assert_eq!(citations[0].id, "pubmed:1");
+    /// ```
+    #[must_use]
+    pub fn with_id_policy(mut self, policy: IdPolicy) -> Self {
+        self.id_policy = policy;
+        self
+    }
+
+    /// Sets the longest field, in bytes, kept from the input.
+    ///
+    /// Longer fields are truncated at a character boundary, as they are read,
+    /// and a warning is reported through [`CsvParser::with_warning_handler`].
+    /// Defaults to [`DEFAULT_MAX_FIELD_LENGTH`](crate::DEFAULT_MAX_FIELD_LENGTH).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::CsvParser;
+    /// let parser = CsvParser::new().with_max_field_length(64 * 1024);
+    /// ```
+    #[must_use]
+    pub fn with_max_field_length(mut self, max: usize) -> Self {
+        self.options.max_field_length = Some(max);
+        self
+    }
+
+    /// Sets the function receiving warnings, which are ignored otherwise.
+    ///
+    /// Warnings are reported when input is altered, for example when a field
+    /// exceeds the [maximum length](CsvParser::with_max_field_length).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::CsvParser;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let warnings = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = Arc::clone(&warnings);
+    /// let parser = CsvParser::new()
+    ///     .with_warning_handler(move |message| sink.lock().unwrap().push(message.to_string()));
+    /// ```
+    #[must_use]
+    pub fn with_warning_handler(mut self, handler: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.options.warning_handler = WarningHandler::new(handler);
+        self
+    }
+
+    /// Sets whether whitespace is normalized in every field.
+    ///
+    /// When enabled, runs of whitespace, including non-breaking spaces and
+    /// line breaks, become a single space and zero-width characters are
+    /// removed, as by
+    /// [`normalize_whitespace`](crate::transform::normalize_whitespace). Off by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::CsvParser;
+    /// let parser = CsvParser::new().with_whitespace_normalization(true);
+    /// ```
+    #[must_use]
+    pub fn with_whitespace_normalization(mut self, enabled: bool) -> Self {
+        self.options.normalize_whitespace = enabled;
+        self
+    }
+
+    /// Sets whether headers match their aliases after normalization.
+    ///
+    /// When enabled, the default, case, whitespace and punctuation are ignored,
+    /// so `"Publication_Year"`, `"publication year"` and `"PUBLICATION-YEAR"`
+    /// all match the alias `"publication year"`. When disabled, headers must
+    /// equal an alias apart from case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{CitationParser, CsvParser};
+    ///
+    /// let input = "Article_Title,Publication-Year\nExample Paper,2023";
+    /// let citations = CsvParser::new().parse(input).unwrap();
+    /// assert_eq!(citations[0].year, Some(2023));
+    ///
+    /// let strict = CsvParser::new().with_fuzzy_headers(false);
+    /// assert_eq!(strict.parse(input).unwrap()[0].year, None);
+    /// ```
+    #[must_use]
+    pub fn with_fuzzy_headers(mut self, fuzzy: bool) -> Self {
+        self.fuzzy_headers = fuzzy;
         self
     }
 
     /// Parses a record into a Citation using the current header mapping
     fn parse_record(&self, headers: &[String], record: StringRecord) -> Result<Citation> {
-        let mut citation = Citation::default();
-        citation.source = self.source.clone(); // Add source if provided
+        let mut citation = Citation {
+            source: self.options.source.clone(), // Add source if provided
+            ..Default::default()
+        };
         let mut has_id = false;
 
         for (i, value) in record.iter().enumerate() {
             if i >= headers.len() {
                 break;
             }
-            if let Some(field) = self.config.get_field_for_header(&headers[i]) {
+            if let Some(field) = self
+                .config
+                .get_field_for_header(&headers[i], self.fuzzy_headers)
+            {
                 match field.as_str() {
                     "id" => {
                         if !value.is_empty() {
@@ -218,26 +507,19 @@ impl CsvParser {
                         }
                     }
                     "title" => citation.title = value.to_string(),
-                    "authors" => {
-                        for author_str in value.split(';') {
-                            let (family, given) = parse_author_name(author_str);
-                            citation.authors.push(Author {
-                                family_name: family,
-                                given_name: given,
-                                affiliation: None,
-                            });
-                        }
-                    }
+                    "authors" => citation.authors.extend(parse_authors(value)),
                     "journal" => citation.journal = Some(value.to_string()),
                     "year" => {
-                        if let Ok(year) = value.parse() {
+                        if let Some(year) = extract_year(value) {
                             citation.year = Some(year);
                         }
                     }
                     "volume" => citation.volume = Some(value.to_string()),
                     "issue" => citation.issue = Some(value.to_string()),
                     "pages" => citation.pages = Some(format_page_numbers(value)),
-                    "doi" => citation.doi = format_doi(value),
+                    "doi" => value
+                        .split(|c: char| c.is_whitespace() || c == ';')
+                        .for_each(|doi| citation.add_doi(doi)),
                     "abstract" => citation.abstract_text = Some(value.to_string()),
                     "keywords" => {
                         citation.keywords.extend(
@@ -249,11 +531,40 @@ impl CsvParser {
                         );
                     }
                     "issn" => {
-                        citation.issn.extend(split_issns(value));
+                        let (issns, isbns) = split_serial_numbers(value);
+                        citation.issn.extend(issns);
+                        citation.isbn.extend(isbns);
                     }
                     "language" => citation.language = Some(value.to_string()),
                     "publisher" => citation.publisher = Some(value.to_string()),
+                    "place" => citation.place = Some(value.to_string()),
+                    "conference_name" => citation.conference_name = Some(value.to_string()),
+                    "series" => citation.series = Some(value.to_string()),
                     "url" => citation.urls.push(value.to_string()),
+                    "cited_by_count" => {
+                        citation.cited_by_count = citation.cited_by_count.or(parse_count(value))
+                    }
+                    "reference_count" => {
+                        citation.reference_count = citation.reference_count.or(parse_count(value))
+                    }
+                    "accession_number" if !value.is_empty() => {
+                        citation.accession_number = Some(value.to_string())
+                    }
+                    "pmid" if !value.is_empty() => citation.pmid = Some(value.to_string()),
+                    "pmc_id" if !value.is_empty() => citation.pmc_id = Some(value.to_string()),
+                    "arxiv_id" => citation.arxiv_id = normalize_arxiv_id(value),
+                    "mesh_terms" | "citation_type" => {
+                        let values = value
+                            .split(';')
+                            .map(str::trim)
+                            .filter(|s| !s.is_empty())
+                            .map(String::from);
+                        if field == "mesh_terms" {
+                            citation.mesh_terms.extend(values);
+                        } else {
+                            citation.citation_type.extend(values);
+                        }
+                    }
                     _ => {
                         citation
                             .extra_fields
@@ -267,14 +578,73 @@ impl CsvParser {
 
         if !has_id {
             citation.id = nanoid!();
+        } else {
+            match (self.id_policy, &self.options.source) {
+                (IdPolicy::Keep, _) | (IdPolicy::Namespace, None) => {}
+                (IdPolicy::Namespace, Some(source)) => {
+                    citation.id = format!("{}:{}", source, citation.id);
+                }
+                (IdPolicy::Regenerate, _) => {
+                    let id = std::mem::replace(&mut citation.id, nanoid!());
+                    citation
+                        .extra_fields
+                        .entry("id".to_string())
+                        .or_default()
+                        .push(id);
+                }
+            }
         }
 
         Ok(citation)
     }
+
+    /// Skips the configured preamble line, if the input starts with it
+    fn strip_preamble<'a>(&self, input: &'a str) -> &'a str {
+        match self.config.preamble {
+            Some(preamble) if input.trim_start_matches('"').starts_with(preamble) => {
+                input.split_once('\n').map_or("", |(_, rest)| rest)
+            }
+            _ => input,
+        }
+    }
+}
+
+/// Parses a count such as `"1,024"`, returning `None` if it is not a number
+/// Parses an author cell such as `"Smith, John; Doe, Jane"`
+///
+/// Authors are separated by `;`, so a comma within a name always separates the
+/// family name from the given names, and any later comma stays in the given
+/// names, as in `"King, Martin Luther, Jr."`. The field delimiter itself is
+/// handled by the CSV reader, which requires such cells to be quoted.
+fn parse_authors(value: &str) -> impl Iterator<Item = Author> + '_ {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            let (family, given) = match name.split_once(',') {
+                Some((family, given)) => (family.trim().to_string(), given.trim().to_string()),
+                None => parse_author_name(name),
+            };
+            Author {
+                family_name: family,
+                given_name: given,
+                affiliation: None,
+                orcid: None,
+                role: None,
+            }
+        })
+}
+
+fn parse_count(value: &str) -> Option<u32> {
+    value.trim().replace([',', ' '], "").parse().ok()
 }
 
 impl CitationParser for CsvParser {
     fn parse(&self, input: &str) -> Result<Vec<Citation>> {
+        let input: &str = &clean_input(input);
+        let input = self.strip_preamble(input);
+
         let mut reader = ReaderBuilder::new()
             .delimiter(self.config.delimiter)
             .has_headers(self.config.has_header)
@@ -297,14 +667,39 @@ impl CitationParser for CsvParser {
                 .collect()
         };
 
+        let limit = self.options.limit();
         let mut citations = Vec::new();
-        for result in reader.records() {
+        for result in reader.records().take(limit) {
             let record = result.map_err(|e| CitationError::InvalidFormat(e.to_string()))?;
             citations.push(self.parse_record(&headers, record)?);
         }
 
+        self.options.finish(&mut citations);
         Ok(citations)
     }
+
+    fn format_name(&self) -> &'static str {
+        "CSV"
+    }
+
+    fn can_parse(&self, input: &str) -> bool {
+        let input: &str = &clean_input(input);
+        if !self.config.has_header {
+            return false;
+        }
+        let mut reader = ReaderBuilder::new()
+            .delimiter(self.config.delimiter)
+            .from_reader(self.strip_preamble(input).as_bytes());
+        reader.headers().is_ok_and(|headers| {
+            headers.len() > 1
+                && headers.iter().any(|header| {
+                    self.config
+                        .get_field_for_header(header, self.fuzzy_headers)
+                        .as_deref()
+                        == Some("title")
+                })
+        })
+    }
 }
 
 #[cfg(test)]
@@ -350,6 +745,26 @@ Test Paper,Smith J,2023,Test Journal";
         assert_eq!(citations[0].journal, Some("Test Journal".to_string()));
     }
 
+    #[test]
+    fn test_fuzzy_headers() {
+        let input = "\
+ TITLE ,Author(s),Publication_Year,Source-Title,Extra Column
+Test Paper,Smith J,2023,Test Journal,kept";
+
+        let citations = CsvParser::new().parse(input).unwrap();
+        assert_eq!(citations[0].title, "Test Paper");
+        assert_eq!(citations[0].authors[0].family_name, "Smith");
+        assert_eq!(citations[0].year, Some(2023));
+        assert_eq!(citations[0].journal.as_deref(), Some("Test Journal"));
+        assert!(CsvParser::new().can_parse(input));
+
+        let parser = CsvParser::new().with_fuzzy_headers(false);
+        let citations = parser.parse(input).unwrap();
+        assert!(citations[0].title.is_empty());
+        assert_eq!(citations[0].year, None);
+        assert!(!parser.can_parse(input));
+    }
+
     #[test]
     fn test_multiple_authors() {
         let input = "\
@@ -364,6 +779,61 @@ Test Paper,\"Smith, John; Doe, Jane\",2023";
         assert_eq!(citations[0].authors[1].family_name, "Doe");
     }
 
+    #[test]
+    fn test_commas_within_author_names() {
+        let input = "\
+Title,Authors,Year
+Test Paper,\"Smith, John; Doe, Jane\",2023
+Second Paper,\"King, Martin Luther, Jr.;  ; Lee K;\",2024";
+
+        let citations = CsvParser::new().parse(input).unwrap();
+        assert_eq!(citations[0].year, Some(2023));
+        let names: Vec<_> = citations[0]
+            .authors
+            .iter()
+            .map(|a| (a.family_name.as_str(), a.given_name.as_str()))
+            .collect();
+        assert_eq!(names, vec![("Smith", "John"), ("Doe", "Jane")]);
+
+        let names: Vec<_> = citations[1]
+            .authors
+            .iter()
+            .map(|a| (a.family_name.as_str(), a.given_name.as_str()))
+            .collect();
+        assert_eq!(names, vec![("King", "Martin Luther, Jr."), ("Lee", "K")]);
+    }
+
+    #[test]
+    fn test_id_policies() {
+        let input = "ID,Title\n1,First\n,Second";
+        let ids = |parser: CsvParser| -> Vec<String> {
+            parser
+                .parse(input)
+                .unwrap()
+                .into_iter()
+                .map(|c| c.id)
+                .collect()
+        };
+
+        let kept = ids(CsvParser::new().with_source("embase"));
+        assert_eq!(kept[0], "1");
+        assert!(!kept[1].is_empty());
+
+        let namespaced = ids(CsvParser::new()
+            .with_source("embase")
+            .with_id_policy(IdPolicy::Namespace));
+        assert_eq!(namespaced[0], "embase:1");
+        assert!(!namespaced[1].starts_with("embase:"));
+        let without_source = ids(CsvParser::new().with_id_policy(IdPolicy::Namespace));
+        assert_eq!(without_source[0], "1");
+
+        let parser = CsvParser::new().with_id_policy(IdPolicy::Regenerate);
+        let citations = parser.parse(input).unwrap();
+        assert_ne!(citations[0].id, "1");
+        assert_eq!(citations[0].extra_first("id"), Some("1"));
+        assert_ne!(parser.parse(input).unwrap()[0].id, citations[0].id);
+    }
+
     #[test]
     fn test_custom_delimiter() {
         let input = "Title;Author;Year\nTest Paper;Smith J;2023";
@@ -378,4 +848,67 @@ Test Paper,\"Smith, John; Doe, Jane\",2023";
         assert_eq!(citations[0].authors[0].family_name, "Smith");
         assert_eq!(citations[0].year, Some(2023));
     }
+
+    #[test]
+    fn test_with_limit() {
+        let input = "Title,Year\nFirst,2020\nSecond,2021\nThird,2022";
+        let citations = CsvParser::new().with_limit(2).parse(input).unwrap();
+        assert_eq!(citations.len(), 2);
+        assert_eq!(citations[1].title, "Second");
+    }
+
+    #[test]
+    fn test_dimensions_preset() {
+        let input = "\
+\"About the data: Exported on Jan 01, 2024. Criteria: sleep.\"
+Rank,Publication ID,DOI,PMID,PMCID,Title,Abstract,Source title,PubYear,Volume,Issue,Pagination,Publication Type,Authors,MeSH terms,Dimensions URL
+1,pub.1000001,10.1000/xyz,12345,PMC678,Sleep and exercise,An abstract.,Journal of Tests,2023,4,2,10-20,Article,\"Smith, John; Doe, Jane A\",Sleep; Exercise,https://app.dimensions.ai/details/publication/pub.1000001
+";
+
+        let citations = CsvParser::dimensions().parse(input).unwrap();
+        assert_eq!(citations.len(), 1);
+        let citation = &citations[0];
+        assert_eq!(citation.id, "pub.1000001");
+        assert_eq!(citation.doi.as_deref(), Some("10.1000/xyz"));
+        assert_eq!(citation.pmid.as_deref(), Some("12345"));
+        assert_eq!(citation.pmc_id.as_deref(), Some("PMC678"));
+        assert_eq!(citation.title, "Sleep and exercise");
+        assert_eq!(citation.journal.as_deref(), Some("Journal of Tests"));
+        assert_eq!(citation.year, Some(2023));
+        assert_eq!(citation.pages.as_deref(), Some("10-20"));
+        assert_eq!(citation.citation_type, vec!["Article"]);
+        assert_eq!(citation.mesh_terms, vec!["Sleep", "Exercise"]);
+        assert_eq!(citation.authors.len(), 2);
+        assert_eq!(citation.authors[0].family_name, "Smith");
+        assert_eq!(citation.authors[0].given_name, "John");
+        assert_eq!(citation.authors[1].family_name, "Doe");
+        assert_eq!(citation.urls.len(), 1);
+
+        // Exports without the preamble line parse the same way
+        let without_preamble = input.split_once('\n').unwrap().1;
+        let citations = CsvParser::dimensions().parse(without_preamble).unwrap();
+        assert_eq!(citations[0].id, "pub.1000001");
+    }
+
+    #[test]
+    fn test_citation_counts() {
+        let input = "\
+Title,Cited by,Cited Reference Count
+Counted Paper,\"1,024\",35
+Uncounted Paper,,n/a";
+
+        let citations = CsvParser::new().parse(input).unwrap();
+        assert_eq!(citations[0].cited_by_count, Some(1024));
+        assert_eq!(citations[0].reference_count, Some(35));
+        assert_eq!(citations[1].cited_by_count, None);
+        assert_eq!(citations[1].reference_count, None);
+    }
+
+    #[test]
+    fn test_can_parse() {
+        let csv = "Title,Authors,Year\nExample,\"Smith, J\",2023\n";
+        assert!(CsvParser::new().can_parse(csv));
+        assert!(!CsvParser::new().can_parse("TY  - JOUR\nTI  - Title\nER  -\n"));
+        assert_eq!(CsvParser::new().format_name(), "CSV");
+    }
 }
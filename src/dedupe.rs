@@ -13,6 +13,7 @@
 //! - Parallel processing support
 //! - Unicode character handling
 //! - Configurable matching thresholds
+//! - Pluggable string similarity, see [`Similarity`]
 //!
 //! ## Usage
 //!
@@ -29,6 +30,8 @@
 //!                 family_name: "Smith".to_string(),
 //!                 given_name: "John".to_string(),
 //!                 affiliation: None,
+//!                 orcid: None,
+//!                 role: None,
 //!             }
 //!         ],
 //!         doi: Some("10.1234/ml.2023.001".to_string()),
@@ -44,6 +47,8 @@
 //!                 family_name: "Smith".to_string(),
 //!                 given_name: "John".to_string(),
 //!                 affiliation: None,
+//!                 orcid: None,
+//!                 role: None,
 //!             }
 //!         ],
 //!         doi: Some("10.1234/ml.2023.001".to_string()),
@@ -78,33 +83,74 @@
 //!     group_by_year: false,     // Disable year-based grouping
 //!     run_in_parallel: true,    // Enable parallel processing
 //!     source_preferences: vec!["PubMed".to_string(), "CrossRef".to_string()],
+//!     ..Default::default()
 //! };
 //!
 //! let deduplicator = Deduplicator::new().with_config(config);
 //! ```
 //!
+//! [`DeduplicatorConfig::conservative`] and [`DeduplicatorConfig::aggressive`]
+//! provide starting points that trade recall for precision and vice versa.
+//!
 //! ## Matching Criteria
 //!
 //! Citations are considered duplicates based on the following criteria:
 //!
+//! 0. The same arXiv ID, ignoring the version
+//!
 //! 1. With DOIs:
 //!    - Matching DOIs and high title similarity (≥ 0.85)
 //!    - Matching journal names or ISSNs
 //!
 //! 2. Without DOIs:
 //!    - Very high title similarity (≥ 0.93)
-//!    - Matching volume or page numbers
+//!    - Matching volume or first page, so `"123-130"` matches `"123-30"`
 //!    - Matching journal names or ISSNs
+//!    - Or a lower title similarity (≥ 0.80) when journal, volume and first
+//!      page all match
+//!
+//! The similarity thresholds above are the defaults and can be changed through
+//! [`DeduplicatorConfig`].
+//!
+//! A meeting abstract is never a duplicate of a full paper with the same title
+//! and authors, unless [`DeduplicatorConfig::respect_type_differences`] is
+//! disabled.
+//!
+//! Manual decisions set with [`Deduplicator::with_overrides`] take precedence over
+//! all of the above.
 
+use crate::utils::{first_page, WarningHandler};
 use crate::{Citation, DuplicateGroup};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::{mpsc, Arc};
 use strsim::jaro;
 use strsim::jaro_winkler;
+use strsim::levenshtein;
+use strsim::normalized_levenshtein;
 
 const DOI_TITLE_SIMILARITY_THRESHOLD: f64 = 0.85;
 const NO_DOI_TITLE_SIMILARITY_THRESHOLD: f64 = 0.93;
+const LOCATOR_TITLE_SIMILARITY_THRESHOLD: f64 = 0.80;
+const PARALLEL_THRESHOLD: usize = 1000;
+
+/// Leading articles ignored in titles by default: English, French, German,
+/// Spanish and Italian
+pub const DEFAULT_LEADING_ARTICLES: [&str; 16] = [
+    "the", "a", "an", "le", "la", "les", "l'", "der", "die", "das", "el", "los", "las", "il", "lo",
+    "gli",
+];
+
+/// Shortest normalized abstract, in characters, that can match another under
+/// [`DeduplicatorConfig::match_identical_abstracts`]
+pub const MIN_IDENTICAL_ABSTRACT_LENGTH: usize = 100;
+
+/// Fewest citations for which [`DeduplicatorConfig::weight_title_tokens`] has
+/// an effect; token frequencies in smaller inputs say little about how common
+/// a word is
+pub const MIN_TITLE_WEIGHTING_CORPUS_SIZE: usize = 20;
 
 static UNICODE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"<U\+([0-9A-Fa-f]+)>").unwrap());
 
@@ -124,6 +170,65 @@ const HTML_REPLACEMENTS: [(&str, &str); 13] = [
     ("γ", "g"),
 ];
 
+/// Normalizes a family name for comparison.
+///
+/// Folds accents, lowercases and drops everything but letters and digits, so
+/// hyphens, spaces and apostrophes within compound names do not matter.
+/// Different compound names still differ, e.g. `"García-López"` and `"García"`.
+///
+/// # Examples
+///
+/// ```
+/// use biblib::dedupe::normalize_surname;
+///
+/// assert_eq!(normalize_surname("García-López"), "garcialopez");
+/// assert_eq!(normalize_surname("Garcia Lopez"), "garcialopez");
+/// assert_eq!(normalize_surname("GarciaLopez"), "garcialopez");
+/// assert_eq!(normalize_surname("O'Brien"), normalize_surname("OBrien"));
+/// ```
+#[must_use]
+pub fn normalize_surname(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    for c in name.chars().flat_map(char::to_lowercase) {
+        match fold_accent(c) {
+            Some(folded) => result.push_str(folded),
+            None if c.is_alphanumeric() => result.push(c),
+            None => {}
+        }
+    }
+    result
+}
+
+/// Returns the unaccented form of a lowercase Latin letter with a diacritic
+fn fold_accent(c: char) -> Option<&'static str> {
+    Some(match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'æ' => "ae",
+        'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => "e",
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => "g",
+        'ĥ' | 'ħ' => "h",
+        'ì' | 'í' | 'î' | 'ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => "i",
+        'ĵ' => "j",
+        'ķ' => "k",
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => "l",
+        'ñ' | 'ń' | 'ņ' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' | 'ŏ' | 'ő' => "o",
+        'œ' => "oe",
+        'ŕ' | 'ŗ' | 'ř' => "r",
+        'ś' | 'ŝ' | 'ş' | 'š' | 'ș' => "s",
+        'ß' => "ss",
+        'ţ' | 'ť' | 'ŧ' | 'ț' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => "u",
+        'ŵ' => "w",
+        'ý' | 'ÿ' | 'ŷ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    })
+}
+
 /// Configuration options for controlling the deduplication process.
 ///
 /// This struct allows fine-tuning of the deduplication algorithm's behavior
@@ -138,7 +243,8 @@ const HTML_REPLACEMENTS: [(&str, &str); 13] = [
 /// let config = DeduplicatorConfig {
 ///     group_by_year: true,    // Enable year-based grouping
 ///     run_in_parallel: true,  // Enable parallel processing
-///     source_preferences: vec!["PubMed".to_string(), "Google Scholar      ".to_string()],
+///     source_preferences: vec!["PubMed".to_string(), "Google Scholar".to_string()],
+///     ..Default::default()
 /// };
 /// ```
 ///
@@ -151,7 +257,7 @@ const HTML_REPLACEMENTS: [(&str, &str); 13] = [
 ///
 /// - When `group_by_year` is false, `run_in_parallel` is automatically disabled
 /// - Year grouping is recommended for datasets with > 1000 citations
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Clone)]
 pub struct DeduplicatorConfig {
     /// Whether to group citations by year before processing.
     /// This can significantly improve performance for large datasets.
@@ -159,9 +265,248 @@ pub struct DeduplicatorConfig {
     /// Whether to use parallel processing for year groups.
     /// Most effective when combined with `group_by_year = true`.
     pub run_in_parallel: bool,
+    /// Fewest citations for which `run_in_parallel` takes effect (default 1000).
+    ///
+    /// Smaller inputs are processed serially, since starting parallel work
+    /// costs more than it saves for them. Set to 0 to always run in parallel
+    /// when `run_in_parallel` is set.
+    pub parallel_threshold: usize,
     /// Ordered list of preferred sources for unique citations.
     /// First source in the list has highest priority.
     pub source_preferences: Vec<String>,
+    /// Map of journal abbreviations to full journal titles, e.g.
+    /// `"J Clin Oncol"` → `"Journal of Clinical Oncology"`.
+    /// Keys and values are compared after journal normalization.
+    pub journal_aliases: HashMap<String, String>,
+    /// Minimum Jaro title similarity for citations sharing a DOI (default 0.85).
+    pub doi_title_threshold: f64,
+    /// Minimum Jaro-Winkler title similarity for citations without DOIs (default 0.93).
+    pub title_threshold: f64,
+    /// Minimum Jaro-Winkler title similarity for citations without DOIs that
+    /// share journal, volume and first page (default 0.80).
+    ///
+    /// Journal, volume and first page locate a single article, so its records
+    /// match even when one title was truncated or translated. First pages are
+    /// compared after normalization, so `"123-130"`, `"123-30"` and `"123"`
+    /// agree, while electronic locators such as `"e123"` must be the same.
+    pub locator_title_threshold: f64,
+    /// Whether citations without DOIs may also match on title, year and authors
+    /// when volumes, pages or journals are missing or disagree (default `false`).
+    /// See `first_author_must_match` for how authors are compared.
+    pub match_on_authors: bool,
+    /// Whether authors only agree when the first authors have the same family
+    /// name (default `true`).
+    ///
+    /// When `false`, authors also agree when both citations list the same
+    /// family names in any order, e.g. alphabetized in one database and in
+    /// contribution order in another. Applies to `match_on_authors` and
+    /// `link_preprints`.
+    pub first_author_must_match: bool,
+    /// Whether titles are also compared without their subtitles, so that
+    /// `"Main title: one subtitle"` can match `"Main title"` (default `false`).
+    /// See [`Citation::title_parts`].
+    pub compare_main_titles: bool,
+    /// Weight of abstract similarity when deciding matches, from 0.0 to 1.0
+    /// (default 0.0, disabled).
+    ///
+    /// When positive, a match not confirmed by a shared DOI is only accepted if
+    /// `(1 - weight) * title_similarity + weight * abstract_similarity` still
+    /// reaches the title threshold. This keeps apart distinct studies with the
+    /// same generic title. Abstracts are only compared for such candidate
+    /// matches where both citations have one, so the cost stays low.
+    pub abstract_weight: f64,
+    /// Whether a preprint may be grouped with its published version even though
+    /// their DOIs differ (default `false`).
+    ///
+    /// A preprint (bioRxiv, medRxiv, arXiv, SSRN, ...) and a published citation
+    /// match when their titles reach `title_threshold`, their first authors
+    /// agree and their years are at most two apart. The published version is
+    /// preferred as the unique citation after `source_preferences`, and the ids
+    /// of the preprints are listed in [`DuplicateGroup::preprint_ids`]. Since
+    /// the years often differ, this works best with `group_by_year` disabled.
+    pub link_preprints: bool,
+    /// Most pairwise comparisons made within a block of citations (default
+    /// `None`, unlimited).
+    ///
+    /// A block is a year group when `group_by_year` is set, and otherwise the
+    /// whole input; a block of `n` citations needs `n * (n - 1) / 2`
+    /// comparisons. A block needing more than this only has its exact
+    /// duplicates and, with `match_identical_abstracts`, its identical
+    /// abstracts grouped, and a warning is reported through
+    /// [`Deduplicator::with_warning_handler`]. Manual overrides are not applied
+    /// within such a block.
+    pub max_pairwise: Option<usize>,
+    /// Whether correction notices are grouped with the work they correct
+    /// (default `false`).
+    ///
+    /// A citation is a correction when [`Citation::is_correction`] says so; its
+    /// [`Citation::corrected_title`] must reach `title_threshold` against the
+    /// other citation's title. The corrected work is always chosen as the unique
+    /// citation, and the ids of the corrections are listed in
+    /// [`DuplicateGroup::correction_ids`]. Errata often appear in a later year,
+    /// so this works best with `group_by_year` disabled.
+    ///
+    /// Whether or not this is enabled, a correction is never matched with a
+    /// citation that is not a correction by the usual rules.
+    pub link_corrections: bool,
+    /// Articles ignored at the start of titles, so that `"The effects of X"`
+    /// matches `"Effects of X"` (default [`DEFAULT_LEADING_ARTICLES`]).
+    ///
+    /// Compared case-insensitively with the first word of the title. Articles
+    /// ending in an apostrophe, such as `"l'"`, also match the start of a word,
+    /// as in `"L'effet"`. Use an empty list to compare titles as they are.
+    pub leading_articles: Vec<String>,
+    /// Whether [`Deduplicator::deduplicate`] records the duplicates each unique
+    /// citation absorbed (default `false`).
+    ///
+    /// When set, the ids of the removed duplicates are stored in
+    /// `extra_fields["merged_ids"]`, and their sources, in the same order, in
+    /// `extra_fields["merged_sources"]`, with an empty string for citations
+    /// without a source.
+    pub record_merged_ids: bool,
+    /// Whether first authors' family names are compared with
+    /// [`normalize_surname`] (default `true`).
+    ///
+    /// This lets `"García-López"`, `"Garcia Lopez"` and `"GarciaLopez"` agree.
+    /// As a guard against merging different people, names that only agree
+    /// after normalization also need the same given-name initial when both
+    /// authors have one. Also applies to the family names compared when
+    /// `first_author_must_match` is `false`.
+    pub normalize_surnames: bool,
+    /// Whether citations with the same abstract are always duplicates
+    /// (default `false`).
+    ///
+    /// Abstracts are compared after normalization, regardless of title
+    /// similarity, which catches records exported twice whose titles were
+    /// mangled differently. Citations are grouped by abstract before the
+    /// pairwise comparison, so this also applies to blocks beyond
+    /// `max_pairwise`. Abstracts shorter than
+    /// [`MIN_IDENTICAL_ABSTRACT_LENGTH`] once normalized, such as empty ones or
+    /// `"No abstract available"`, never match this way. Corrections are still
+    /// only matched as described in `link_corrections`.
+    pub match_identical_abstracts: bool,
+    /// Whether title similarity is scaled by how rare the shared title words
+    /// are across the input (default `false`).
+    ///
+    /// A first pass counts the citations whose title contains each word. A word
+    /// found in `df` of `n` titles weighs `ln(n / df) / ln(n)`: 1.0 for a word
+    /// in one title, 0.0 for a word in all of them. Title similarity is then
+    /// multiplied by the weight of the words the titles share divided by the
+    /// weight of all words of both titles, or by 1.0 when that is less. Words
+    /// one typo apart count as shared. Identical titles keep their similarity
+    /// even in a single-topic input where most words are common, and titles
+    /// differing in a rare word, such as a chapter number, lose it. Generic
+    /// titles such as `"Introduction"` or `"Editorial"`, made of a few words
+    /// common enough that their weights add up to little, no longer reach the
+    /// thresholds on their own. The input to [`Deduplicator::find_against`]
+    /// counts both lists. Has no effect on inputs smaller than
+    /// [`MIN_TITLE_WEIGHTING_CORPUS_SIZE`].
+    pub weight_title_tokens: bool,
+    /// Whether a meeting abstract and a full paper are kept apart even when
+    /// everything else matches (default `true`).
+    ///
+    /// Conferences often publish an abstract and later the full paper under
+    /// the same title and authors. A citation whose `citation_type` names an
+    /// abstract or poster, e.g. `"Meeting Abstract"` or RIS `ABST`, is then
+    /// never a duplicate of one typed as another kind of document, such as
+    /// `"Journal Article"`. Citations without a `citation_type` match either.
+    /// A shared DOI or accession number overrides the types, since databases
+    /// label the same record differently, e.g. Embase `"Conference Abstract"`
+    /// for what Scopus exports as `JOUR`. Manual overrides still group them.
+    pub respect_type_differences: bool,
+}
+
+impl Default for DeduplicatorConfig {
+    fn default() -> Self {
+        Self {
+            group_by_year: false,
+            run_in_parallel: false,
+            parallel_threshold: PARALLEL_THRESHOLD,
+            source_preferences: Vec::new(),
+            journal_aliases: HashMap::new(),
+            doi_title_threshold: DOI_TITLE_SIMILARITY_THRESHOLD,
+            title_threshold: NO_DOI_TITLE_SIMILARITY_THRESHOLD,
+            locator_title_threshold: LOCATOR_TITLE_SIMILARITY_THRESHOLD,
+            match_on_authors: false,
+            first_author_must_match: true,
+            compare_main_titles: false,
+            abstract_weight: 0.0,
+            link_preprints: false,
+            max_pairwise: None,
+            link_corrections: false,
+            leading_articles: DEFAULT_LEADING_ARTICLES
+                .iter()
+                .map(|article| article.to_string())
+                .collect(),
+            record_merged_ids: false,
+            normalize_surnames: true,
+            match_identical_abstracts: false,
+            weight_title_tokens: false,
+            respect_type_differences: true,
+        }
+    }
+}
+
+impl DeduplicatorConfig {
+    /// Returns a preset that favours precision over recall.
+    ///
+    /// Citations sharing a DOI need a title similarity of at least 0.90, and
+    /// citations without DOIs need at least 0.97 plus matching volume/pages and
+    /// journal. Few false merges, but duplicates with noisy titles or missing
+    /// metadata are more likely to be kept apart. Year grouping is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::dedupe::DeduplicatorConfig;
+    ///
+    /// let config = DeduplicatorConfig {
+    ///     run_in_parallel: true,
+    ///     ..DeduplicatorConfig::conservative()
+    /// };
+    /// assert_eq!(config.title_threshold, 0.97);
+    /// ```
+    #[must_use]
+    pub fn conservative() -> Self {
+        Self {
+            group_by_year: true,
+            doi_title_threshold: 0.90,
+            title_threshold: 0.97,
+            locator_title_threshold: 0.90,
+            ..Default::default()
+        }
+    }
+
+    /// Returns a preset that favours recall over precision.
+    ///
+    /// Citations sharing a DOI need a title similarity of at least 0.80, and
+    /// citations without DOIs need at least 0.88. Citations without DOIs also
+    /// match on title, year and first author alone, so records with missing
+    /// journal or pagination are merged. More duplicates are found, at the cost
+    /// of occasionally merging distinct works such as errata or conference and
+    /// journal versions, as `respect_type_differences` is disabled. Year
+    /// grouping is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::dedupe::DeduplicatorConfig;
+    ///
+    /// let config = DeduplicatorConfig::aggressive();
+    /// assert!(config.match_on_authors);
+    /// ```
+    #[must_use]
+    pub fn aggressive() -> Self {
+        Self {
+            group_by_year: true,
+            doi_title_threshold: 0.80,
+            title_threshold: 0.88,
+            locator_title_threshold: 0.75,
+            match_on_authors: true,
+            respect_type_differences: false,
+            ..Default::default()
+        }
+    }
 }
 
 /// Core deduplication engine for finding duplicate citations.
@@ -174,6 +519,9 @@ pub struct DeduplicatorConfig {
 ///
 /// Citations are considered duplicates based on these criteria:
 ///
+/// 0. **Same accession number** from the same source, or **same arXiv ID** in
+///    any version: always duplicates
+///
 /// 1. **With DOIs**:
 ///    - Matching DOIs and high title similarity (≥ 0.85)
 ///    - Matching journal names or ISSNs
@@ -182,6 +530,8 @@ pub struct DeduplicatorConfig {
 ///    - Very high title similarity (≥ 0.93)
 ///    - Matching volume/pages
 ///    - Matching journal names/ISSNs
+///    - Or a lower title similarity (≥ 0.80) with the same journal, volume and
+///      first page
 ///
 /// # Examples
 ///
@@ -195,6 +545,7 @@ pub struct DeduplicatorConfig {
 ///     group_by_year: true,
 ///     run_in_parallel: true,
 ///     source_preferences: vec!["PubMed".to_string(), "Embase".to_string()],
+///     ..Default::default()
 /// };
 /// let deduplicator = Deduplicator::new().with_config(config);
 /// ```
@@ -207,16 +558,279 @@ pub struct DeduplicatorConfig {
 #[derive(Debug, Default, Clone)]
 pub struct Deduplicator {
     config: DeduplicatorConfig,
+    overrides: HashMap<(CitationId, CitationId), Decision>,
+    warning_handler: WarningHandler,
+    similarity: SimilarityBackend,
+}
+
+/// A string similarity measure used to compare titles and abstracts.
+///
+/// Scores range from 0.0 for unrelated strings to 1.0 for identical ones, and
+/// are compared with the thresholds in [`DeduplicatorConfig`]. Strings are
+/// normalized before scoring: lowercased, with only letters and digits kept.
+/// Set one with [`Deduplicator::with_similarity`]; [`JaroWinkler`],
+/// [`Levenshtein`] and [`BigramCosine`] are provided.
+///
+/// # Examples
+///
+/// ```
+/// use biblib::dedupe::{Deduplicator, Similarity};
+///
+/// /// Only identical strings are similar
+/// struct Exact;
+///
+/// impl Similarity for Exact {
+///     fn score(&self, a: &str, b: &str) -> f64 {
+///         if a == b { 1.0 } else { 0.0 }
+///     }
+/// }
+///
+/// let deduplicator = Deduplicator::new().with_similarity(Exact);
+/// ```
+pub trait Similarity: Send + Sync {
+    /// Scores the similarity of two strings, from 0.0 to 1.0
+    fn score(&self, a: &str, b: &str) -> f64;
+}
+
+/// Jaro-Winkler similarity, which favours strings sharing a prefix.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JaroWinkler;
+
+impl Similarity for JaroWinkler {
+    fn score(&self, a: &str, b: &str) -> f64 {
+        jaro_winkler(a, b)
+    }
+}
+
+/// Levenshtein edit distance, as a similarity relative to the longer string.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Levenshtein;
+
+impl Similarity for Levenshtein {
+    fn score(&self, a: &str, b: &str) -> f64 {
+        normalized_levenshtein(a, b)
+    }
+}
+
+/// Cosine similarity of character bigram counts.
+///
+/// Insensitive to the order of words, so titles whose parts were rearranged
+/// still score high.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BigramCosine;
+
+impl Similarity for BigramCosine {
+    fn score(&self, a: &str, b: &str) -> f64 {
+        fn bigrams(text: &str) -> HashMap<(char, char), f64> {
+            let chars: Vec<char> = text.chars().collect();
+            let mut counts = HashMap::new();
+            for pair in chars.windows(2) {
+                *counts.entry((pair[0], pair[1])).or_insert(0.0) += 1.0;
+            }
+            counts
+        }
+
+        if a == b {
+            return 1.0;
+        }
+        let (a, b) = (bigrams(a), bigrams(b));
+        let dot: f64 = a
+            .iter()
+            .filter_map(|(bigram, count)| Some(count * b.get(bigram)?))
+            .sum();
+        let norm = |counts: &HashMap<(char, char), f64>| {
+            counts
+                .values()
+                .map(|count| count * count)
+                .sum::<f64>()
+                .sqrt()
+        };
+        let norms = norm(&a) * norm(&b);
+        if norms == 0.0 {
+            0.0
+        } else {
+            dot / norms
+        }
+    }
+}
+
+/// The similarity set by the user; Jaro and Jaro-Winkler if unset
+#[derive(Clone, Default)]
+struct SimilarityBackend(Option<Arc<dyn Similarity>>);
+
+impl SimilarityBackend {
+    /// Scores two strings with the user's similarity, or `default` if unset
+    fn score(&self, a: &str, b: &str, default: fn(&str, &str) -> f64) -> f64 {
+        match &self.0 {
+            Some(similarity) => similarity.score(a, b),
+            None => default(a, b),
+        }
+    }
+}
+
+impl fmt::Debug for SimilarityBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0.is_some() {
+            "SimilarityBackend(Some(..))"
+        } else {
+            "SimilarityBackend(None)"
+        })
+    }
+}
+
+/// Identifier of a citation, as found in [`Citation::id`].
+pub type CitationId = String;
+
+/// A manual decision about whether two citations are duplicates.
+///
+/// Used with [`Deduplicator::with_overrides`] to correct the matching algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// The two citations are always grouped together
+    Duplicate,
+    /// The two citations are never grouped together
+    NotDuplicate,
+}
+
+/// Result of deduplicating several labeled collections with
+/// [`Deduplicator::dedupe_sources`].
+#[derive(Debug, Clone, Default)]
+pub struct DedupeResult {
+    /// The unique citations, in input order
+    pub unique: Vec<Citation>,
+    /// The duplicate groups the unique citations were chosen from
+    pub groups: Vec<DuplicateGroup>,
+    /// Counts for each source, in the order the sources were given
+    pub sources: Vec<SourceSummary>,
+}
+
+impl DedupeResult {
+    /// Number of records across all sources, before deduplication
+    #[must_use]
+    pub fn total_records(&self) -> usize {
+        self.sources.iter().map(|s| s.records).sum()
+    }
+
+    /// Number of records removed as duplicates
+    #[must_use]
+    pub fn duplicates_removed(&self) -> usize {
+        self.sources.iter().map(|s| s.duplicates_removed).sum()
+    }
+}
+
+/// Per-source counts for a PRISMA flow diagram.
+///
+/// For every source, `records == kept + duplicates_removed`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceSummary {
+    /// Label of the source
+    pub source: String,
+    /// Records identified in the source
+    pub records: usize,
+    /// Records of the source kept as the unique citation of their group
+    pub kept: usize,
+    /// Records of the source removed as duplicates of another record
+    pub duplicates_removed: usize,
+    /// Works found only in this source, i.e. groups whose records all come from it
+    pub only_in_source: usize,
+}
+
+/// A pair of citations whose title similarity fell just short of the
+/// threshold, from [`Deduplicator::find_duplicates_with_nearmisses`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NearMiss {
+    /// Id of the citation appearing first in the input
+    pub first: CitationId,
+    /// Id of the other citation
+    pub second: CitationId,
+    /// Title similarity of the pair
+    pub score: f64,
+    /// Threshold the similarity was compared with: `doi_title_threshold` when
+    /// both citations have a DOI, and `title_threshold` otherwise
+    pub threshold: f64,
+}
+
+/// A duplicate group with the input positions of its citations
+#[derive(Debug)]
+struct PositionedGroup {
+    group: DuplicateGroup,
+    /// Input positions of the group's citations, in ascending order
+    positions: Vec<usize>,
+    /// Input position of the unique citation
+    unique: usize,
 }
 
 #[derive(Debug)]
 struct PreprocessedCitation<'a> {
     original: &'a Citation,
     normalized_title: String,
+    normalized_main_title: String,
+    normalized_abstract: Option<String>,
+    /// Hash of the normalized abstract, if long enough to match on its own
+    abstract_hash: Option<u64>,
     normalized_journal: Option<String>,
     normalized_journal_abbr: Option<String>,
     normalized_issn: Vec<String>,
     normalized_volume: String,
+    /// First page from [`first_page`]
+    first_page: Option<String>,
+    /// Normalized title of the corrected work, if the citation is a correction
+    normalized_corrected_title: Option<String>,
+    /// Normalized family names of all authors, sorted
+    normalized_family_names: Vec<String>,
+    /// Family name of the first author from [`normalize_surname`], if enabled
+    normalized_first_author: Option<String>,
+    /// Distinct title words, sorted, with their weights, if title words are
+    /// weighted
+    title_tokens: Option<Vec<(String, f64)>>,
+    /// Whether the citation is an abstract or a full paper, if types are
+    /// respected and it has one
+    document_part: Option<DocumentPart>,
+    /// arXiv ID without its version, lowercase
+    arxiv_id: Option<String>,
+}
+
+/// The part of a conference contribution or paper a citation describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum DocumentPart {
+    /// A meeting abstract or poster
+    Abstract,
+    /// Any other document, such as a journal article or conference paper
+    FullText,
+}
+
+impl DocumentPart {
+    /// Classifies a citation by its `citation_type`, if it has one
+    fn of(citation: &Citation) -> Option<Self> {
+        let mut types = citation
+            .citation_type
+            .iter()
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty())
+            .peekable();
+        types.peek()?;
+        if types.any(|t| t == "abst" || t.contains("abstract") || t.contains("poster")) {
+            Some(Self::Abstract)
+        } else {
+            Some(Self::FullText)
+        }
+    }
+}
+
+/// Number of titles containing each word, for weighting title words
+#[derive(Debug)]
+struct TitleTokenWeights {
+    document_frequency: HashMap<String, usize>,
+    corpus_size: usize,
+}
+
+impl TitleTokenWeights {
+    /// Weight of a word: 1.0 if in one title, 0.0 if in all of them
+    fn weight(&self, token: &str) -> f64 {
+        let frequency = self.document_frequency.get(token).copied().unwrap_or(1);
+        let corpus_size = self.corpus_size as f64;
+        ((corpus_size / frequency as f64).ln() / corpus_size.ln()).max(0.0)
+    }
 }
 
 /// Error types for dedupe operations
@@ -249,9 +863,11 @@ impl Deduplicator {
         Self {
             config: DeduplicatorConfig {
                 group_by_year: true,
-                run_in_parallel: false,
-                source_preferences: Vec::new(),
+                ..Default::default()
             },
+            overrides: HashMap::new(),
+            warning_handler: WarningHandler::default(),
+            similarity: SimilarityBackend::default(),
         }
     }
 
@@ -271,7 +887,8 @@ impl Deduplicator {
     /// let config = DeduplicatorConfig {
     ///     group_by_year: true,
     ///     run_in_parallel: true,
-    ///     source_preferences: vec!["PubMed".to_string(), "Google Scholar".to_string()],   
+    ///     source_preferences: vec!["PubMed".to_string(), "Google Scholar".to_string()],
+    ///     ..Default::default()
     /// };
     /// let deduplicator = Deduplicator::new().with_config(config);
     /// ```
@@ -285,6 +902,101 @@ impl Deduplicator {
         self
     }
 
+    /// Sets manual decisions for specific pairs of citations.
+    ///
+    /// Each entry names two citations by their `id` and forces them to be grouped
+    /// together ([`Decision::Duplicate`]) or kept apart ([`Decision::NotDuplicate`]),
+    /// regardless of their similarity. Pairs are unordered. If the same pair is
+    /// listed more than once, the last entry wins.
+    ///
+    /// # Notes
+    ///
+    /// - Citations are only compared within the same year group when `group_by_year`
+    ///   is enabled, so a [`Decision::Duplicate`] across different years has no effect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::dedupe::{Decision, Deduplicator};
+    ///
+    /// let deduplicator = Deduplicator::new().with_overrides(vec![
+    ///     ("1".to_string(), "2".to_string(), Decision::NotDuplicate),
+    ///     ("3".to_string(), "4".to_string(), Decision::Duplicate),
+    /// ]);
+    /// ```
+    #[must_use]
+    pub fn with_overrides(mut self, pairs: Vec<(CitationId, CitationId, Decision)>) -> Self {
+        for (a, b, decision) in pairs {
+            self.overrides.insert(Self::override_key(a, b), decision);
+        }
+        self
+    }
+
+    /// Sets the function receiving warnings, which are ignored otherwise.
+    ///
+    /// Warnings are reported when results are degraded, for example when a
+    /// block exceeds [`DeduplicatorConfig::max_pairwise`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::dedupe::{Deduplicator, DeduplicatorConfig};
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let warnings = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = Arc::clone(&warnings);
+    /// let deduplicator = Deduplicator::new()
+    ///     .with_config(DeduplicatorConfig {
+    ///         max_pairwise: Some(50_000_000),
+    ///         ..Default::default()
+    ///     })
+    ///     .with_warning_handler(move |message| sink.lock().unwrap().push(message.to_string()));
+    /// ```
+    #[must_use]
+    pub fn with_warning_handler(mut self, handler: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.warning_handler = WarningHandler::new(handler);
+        self
+    }
+
+    /// Sets the string similarity used to compare titles and abstracts.
+    ///
+    /// By default, titles of citations sharing a DOI are compared with Jaro
+    /// similarity and everything else with Jaro-Winkler. A custom similarity
+    /// replaces both, and is compared with the same thresholds, so these may
+    /// need tuning for it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::dedupe::{BigramCosine, Deduplicator};
+    ///
+    /// let deduplicator = Deduplicator::new().with_similarity(BigramCosine);
+    /// ```
+    #[must_use]
+    pub fn with_similarity(mut self, similarity: impl Similarity + 'static) -> Self {
+        self.similarity = SimilarityBackend(Some(Arc::new(similarity)));
+        self
+    }
+
+    /// Orders a pair of ids so that lookups do not depend on argument order
+    fn override_key(a: CitationId, b: CitationId) -> (CitationId, CitationId) {
+        if a <= b {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    }
+
+    /// Returns the manual decision for a pair of citations, if any
+    fn override_for(&self, a: &str, b: &str) -> Option<Decision> {
+        if self.overrides.is_empty() {
+            return None;
+        }
+        self.overrides
+            .get(&Self::override_key(a.to_string(), b.to_string()))
+            .copied()
+    }
+
     /// Processes a list of citations and returns groups of duplicates.
     ///
     /// This method analyzes the provided citations and groups them based on
@@ -298,7 +1010,9 @@ impl Deduplicator {
     /// # Returns
     ///
     /// Returns a vector of `DuplicateGroup`s, where each group contains
-    /// one unique citation and its identified duplicates.
+    /// one unique citation and its identified duplicates. Groups are ordered by
+    /// the input position of their earliest citation, so the result is the same
+    /// with and without `run_in_parallel`.
     ///
     /// # Examples
     ///
@@ -322,53 +1036,668 @@ impl Deduplicator {
         self,
         citations: &[Citation],
     ) -> Result<Vec<DuplicateGroup>, DedupeError> {
-        if citations.is_empty() {
-            return Ok(Vec::new());
-        }
-
-        if self.config.group_by_year {
-            let year_groups = Self::group_by_year(citations);
-            if self.config.run_in_parallel {
-                use rayon::prelude::*;
+        self.group_duplicates(citations)
+    }
 
-                let duplicate_groups: Result<Vec<_>, _> = year_groups
-                    .par_iter()
-                    .map(|(_, citations_in_year)| self.process_citation_group(citations_in_year))
-                    .collect();
+    /// Finds duplicate groups like [`Deduplicator::find_duplicates`], reporting
+    /// each group and the progress as groups are finalized.
+    ///
+    /// `on_group` is called with each group once it is complete, and then
+    /// `on_progress` with the number of citations grouped so far and the total.
+    /// The last progress report is always `(total, total)`. Both are called on
+    /// the calling thread, also with `run_in_parallel`, while the grouping runs
+    /// on another, so a UI can update a progress bar from them.
+    ///
+    /// Groups are reported in the order they are finalized, which depends on
+    /// year grouping and parallelism; the returned groups are in input order as
+    /// with `find_duplicates`.
+    ///
+    /// # Arguments
+    ///
+    /// * `citations` - A slice of Citation objects to be analyzed
+    /// * `on_group` - Called with each duplicate group as it is finalized
+    /// * `on_progress` - Called with the citations grouped so far and the total
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{dedupe::Deduplicator, Citation};
+    ///
+    /// let citation = |id: &str, title: &str| Citation {
+    ///     id: id.to_string(),
+    ///     title: title.to_string(),
+    ///     doi: Some(format!("10.1234/{title}")),
+    ///     journal: Some("Example Journal".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// let citations = [
+    ///     citation("1", "first"),
+    ///     citation("2", "first"),
+    ///     citation("3", "second"),
+    /// ];
+    ///
+    /// let mut sizes = Vec::new();
+    /// let mut progress = Vec::new();
+    /// let groups = Deduplicator::new()
+    ///     .find_duplicates_with_progress(
+    ///         &citations,
+    ///         |group| sizes.push(1 + group.duplicates.len()),
+    ///         |processed, total| progress.push((processed, total)),
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(groups.len(), 2);
+    /// assert_eq!(sizes, vec![2, 1]);
+    /// assert_eq!(progress, vec![(2, 3), (3, 3)]);
+    /// ```
+    pub fn find_duplicates_with_progress(
+        &self,
+        citations: &[Citation],
+        mut on_group: impl FnMut(&DuplicateGroup),
+        mut on_progress: impl FnMut(usize, usize),
+    ) -> Result<Vec<DuplicateGroup>, DedupeError> {
+        let total = citations.len();
+        let (sender, receiver) = mpsc::channel();
 
-                // Flatten results
-                Ok(duplicate_groups?.into_iter().flatten().collect())
-            } else {
-                let mut duplicate_groups = Vec::new();
+        let result = std::thread::scope(|scope| {
+            let worker = scope.spawn(move || {
+                self.group_duplicates_reporting(citations, &|group| {
+                    // The receiver outlives the worker, so sending cannot fail
+                    let _ = sender.send(group.clone());
+                })
+            });
 
-                for citations_in_year in year_groups.values() {
-                    duplicate_groups.extend(self.process_citation_group(citations_in_year)?);
+            let mut processed = 0;
+            for group in receiver {
+                processed += 1 + group.duplicates.len();
+                on_group(&group);
+                if processed < total {
+                    on_progress(processed, total);
                 }
-                Ok(duplicate_groups)
             }
-        } else {
-            let citations_refs: Vec<&Citation> = citations.iter().collect();
-            self.process_citation_group(&citations_refs)
+            worker
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        })?;
+
+        if total > 0 {
+            on_progress(total, total);
         }
+        Ok(result.into_iter().map(|group| group.group).collect())
     }
 
-    fn select_unique_citation<'a>(&self, citations: &[&'a Citation]) -> &'a Citation {
-        if citations.len() == 1 {
-            return citations[0];
-        }
+    /// Removes duplicates and returns the unique citations.
+    ///
+    /// Returns the citation chosen as unique for each group, in the order the
+    /// citations appear in the input. Use [`Deduplicator::find_duplicates`] to
+    /// also see which citations were removed as duplicates, or enable
+    /// [`DeduplicatorConfig::record_merged_ids`] to keep their ids.
+    ///
+    /// # Arguments
+    ///
+    /// * `citations` - A slice of Citation objects to be deduplicated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{dedupe::Deduplicator, Citation};
+    ///
+    /// let citation = Citation {
+    ///     id: "1".to_string(),
+    ///     title: "Example Title".to_string(),
+    ///     doi: Some("10.1234/example".to_string()),
+    ///     journal: Some("Example Journal".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// let duplicate = Citation {
+    ///     id: "2".to_string(),
+    ///     ..citation.clone()
+    /// };
+    ///
+    /// let unique = Deduplicator::new()
+    ///     .deduplicate(&[citation, duplicate])
+    ///     .unwrap();
+    /// assert_eq!(unique.len(), 1);
+    /// ```
+    pub fn deduplicate(&self, citations: &[Citation]) -> Result<Vec<Citation>, DedupeError> {
+        let groups = self.positioned_groups(citations)?;
+        Ok(self.unique_citations(groups))
+    }
 
-        // First try source preferences
-        if !self.config.source_preferences.is_empty() {
-            for preferred_source in &self.config.source_preferences {
-                if let Some(citation) = citations
-                    .iter()
-                    .find(|c| c.source.as_ref().map_or(false, |s| s == preferred_source))
-                {
-                    return citation;
+    /// Finds duplicates, and the pairs that almost matched.
+    ///
+    /// Returns the groups of [`Deduplicator::find_duplicates`], and every pair
+    /// of citations in different groups whose title similarity is below the
+    /// threshold by at most `margin`, i.e. in `[threshold - margin, threshold)`.
+    /// Reviewing these pairs helps decide whether to lower the thresholds, and
+    /// finds borderline duplicates the algorithm kept apart. Only pairs that
+    /// were compared are reported: with `group_by_year`, both citations are
+    /// from the same year. Pairs with a manual override and blocks needing more
+    /// than `max_pairwise` comparisons are skipped.
+    ///
+    /// Every pair is scored again, so this costs more than
+    /// [`Deduplicator::find_duplicates`]. Near misses are ordered by the input
+    /// positions of their citations.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DedupeError::ConfigError`] if `margin` is negative or not a
+    /// number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{dedupe::Deduplicator, Citation};
+    ///
+    /// let citation = |id: &str, title: &str| Citation {
+    ///     id: id.to_string(),
+    ///     title: title.to_string(),
+    ///     journal: Some("Journal of Tests".to_string()),
+    ///     volume: Some("1".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// let citations = [
+    ///     citation("1", "Effects of aspirin on platelet aggregation"),
+    ///     citation("2", "Effects of aspirin on platelet function"),
+    /// ];
+    ///
+    /// let (groups, near_misses) = Deduplicator::new()
+    ///     .find_duplicates_with_nearmisses(&citations, 0.1)
+    ///     .unwrap();
+    /// assert_eq!(groups.len(), 2);
+    /// assert_eq!(near_misses.len(), 1);
+    /// assert!(near_misses[0].score < near_misses[0].threshold);
+    /// ```
+    pub fn find_duplicates_with_nearmisses(
+        &self,
+        citations: &[Citation],
+        margin: f64,
+    ) -> Result<(Vec<DuplicateGroup>, Vec<NearMiss>), DedupeError> {
+        if margin.is_nan() || margin < 0.0 {
+            return Err(DedupeError::ConfigError(format!(
+                "near-miss margin must be a non-negative number, got {margin}"
+            )));
+        }
+        let groups = self.positioned_groups(citations)?;
+
+        let mut group_of = vec![0; citations.len()];
+        for (index, group) in groups.iter().enumerate() {
+            for &position in &group.positions {
+                group_of[position] = index;
+            }
+        }
+
+        let weights = self.title_token_weights(citations);
+        let mut near_misses = Vec::new();
+        for block in self.comparison_blocks(citations) {
+            if self.exceeds_max_pairwise(block.len()) {
+                continue;
+            }
+            let block_citations: Vec<&Citation> = block.iter().map(|&i| &citations[i]).collect();
+            let preprocessed = self.preprocess(&block_citations, weights.as_ref())?;
+            for (i, current) in preprocessed.iter().enumerate() {
+                for (j, other) in preprocessed.iter().enumerate().skip(i + 1) {
+                    let (a, b) = (&current.original.id, &other.original.id);
+                    if group_of[block[i]] == group_of[block[j]] || self.override_for(a, b).is_some()
+                    {
+                        continue;
+                    }
+                    let (score, threshold) = self.title_score(current, other);
+                    if score < threshold && score >= threshold - margin {
+                        // Blocks are in input order, so `a` comes first
+                        let near_miss = NearMiss {
+                            first: a.clone(),
+                            second: b.clone(),
+                            score,
+                            threshold,
+                        };
+                        near_misses.push(((block[i], block[j]), near_miss));
+                    }
+                }
+            }
+        }
+        near_misses.sort_by_key(|(positions, _)| *positions);
+        Ok((
+            groups.into_iter().map(|group| group.group).collect(),
+            near_misses
+                .into_iter()
+                .map(|(_, near_miss)| near_miss)
+                .collect(),
+        ))
+    }
+
+    /// Lists the pairs of citations that match each other directly.
+    ///
+    /// Each edge holds the ids of two citations, the one appearing first in
+    /// the input first, and their title similarity. Pairs match as in
+    /// [`Deduplicator::find_duplicates`], including manual overrides, but each
+    /// pair is judged on its own: the groups are the connected components of
+    /// the edges, except where an override keeps a citation out of a group it
+    /// is linked to. Loaded into a graph tool such as Gephi or d3, the edges
+    /// show how tightly each group hangs together, e.g. a chain of weak matches
+    /// that merged two works. Only pairs that are compared can match: with
+    /// `group_by_year`, both citations are from the same year, and blocks
+    /// needing more than `max_pairwise` comparisons only link exact duplicates.
+    ///
+    /// Every pair is scored, so this costs more than
+    /// [`Deduplicator::find_duplicates`]. Edges are ordered by the input
+    /// positions of their citations.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DedupeError` if the citations cannot be compared.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{dedupe::Deduplicator, Citation};
+    ///
+    /// let citation = |id: &str, doi: &str| Citation {
+    ///     id: id.to_string(),
+    ///     title: "Effects of aspirin on platelet aggregation".to_string(),
+    ///     doi: Some(doi.to_string()),
+    ///     journal: Some("Journal of Tests".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// let citations = [
+    ///     citation("1", "10.1000/a"),
+    ///     citation("2", "10.1000/b"),
+    ///     citation("3", "10.1000/a"),
+    /// ];
+    ///
+    /// let edges = Deduplicator::new().duplicate_edges(&citations).unwrap();
+    /// assert_eq!(edges, vec![("1".to_string(), "3".to_string(), 1.0)]);
+    /// ```
+    pub fn duplicate_edges(
+        &self,
+        citations: &[Citation],
+    ) -> Result<Vec<(CitationId, CitationId, f64)>, DedupeError> {
+        let weights = self.title_token_weights(citations);
+        let mut edges = Vec::new();
+        for block in self.comparison_blocks(citations) {
+            let block_citations: Vec<&Citation> = block.iter().map(|&i| &citations[i]).collect();
+            let preprocessed = self.preprocess(&block_citations, weights.as_ref())?;
+            let mut link = |i: usize, j: usize| {
+                let (score, _) = self.title_score(&preprocessed[i], &preprocessed[j]);
+                let (first, second) = if block[i] <= block[j] { (i, j) } else { (j, i) };
+                let edge = (
+                    preprocessed[first].original.id.clone(),
+                    preprocessed[second].original.id.clone(),
+                    score,
+                );
+                edges.push(((block[first], block[second]), edge));
+            };
+            if self.exceeds_max_pairwise(block.len()) {
+                for members in self.exact_duplicates(&preprocessed) {
+                    for (n, &i) in members.iter().enumerate() {
+                        for &j in &members[n + 1..] {
+                            link(i, j);
+                        }
+                    }
+                }
+                continue;
+            }
+            for (i, current) in preprocessed.iter().enumerate() {
+                for (j, other) in preprocessed.iter().enumerate().skip(i + 1) {
+                    let is_duplicate =
+                        match self.override_for(&current.original.id, &other.original.id) {
+                            Some(decision) => decision == Decision::Duplicate,
+                            None => self.is_duplicate(current, other),
+                        };
+                    if is_duplicate {
+                        link(i, j);
+                    }
+                }
+            }
+        }
+        edges.sort_by_key(|(positions, _)| *positions);
+        Ok(edges.into_iter().map(|(_, edge)| edge).collect())
+    }
+
+    /// Splits citations into the blocks compared pairwise, as input positions
+    /// in ascending order: year groups with `group_by_year`, and otherwise the
+    /// whole input
+    fn comparison_blocks(&self, citations: &[Citation]) -> Vec<Vec<usize>> {
+        if self.config.group_by_year {
+            Self::group_by_year(citations).into_values().collect()
+        } else {
+            vec![(0..citations.len()).collect()]
+        }
+    }
+
+    /// Whether a block of `len` citations needs more pairwise comparisons than
+    /// `max_pairwise` allows
+    fn exceeds_max_pairwise(&self, len: usize) -> bool {
+        let pairs = len.saturating_mul(len.saturating_sub(1)) / 2;
+        self.config.max_pairwise.is_some_and(|max| pairs > max)
+    }
+
+    /// Deduplicates several labeled collections together.
+    ///
+    /// This is the import step of a systematic review in one call: the source
+    /// of every citation is set to its collection's label, the collections are
+    /// concatenated and deduplicated with the deduplicator's configuration, and
+    /// the result holds the unique citations, the duplicate groups and the
+    /// counts per source for a PRISMA flow diagram. Citation ids should be
+    /// unique across all collections.
+    ///
+    /// # Arguments
+    ///
+    /// * `sources` - Pairs of a source label and the citations imported from it
+    ///
+    /// # Errors
+    ///
+    /// Returns `DedupeError` if deduplication fails.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{dedupe::Deduplicator, Citation};
+    ///
+    /// let citation = |id: &str| Citation {
+    ///     id: id.to_string(),
+    ///     title: "Example Title".to_string(),
+    ///     doi: Some("10.1234/example".to_string()),
+    ///     journal: Some("Example Journal".to_string()),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let result = Deduplicator::new()
+    ///     .dedupe_sources(vec![
+    ///         ("PubMed".to_string(), vec![citation("1")]),
+    ///         ("Embase".to_string(), vec![citation("2")]),
+    ///     ])
+    ///     .unwrap();
+    ///
+    /// assert_eq!(result.unique.len(), 1);
+    /// assert_eq!(result.total_records(), 2);
+    /// assert_eq!(result.duplicates_removed(), 1);
+    /// assert_eq!(result.sources[1].source, "Embase");
+    /// assert_eq!(result.sources[1].duplicates_removed, 1);
+    /// ```
+    pub fn dedupe_sources(
+        &self,
+        sources: Vec<(String, Vec<Citation>)>,
+    ) -> Result<DedupeResult, DedupeError> {
+        let mut summaries: Vec<SourceSummary> = Vec::new();
+        let mut citations = Vec::new();
+        for (label, collection) in sources {
+            let index = match summaries.iter().position(|s| s.source == label) {
+                Some(index) => index,
+                None => {
+                    summaries.push(SourceSummary {
+                        source: label.clone(),
+                        ..Default::default()
+                    });
+                    summaries.len() - 1
+                }
+            };
+            summaries[index].records += collection.len();
+            citations.extend(collection.into_iter().map(|mut citation| {
+                citation.source = Some(label.clone());
+                citation
+            }));
+        }
+
+        let positioned = self.positioned_groups(&citations)?;
+        let groups: Vec<DuplicateGroup> = positioned.iter().map(|g| g.group.clone()).collect();
+        let summary = |summaries: &[SourceSummary], citation: &Citation| {
+            summaries
+                .iter()
+                .position(|s| citation.source.as_deref() == Some(s.source.as_str()))
+        };
+        for group in &groups {
+            if let Some(index) = summary(&summaries, &group.unique) {
+                summaries[index].kept += 1;
+                if group
+                    .duplicates
+                    .iter()
+                    .all(|c| c.source == group.unique.source)
+                {
+                    summaries[index].only_in_source += 1;
+                }
+            }
+            for duplicate in &group.duplicates {
+                if let Some(index) = summary(&summaries, duplicate) {
+                    summaries[index].duplicates_removed += 1;
+                }
+            }
+        }
+
+        Ok(DedupeResult {
+            unique: self.unique_citations(positioned),
+            groups,
+            sources: summaries,
+        })
+    }
+
+    /// Finds the records of a master library that each new citation duplicates.
+    ///
+    /// Unlike [`Deduplicator::find_duplicates`], citations are only compared
+    /// across the two lists: duplicates within `new`, or within `master`, are
+    /// not looked for. Citations are compared with the deduplicator's
+    /// configuration and overrides, and only within the same year when
+    /// `group_by_year` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `new` - The citations to check, such as a new search batch
+    /// * `master` - The citations already in the library
+    ///
+    /// # Returns
+    ///
+    /// For every new citation, in order, its index in `new` and the indices in
+    /// `master` of the records it duplicates, in ascending order. The list is
+    /// empty for citations not yet in the library.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DedupeError` if a citation cannot be processed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{dedupe::Deduplicator, Citation};
+    ///
+    /// let citation = |title: &str, doi: &str| Citation {
+    ///     title: title.to_string(),
+    ///     doi: Some(doi.to_string()),
+    ///     journal: Some("Example Journal".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// let master = vec![citation("Known title", "10.1234/known")];
+    /// let new = vec![
+    ///     citation("Unknown title", "10.1234/unknown"),
+    ///     citation("Known title", "10.1234/known"),
+    /// ];
+    ///
+    /// let matches = Deduplicator::new().find_against(&new, &master).unwrap();
+    /// assert_eq!(matches, vec![(0, vec![]), (1, vec![0])]);
+    /// ```
+    pub fn find_against(
+        &self,
+        new: &[Citation],
+        master: &[Citation],
+    ) -> Result<Vec<(usize, Vec<usize>)>, DedupeError> {
+        let new_refs: Vec<&Citation> = new.iter().collect();
+        let master_refs: Vec<&Citation> = master.iter().collect();
+        let weights = self.title_token_weights(new.iter().chain(master));
+        let new = self.preprocess(&new_refs, weights.as_ref())?;
+        let master = self.preprocess(&master_refs, weights.as_ref())?;
+
+        let matches = |(i, current): (usize, &PreprocessedCitation)| {
+            let duplicates = master
+                .iter()
+                .enumerate()
+                .filter(|(_, other)| {
+                    !self.config.group_by_year || current.original.year == other.original.year
+                })
+                .filter(|(_, other)| {
+                    match self.override_for(&current.original.id, &other.original.id) {
+                        Some(decision) => decision == Decision::Duplicate,
+                        None => self.is_duplicate(current, other),
+                    }
+                })
+                .map(|(j, _)| j)
+                .collect();
+            (i, duplicates)
+        };
+
+        if self.runs_in_parallel(new.len() + master.len()) {
+            use rayon::prelude::*;
+
+            Ok(new.par_iter().enumerate().map(matches).collect())
+        } else {
+            Ok(new.iter().enumerate().map(matches).collect())
+        }
+    }
+
+    /// Checks whether `count` citations are processed in parallel
+    fn runs_in_parallel(&self, count: usize) -> bool {
+        self.config.run_in_parallel && count >= self.config.parallel_threshold
+    }
+
+    /// Takes the unique citation of each group, in input order, recording the
+    /// merged ids if configured
+    fn unique_citations(&self, groups: Vec<PositionedGroup>) -> Vec<Citation> {
+        let mut unique: Vec<(usize, Citation)> = groups
+            .into_iter()
+            .map(
+                |PositionedGroup {
+                     group,
+                     unique: position,
+                     ..
+                 }| {
+                    let mut unique = group.unique;
+                    if self.config.record_merged_ids && !group.duplicates.is_empty() {
+                        let (ids, sources) = group
+                            .duplicates
+                            .into_iter()
+                            .map(|c| (c.id, c.source.unwrap_or_default()))
+                            .unzip();
+                        unique.extra_fields.insert("merged_ids".to_string(), ids);
+                        unique
+                            .extra_fields
+                            .insert("merged_sources".to_string(), sources);
+                    }
+                    (position, unique)
+                },
+            )
+            .collect();
+        unique.sort_by_key(|(position, _)| *position);
+        unique.into_iter().map(|(_, citation)| citation).collect()
+    }
+
+    fn group_duplicates(&self, citations: &[Citation]) -> Result<Vec<DuplicateGroup>, DedupeError> {
+        let groups = self.positioned_groups(citations)?;
+        Ok(groups.into_iter().map(|group| group.group).collect())
+    }
+
+    fn positioned_groups(
+        &self,
+        citations: &[Citation],
+    ) -> Result<Vec<PositionedGroup>, DedupeError> {
+        self.group_duplicates_reporting(citations, &|_| {})
+    }
+
+    /// Groups duplicates, calling `on_group` with each group as it is finalized
+    fn group_duplicates_reporting(
+        &self,
+        citations: &[Citation],
+        on_group: &(dyn Fn(&DuplicateGroup) + Sync),
+    ) -> Result<Vec<PositionedGroup>, DedupeError> {
+        if citations.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Word frequencies are counted over the whole input, not per year
+        let weights = self.title_token_weights(citations);
+        let blocks = self.comparison_blocks(citations);
+        let mut duplicate_groups: Vec<PositionedGroup> = if self.runs_in_parallel(citations.len()) {
+            use rayon::prelude::*;
+
+            let duplicate_groups: Result<Vec<_>, _> = blocks
+                .par_iter()
+                .map(|block| {
+                    self.process_citation_group(citations, block, weights.as_ref(), on_group)
+                })
+                .collect();
+
+            // Flatten results
+            duplicate_groups?.into_iter().flatten().collect()
+        } else {
+            let mut duplicate_groups = Vec::new();
+
+            for block in &blocks {
+                duplicate_groups.extend(self.process_citation_group(
+                    citations,
+                    block,
+                    weights.as_ref(),
+                    on_group,
+                )?);
+            }
+            duplicate_groups
+        };
+
+        // Year groups are processed in no particular order, so put the groups
+        // back in input order of their first citation
+        duplicate_groups.sort_by_key(|group| group.positions[0]);
+        Ok(duplicate_groups)
+    }
+
+    fn select_unique_citation<'a>(&self, citations: &[&'a Citation]) -> &'a Citation {
+        if citations.len() == 1 {
+            return citations[0];
+        }
+
+        // Corrections are never chosen over the work they correct
+        let corrected: Vec<&Citation>;
+        let citations = if self.config.link_corrections {
+            corrected = citations
+                .iter()
+                .copied()
+                .filter(|c| !c.is_correction())
+                .collect();
+            if corrected.is_empty() {
+                citations
+            } else {
+                &corrected
+            }
+        } else {
+            citations
+        };
+        if citations.len() == 1 {
+            return citations[0];
+        }
+
+        // First try source preferences
+        if !self.config.source_preferences.is_empty() {
+            for preferred_source in &self.config.source_preferences {
+                if let Some(citation) = citations
+                    .iter()
+                    .find(|c| c.source.as_ref() == Some(preferred_source))
+                {
+                    return citation;
                 }
             }
         }
 
+        // Then prefer published versions over preprints
+        let published: Vec<&Citation>;
+        let citations = if self.config.link_preprints {
+            published = citations
+                .iter()
+                .copied()
+                .filter(|c| !Self::is_preprint(c))
+                .collect();
+            if published.is_empty() {
+                citations
+            } else {
+                &published
+            }
+        } else {
+            citations
+        };
+
         // If no source preference matches, prefer citations with abstracts
         let citations_with_abstract: Vec<_> = citations
             .iter()
@@ -382,148 +1711,736 @@ impl Deduplicator {
                 // Multiple abstracts, prefer ones with DOI
                 let with_doi = citations_with_abstract
                     .iter()
-                    .find(|c| c.doi.as_ref().map_or(false, |d| !d.is_empty()));
+                    .find(|c| c.doi.as_ref().is_some_and(|d| !d.is_empty()));
 
                 with_doi.copied().unwrap_or(citations_with_abstract[0])
             }
         }
     }
 
-    fn process_citation_group(
+    /// Counts the titles containing each word, if title words are weighted and
+    /// there are enough citations
+    fn title_token_weights<'c>(
         &self,
-        citations: &[&Citation],
-    ) -> Result<Vec<DuplicateGroup>, DedupeError> {
-        let mut duplicate_groups = Vec::new();
-        // Preprocess all citations in this group
-        let preprocessed: Vec<PreprocessedCitation> = citations
+        citations: impl IntoIterator<Item = &'c Citation>,
+    ) -> Option<TitleTokenWeights> {
+        if !self.config.weight_title_tokens {
+            return None;
+        }
+        let mut document_frequency: HashMap<String, usize> = HashMap::new();
+        let mut corpus_size = 0;
+        for citation in citations {
+            for token in self.title_tokens(&citation.title) {
+                *document_frequency.entry(token).or_default() += 1;
+            }
+            corpus_size += 1;
+        }
+        (corpus_size >= MIN_TITLE_WEIGHTING_CORPUS_SIZE).then_some(TitleTokenWeights {
+            document_frequency,
+            corpus_size,
+        })
+    }
+
+    /// Splits a title into its distinct lowercase words, sorted
+    fn title_tokens(&self, title: &str) -> Vec<String> {
+        let title = Self::convert_unicode_string(self.strip_leading_article(title)).to_lowercase();
+        let mut tokens: Vec<String> = title
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|token| !token.is_empty())
+            .map(String::from)
+            .collect();
+        tokens.sort_unstable();
+        tokens.dedup();
+        tokens
+    }
+
+    /// Weight of the words each title shares with the other, as a share of
+    /// the weight of all their words, or of 1.0 if that is less
+    ///
+    /// Words of five or more letters one edit apart count as shared, so a typo
+    /// does not turn a common word into a rare one.
+    fn title_token_overlap(a: &[(String, f64)], b: &[(String, f64)]) -> f64 {
+        fn shared(tokens: &[(String, f64)], other: &[(String, f64)]) -> f64 {
+            tokens
+                .iter()
+                .filter(|(token, _)| {
+                    other.binary_search_by(|(o, _)| o.cmp(token)).is_ok()
+                        || (token.chars().count() >= 5
+                            && other.iter().any(|(o, _)| levenshtein(token, o) <= 1))
+                })
+                .map(|(_, weight)| weight)
+                .sum()
+        }
+        let total: f64 = a.iter().chain(b).map(|(_, weight)| weight).sum();
+        ((shared(a, b) + shared(b, a)) / total.max(1.0)).min(1.0)
+    }
+
+    /// Normalizes the fields of citations for comparison
+    fn preprocess<'a>(
+        &self,
+        citations: &[&'a Citation],
+        weights: Option<&TitleTokenWeights>,
+    ) -> Result<Vec<PreprocessedCitation<'a>>, DedupeError> {
+        let journal_aliases = self.normalized_journal_aliases();
+        citations
             .iter()
             .map(|c| {
                 Ok(PreprocessedCitation {
                     original: c,
                     normalized_title: Self::normalize_string(&Self::convert_unicode_string(
-                        &c.title,
+                        self.strip_leading_article(&c.title),
                     ))
                     .ok_or_else(|| {
                         DedupeError::ProcessingError("Failed to normalize title".to_string())
                     })?,
-                    normalized_journal: Self::format_journal_name(c.journal.as_deref()),
-                    normalized_journal_abbr: Self::format_journal_name(c.journal_abbr.as_deref()),
+                    normalized_main_title: Self::normalize_string(&Self::convert_unicode_string(
+                        self.strip_leading_article(&c.title_parts().0),
+                    ))
+                    .unwrap_or_default(),
+                    normalized_abstract: if self.config.abstract_weight > 0.0 {
+                        c.abstract_text.as_deref().and_then(|abstract_text| {
+                            Self::normalize_string(&Self::convert_unicode_string(abstract_text))
+                        })
+                    } else {
+                        None
+                    },
+                    abstract_hash: c
+                        .abstract_text
+                        .as_deref()
+                        .filter(|_| self.config.match_identical_abstracts)
+                        .and_then(|abstract_text| {
+                            Self::normalize_string(&Self::convert_unicode_string(abstract_text))
+                        })
+                        .filter(|normalized| {
+                            normalized.chars().count() >= MIN_IDENTICAL_ABSTRACT_LENGTH
+                        })
+                        .map(|normalized| crate::utils::fnv1a_hash(normalized.as_bytes())),
+                    normalized_journal: Self::normalize_journal(
+                        c.journal.as_deref(),
+                        &journal_aliases,
+                    ),
+                    normalized_journal_abbr: Self::normalize_journal(
+                        c.journal_abbr.as_deref(),
+                        &journal_aliases,
+                    ),
                     normalized_volume: c
                         .volume
                         .as_deref()
                         .map_or(String::new(), Deduplicator::normalize_volume),
+                    first_page: c.pages.as_deref().and_then(first_page),
                     normalized_issn: c
                         .issn
                         .iter()
                         .filter_map(|issn| Deduplicator::format_issn(issn))
                         .collect(),
+                    normalized_family_names: {
+                        let mut names: Vec<String> = c
+                            .authors_only()
+                            .filter_map(|author| {
+                                let name = Self::convert_unicode_string(&author.family_name);
+                                if self.config.normalize_surnames {
+                                    Some(normalize_surname(&name))
+                                } else {
+                                    Self::normalize_string(&name)
+                                }
+                            })
+                            .filter(|name| !name.is_empty())
+                            .collect();
+                        names.sort_unstable();
+                        names
+                    },
+                    normalized_first_author: c
+                        .authors_only()
+                        .next()
+                        .filter(|_| self.config.normalize_surnames)
+                        .map(|author| {
+                            normalize_surname(&Self::convert_unicode_string(&author.family_name))
+                        }),
+                    title_tokens: weights.map(|weights| {
+                        self.title_tokens(&c.title)
+                            .into_iter()
+                            .map(|token| {
+                                let weight = weights.weight(&token);
+                                (token, weight)
+                            })
+                            .collect()
+                    }),
+                    normalized_corrected_title: c.corrected_title().map(|title| {
+                        Self::normalize_string(&Self::convert_unicode_string(
+                            self.strip_leading_article(title),
+                        ))
+                        .unwrap_or_default()
+                    }),
+                    arxiv_id: c.arxiv_id.as_deref().map(|id| {
+                        let id = id.trim().to_lowercase();
+                        match id.rfind('v') {
+                            Some(pos)
+                                if pos + 1 < id.len()
+                                    && id[pos + 1..].bytes().all(|b| b.is_ascii_digit()) =>
+                            {
+                                id[..pos].to_string()
+                            }
+                            _ => id,
+                        }
+                    }),
+                    document_part: if self.config.respect_type_differences {
+                        DocumentPart::of(c)
+                    } else {
+                        None
+                    },
                 })
             })
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect()
+    }
+
+    /// Groups the duplicates among the citations at the input positions of
+    /// `block`, calling `on_group` with each group as it is finalized
+    fn process_citation_group(
+        &self,
+        citations: &[Citation],
+        block: &[usize],
+        weights: Option<&TitleTokenWeights>,
+        on_group: &(dyn Fn(&DuplicateGroup) + Sync),
+    ) -> Result<Vec<PositionedGroup>, DedupeError> {
+        let mut duplicate_groups = Vec::new();
+        let citations: Vec<&Citation> = block.iter().map(|&i| &citations[i]).collect();
+        let preprocessed = self.preprocess(&citations, weights)?;
 
+        let members = self.exact_duplicates(&preprocessed);
         let mut processed_ids = std::collections::HashSet::new();
 
+        let compare_pairs = match self.config.max_pairwise {
+            Some(max) if self.exceeds_max_pairwise(citations.len()) => {
+                self.warning_handler.warn(&format!(
+                    "block of {} citations exceeds max_pairwise ({max}); \
+                     only exact duplicates are grouped",
+                    citations.len()
+                ));
+                false
+            }
+            _ => true,
+        };
+
         for i in 0..preprocessed.len() {
-            if processed_ids.contains(&preprocessed[i].original.id) {
+            // Skip exact duplicates, which are grouped with their representative
+            if members[i].is_empty() || processed_ids.contains(&preprocessed[i].original.id) {
                 continue;
             }
 
-            let mut group_citations = vec![preprocessed[i].original];
+            let mut group = members[i].clone();
             let current = &preprocessed[i];
 
-            for j in 0..preprocessed.len() {
-                if i == j || processed_ids.contains(&preprocessed[j].original.id) {
+            for (j, other) in preprocessed.iter().enumerate() {
+                if !compare_pairs {
+                    break;
+                }
+                if i == j || members[j].is_empty() || processed_ids.contains(&other.original.id) {
                     continue;
                 }
 
-                let other = &preprocessed[j];
+                // Never group citations a reviewer has marked as distinct
+                if group.iter().any(|&k| {
+                    self.override_for(&preprocessed[k].original.id, &other.original.id)
+                        == Some(Decision::NotDuplicate)
+                }) {
+                    continue;
+                }
 
-                let journal_match = Self::journals_match(
-                    &current.normalized_journal,
-                    &current.normalized_journal_abbr,
-                    &other.normalized_journal,
-                    &other.normalized_journal_abbr,
-                );
-                let issns_match =
-                    Self::match_issns(&current.normalized_issn, &other.normalized_issn);
-                let volumes_match = !current.normalized_volume.is_empty()
-                    && !other.normalized_volume.is_empty()
-                    && current.normalized_volume == other.normalized_volume;
-                let pages_match = current.original.pages.is_some()
-                    && other.original.pages.is_some()
-                    && current.original.pages == other.original.pages;
-                let years_match = current.original.year == other.original.year;
-
-                let is_duplicate = match (&current.original.doi, &other.original.doi) {
-                    // With DOIs
-                    (Some(doi1), Some(doi2)) if !doi1.is_empty() && !doi2.is_empty() => {
-                        let title_similarity =
-                            jaro(&current.normalized_title, &other.normalized_title);
-
-                        // With Journal/ISSN match
-                        (doi1 == doi2 && title_similarity >= DOI_TITLE_SIMILARITY_THRESHOLD && (journal_match || issns_match))
-                        // Without Journal/ISSN match: only when we have same DOI (and we use volume/pages instead)
-                        || (doi1 == doi2 && title_similarity >= 0.99 && (volumes_match || pages_match))
-                        // Without DOI match: only when we have a very high title similarity and all other fields match
-                        || (title_similarity >= 0.99 && years_match && (volumes_match || pages_match) && (journal_match || issns_match))
-                    }
-                    // Without DOIs
-                    _ => {
-                        let title_similarity =
-                            jaro_winkler(&current.normalized_title, &other.normalized_title);
-
-                        // With Journal/ISSN match
-                        (title_similarity >= NO_DOI_TITLE_SIMILARITY_THRESHOLD && (volumes_match || pages_match) && (journal_match || issns_match))
-                        // Without Journal/ISSN match: only when we have a very high title similarity and all other fields match
-                        || (title_similarity >= 0.99 && years_match && (volumes_match && pages_match))
-                    }
+                let decision = self.override_for(&current.original.id, &other.original.id);
+                let is_duplicate = match decision {
+                    Some(decision) => decision == Decision::Duplicate,
+                    None => self.is_duplicate(current, other),
                 };
 
                 if is_duplicate {
-                    group_citations.push(other.original);
-                    processed_ids.insert(other.original.id.clone());
+                    for &k in &members[j] {
+                        processed_ids.insert(preprocessed[k].original.id.clone());
+                    }
+                    group.extend(&members[j]);
                 }
             }
 
+            // Keep input order so the unique citation is selected as without the fast path
+            group.sort_unstable();
+            let group_citations: Vec<&Citation> =
+                group.iter().map(|&k| preprocessed[k].original).collect();
+            let positions: Vec<usize> = group.iter().map(|&k| block[k]).collect();
+
             if group_citations.len() > 1 {
                 let unique = self.select_unique_citation(&group_citations);
+                let unique_position = group_citations
+                    .iter()
+                    .position(|&c| std::ptr::eq(c, unique))
+                    .map_or(positions[0], |n| positions[n]);
 
+                let preprint_ids = if self.config.link_preprints {
+                    group_citations
+                        .iter()
+                        .filter(|c| Self::is_preprint(c))
+                        .map(|c| c.id.clone())
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                let correction_ids = if self.config.link_corrections {
+                    group_citations
+                        .iter()
+                        .filter(|c| c.id != unique.id && c.is_correction())
+                        .map(|c| c.id.clone())
+                        .collect()
+                } else {
+                    Vec::new()
+                };
                 let duplicates: Vec<Citation> = group_citations
                     .into_iter()
                     .filter(|c| c.id != unique.id)
-                    .map(|c| c.clone())
+                    .cloned()
                     .collect();
 
-                duplicate_groups.push(DuplicateGroup {
-                    unique: unique.clone(),
-                    duplicates,
+                duplicate_groups.push(PositionedGroup {
+                    group: DuplicateGroup {
+                        unique: unique.clone(),
+                        duplicates,
+                        preprint_ids,
+                        correction_ids,
+                    },
+                    positions,
+                    unique: unique_position,
                 });
                 processed_ids.insert(unique.id.clone());
             } else {
-                duplicate_groups.push(DuplicateGroup {
-                    unique: current.original.clone(),
-                    duplicates: Vec::new(),
+                duplicate_groups.push(PositionedGroup {
+                    group: DuplicateGroup {
+                        unique: current.original.clone(),
+                        ..Default::default()
+                    },
+                    unique: positions[0],
+                    positions,
                 });
             }
+            if let Some(group) = duplicate_groups.last() {
+                on_group(&group.group);
+            }
         }
 
         Ok(duplicate_groups)
     }
 
-    fn group_by_year(citations: &[Citation]) -> HashMap<i32, Vec<&Citation>> {
-        let mut year_map: HashMap<i32, Vec<&Citation>> = HashMap::new();
+    /// Finds exact duplicates in O(n) by hashing every field used for matching.
+    ///
+    /// Returns, for each citation, the indices of the citations it represents in
+    /// the pairwise comparison: itself and its exact duplicates for the first of a
+    /// set, and nothing for the others. Citations with identical matching fields
+    /// compare exactly as the first of them does, so the pairwise result is
+    /// unchanged. Sets whose abstracts are identical, which always match under
+    /// [`DeduplicatorConfig::match_identical_abstracts`], are then joined into
+    /// the first of them, so they are grouped even without pairwise comparison.
+    /// Citations that would not match themselves, or that have manual
+    /// overrides, are always compared individually.
+    fn exact_duplicates(&self, preprocessed: &[PreprocessedCitation]) -> Vec<Vec<usize>> {
+        let overridden: std::collections::HashSet<&str> = self
+            .overrides
+            .keys()
+            .flat_map(|(a, b)| [a.as_str(), b.as_str()])
+            .collect();
 
-        for citation in citations {
-            let year = citation.year.unwrap_or(0);
-            year_map.entry(year).or_default().push(citation);
+        let mut members: Vec<Vec<usize>> = (0..preprocessed.len()).map(|i| vec![i]).collect();
+        let mut representatives: HashMap<_, usize> = HashMap::new();
+        let mut individual = vec![false; preprocessed.len()];
+
+        for (i, citation) in preprocessed.iter().enumerate() {
+            if overridden.contains(citation.original.id.as_str())
+                || !self.is_duplicate(citation, citation)
+            {
+                individual[i] = true;
+                continue;
+            }
+
+            let key = (
+                (
+                    &citation.normalized_title,
+                    &citation.normalized_main_title,
+                    citation.title_tokens.as_ref().map(|tokens| {
+                        tokens
+                            .iter()
+                            .map(|(token, _)| token.as_str())
+                            .collect::<Vec<_>>()
+                    }),
+                ),
+                (&citation.normalized_abstract, citation.abstract_hash),
+                citation.original.year,
+                (
+                    // Family names agreeing only once normalized also need the
+                    // same initial, so the initial is part of the key
+                    citation
+                        .original
+                        .authors_only()
+                        .next()
+                        .map(|a| (a.family_name.to_lowercase(), Self::first_initial(a))),
+                    &citation.normalized_first_author,
+                    &citation.normalized_family_names,
+                ),
+                (&citation.original.doi, &citation.arxiv_id),
+                &citation.original.all_dois,
+                // Accession numbers match only within a source
+                citation
+                    .original
+                    .accession_number
+                    .as_ref()
+                    .map(|accession_number| (accession_number, &citation.original.source)),
+                &citation.normalized_journal,
+                &citation.normalized_journal_abbr,
+                &citation.normalized_issn,
+                &citation.normalized_volume,
+                (&citation.original.pages, citation.document_part),
+            );
+
+            match representatives.get(&key) {
+                Some(&representative) => {
+                    members[i].clear();
+                    members[representative].push(i);
+                }
+                None => {
+                    representatives.insert(key, i);
+                }
+            }
         }
 
-        year_map
+        // Abstracts only match when the document parts agree and neither or
+        // both citations are corrections, so those are part of the key
+        let mut by_abstract: HashMap<_, usize> = HashMap::new();
+        for (i, citation) in preprocessed.iter().enumerate() {
+            let Some(hash) = citation.abstract_hash else {
+                continue;
+            };
+            if individual[i] || members[i].is_empty() {
+                continue;
+            }
+            let key = (
+                hash,
+                citation.document_part,
+                citation.normalized_corrected_title.is_some(),
+            );
+            match by_abstract.get(&key) {
+                Some(&representative) => {
+                    let set = std::mem::take(&mut members[i]);
+                    members[representative].extend(set);
+                }
+                None => {
+                    by_abstract.insert(key, i);
+                }
+            }
+        }
+
+        members
     }
 
-    fn convert_unicode_string(input: &str) -> String {
-        UNICODE_REGEX
-            .replace_all(input, |caps: &regex::Captures| {
+    /// Whether two citations share a DOI or an accession number
+    fn share_identifier(a: &Citation, b: &Citation) -> bool {
+        let shared_doi = a
+            .dois()
+            .any(|doi| b.dois().any(|other| doi.eq_ignore_ascii_case(other)));
+        let shared_accession_number = match (&a.accession_number, &b.accession_number) {
+            (Some(x), Some(y)) => !x.trim().is_empty() && x.trim() == y.trim(),
+            _ => false,
+        };
+        shared_doi || shared_accession_number
+    }
+
+    /// Compares two preprocessed citations using the matching criteria
+    fn is_duplicate(&self, current: &PreprocessedCitation, other: &PreprocessedCitation) -> bool {
+        let journal_match = Self::journals_match(
+            &current.normalized_journal,
+            &current.normalized_journal_abbr,
+            &other.normalized_journal,
+            &other.normalized_journal_abbr,
+        );
+        let issns_match = Self::match_issns(&current.normalized_issn, &other.normalized_issn);
+        let volumes_match = !current.normalized_volume.is_empty()
+            && !other.normalized_volume.is_empty()
+            && current.normalized_volume == other.normalized_volume;
+        let pages_match = current.first_page.is_some() && current.first_page == other.first_page;
+        let years_match = current.original.year == other.original.year;
+        let first_authors_match = match (
+            current.original.authors_only().next(),
+            other.original.authors_only().next(),
+        ) {
+            (Some(a), Some(b)) if !a.family_name.is_empty() => {
+                a.family_name.to_lowercase() == b.family_name.to_lowercase()
+                    || match (
+                        &current.normalized_first_author,
+                        &other.normalized_first_author,
+                    ) {
+                        // Only agreeing after normalization, so the initials must agree too
+                        (Some(x), Some(y)) => !x.is_empty() && x == y && Self::initials_agree(a, b),
+                        _ => false,
+                    }
+            }
+            _ => false,
+        };
+        // The same family names in any order, unless the first authors must agree
+        let authors_match = first_authors_match
+            || (!self.config.first_author_must_match
+                && !current.normalized_family_names.is_empty()
+                && current.normalized_family_names == other.normalized_family_names);
+
+        // The same accession number in the same database is the same record
+        if let (Some(a), Some(b)) = (
+            &current.original.accession_number,
+            &other.original.accession_number,
+        ) {
+            let same_source = match (&current.original.source, &other.original.source) {
+                (Some(s1), Some(s2)) => s1.eq_ignore_ascii_case(s2),
+                _ => false,
+            };
+            if same_source && !a.trim().is_empty() && a.trim() == b.trim() {
+                return true;
+            }
+        }
+
+        // Versions of an arXiv preprint share its ID
+        if current.arxiv_id.is_some() && current.arxiv_id == other.arxiv_id {
+            return true;
+        }
+
+        // A meeting abstract is a different document from the full paper,
+        // unless a shared identifier shows the type labels merely differ
+        if let (Some(a), Some(b)) = (current.document_part, other.document_part) {
+            if a != b && !Self::share_identifier(current.original, other.original) {
+                return false;
+            }
+        }
+
+        // A correction only matches the work it corrects, and only when enabled
+        match (
+            &current.normalized_corrected_title,
+            &other.normalized_corrected_title,
+        ) {
+            (Some(corrected_title), None) | (None, Some(corrected_title)) => {
+                let original = if current.normalized_corrected_title.is_some() {
+                    &other.normalized_title
+                } else {
+                    &current.normalized_title
+                };
+                return self.config.link_corrections
+                    && !corrected_title.is_empty()
+                    && self
+                        .similarity
+                        .score(corrected_title, original, jaro_winkler)
+                        >= self.config.title_threshold;
+            }
+            _ => {}
+        }
+
+        // The same abstract, exported twice, is the same record
+        if current.abstract_hash.is_some() && current.abstract_hash == other.abstract_hash {
+            return true;
+        }
+
+        if self.config.link_preprints
+            && Self::is_preprint(current.original) != Self::is_preprint(other.original)
+            && authors_match
+            && current
+                .original
+                .year
+                .zip(other.original.year)
+                .is_none_or(|(a, b)| (a - b).abs() <= 2)
+            && self.title_similarity(current, other, jaro_winkler) >= self.config.title_threshold
+        {
+            return true;
+        }
+
+        match (&current.original.doi, &other.original.doi) {
+            // With DOIs
+            (Some(doi1), Some(doi2)) if !doi1.is_empty() && !doi2.is_empty() => {
+                let title_similarity = self.title_similarity(current, other, jaro);
+                // Records listing several DOIs match when any of them is shared
+                let dois_match = doi1 == doi2
+                    || current
+                        .original
+                        .dois()
+                        .any(|doi| other.original.dois().any(|other_doi| doi == other_doi));
+
+                // With Journal/ISSN match
+                (dois_match && title_similarity >= self.config.doi_title_threshold && (journal_match || issns_match))
+                // Without Journal/ISSN match: only when we have same DOI (and we use volume/pages instead)
+                || (dois_match && title_similarity >= 0.99 && (volumes_match || pages_match))
+                // Without DOI match: only when we have a very high title similarity and all other fields match
+                || (title_similarity >= 0.99 && years_match && (volumes_match || pages_match) && (journal_match || issns_match)
+                    && self.abstracts_agree(current, other, title_similarity, 0.99))
+            }
+            // Without DOIs
+            _ => {
+                let title_similarity = self.title_similarity(current, other, jaro_winkler);
+
+                // With Journal/ISSN match
+                ((title_similarity >= self.config.title_threshold && (volumes_match || pages_match) && (journal_match || issns_match))
+                // The same journal, volume and first page locate the same article
+                || (title_similarity >= self.config.locator_title_threshold && volumes_match && pages_match && (journal_match || issns_match))
+                // Without Journal/ISSN match: only when we have a very high title similarity and all other fields match
+                || (title_similarity >= 0.99 && years_match && (volumes_match && pages_match))
+                // Without volume/pages or journal: only when enabled and the first authors agree
+                || (self.config.match_on_authors && title_similarity >= self.config.title_threshold && years_match && authors_match))
+                // Distinct works sharing a title are told apart by their abstracts, when enabled
+                && self.abstracts_agree(current, other, title_similarity, self.config.title_threshold)
+            }
+        }
+    }
+
+    /// Title similarity of two citations and the threshold it is held to, as
+    /// compared by `is_duplicate`
+    fn title_score(
+        &self,
+        current: &PreprocessedCitation,
+        other: &PreprocessedCitation,
+    ) -> (f64, f64) {
+        let has_doi =
+            |c: &PreprocessedCitation| c.original.doi.as_deref().is_some_and(|doi| !doi.is_empty());
+        if has_doi(current) && has_doi(other) {
+            (
+                self.title_similarity(current, other, jaro),
+                self.config.doi_title_threshold,
+            )
+        } else {
+            (
+                self.title_similarity(current, other, jaro_winkler),
+                self.config.title_threshold,
+            )
+        }
+    }
+
+    /// Checks whether a citation is a preprint, from its DOI prefix or journal
+    fn is_preprint(citation: &Citation) -> bool {
+        const PREPRINT_DOI_PREFIXES: [&str; 8] = [
+            "10.1101/",  // bioRxiv, medRxiv
+            "10.48550/", // arXiv
+            "10.21203/", // Research Square
+            "10.20944/", // Preprints.org
+            "10.31219/", // OSF Preprints
+            "10.31234/", // PsyArXiv
+            "10.31235/", // SocArXiv
+            "10.2139/",  // SSRN
+        ];
+        const PREPRINT_SERVERS: [&str; 5] = ["biorxiv", "medrxiv", "arxiv", "ssrn", "preprint"];
+
+        citation.doi.as_deref().is_some_and(|doi| {
+            let doi = doi.to_lowercase();
+            PREPRINT_DOI_PREFIXES
+                .iter()
+                .any(|prefix| doi.starts_with(prefix))
+        }) || citation.journal.as_deref().is_some_and(|journal| {
+            let journal = journal.to_lowercase();
+            PREPRINT_SERVERS
+                .iter()
+                .any(|server| journal.contains(server))
+        })
+    }
+
+    /// Checks that the weighted title and abstract similarity reaches `threshold`.
+    ///
+    /// Always true when abstract comparison is disabled or either citation has
+    /// no abstract.
+    fn abstracts_agree(
+        &self,
+        current: &PreprocessedCitation,
+        other: &PreprocessedCitation,
+        title_similarity: f64,
+        threshold: f64,
+    ) -> bool {
+        let weight = self.config.abstract_weight.clamp(0.0, 1.0);
+        match (&current.normalized_abstract, &other.normalized_abstract) {
+            (Some(a), Some(b)) if weight > 0.0 => {
+                let abstract_similarity = self.similarity.score(a, b, jaro_winkler);
+                (1.0 - weight) * title_similarity + weight * abstract_similarity >= threshold
+            }
+            _ => true,
+        }
+    }
+
+    /// Compares normalized titles, and main titles when `compare_main_titles` is set,
+    /// with `metric` unless a similarity was set, scaled by the weight of the
+    /// shared title words when they are weighted
+    fn title_similarity(
+        &self,
+        current: &PreprocessedCitation,
+        other: &PreprocessedCitation,
+        metric: fn(&str, &str) -> f64,
+    ) -> f64 {
+        let similarity =
+            self.similarity
+                .score(&current.normalized_title, &other.normalized_title, metric);
+        let similarity = if self.config.compare_main_titles {
+            similarity.max(self.similarity.score(
+                &current.normalized_main_title,
+                &other.normalized_main_title,
+                metric,
+            ))
+        } else {
+            similarity
+        };
+        match (&current.title_tokens, &other.title_tokens) {
+            (Some(a), Some(b)) => similarity * Self::title_token_overlap(a, b),
+            _ => similarity,
+        }
+    }
+
+    /// Input positions of the citations of each year, in input order
+    fn group_by_year(citations: &[Citation]) -> HashMap<i32, Vec<usize>> {
+        let mut year_map: HashMap<i32, Vec<usize>> = HashMap::new();
+
+        for (i, citation) in citations.iter().enumerate() {
+            let year = citation.year.unwrap_or(0);
+            year_map.entry(year).or_default().push(i);
+        }
+
+        year_map
+    }
+
+    /// Removes a leading article listed in `leading_articles` from a title.
+    ///
+    /// The title is returned unchanged if nothing would remain.
+    fn strip_leading_article<'t>(&self, title: &'t str) -> &'t str {
+        let title = title.trim_start();
+        let first_word = title.split_whitespace().next().unwrap_or_default();
+        for article in &self.config.leading_articles {
+            let rest = if article.ends_with('\'') {
+                first_word
+                    .get(..article.len())
+                    .filter(|start| start.eq_ignore_ascii_case(article))
+                    .map(|_| &title[article.len()..])
+            } else if first_word.eq_ignore_ascii_case(article) {
+                Some(&title[first_word.len()..])
+            } else {
+                None
+            };
+            if let Some(rest) = rest.map(str::trim_start).filter(|rest| !rest.is_empty()) {
+                return rest;
+            }
+        }
+        title
+    }
+
+    /// Checks that two authors' given names start with the same letter, when
+    /// both have one
+    fn initials_agree(a: &crate::Author, b: &crate::Author) -> bool {
+        match (Self::first_initial(a), Self::first_initial(b)) {
+            (Some(x), Some(y)) => x == y,
+            _ => true,
+        }
+    }
+
+    /// The first letter of an author's given name, normalized like surnames
+    fn first_initial(author: &crate::Author) -> Option<String> {
+        author
+            .given_name
+            .chars()
+            .find(|c| c.is_alphabetic())
+            .map(|c| normalize_surname(&c.to_string()))
+    }
+
+    fn convert_unicode_string(input: &str) -> String {
+        UNICODE_REGEX
+            .replace_all(input, |caps: &regex::Captures| {
                 u32::from_str_radix(&caps[1], 16)
                     .ok()
                     .and_then(char::from_u32)
@@ -581,19 +2498,36 @@ impl Deduplicator {
         journal1
             .as_ref()
             .zip(journal2.as_ref())
-            .map_or(false, |(j1, j2)| j1 == j2)
+            .is_some_and(|(j1, j2)| j1 == j2)
             || journal_abbr1
                 .as_ref()
                 .zip(journal_abbr2.as_ref())
-                .map_or(false, |(a1, a2)| a1 == a2)
+                .is_some_and(|(a1, a2)| a1 == a2)
             || journal1
                 .as_ref()
                 .zip(journal_abbr2.as_ref())
-                .map_or(false, |(j1, a2)| j1 == a2)
+                .is_some_and(|(j1, a2)| j1 == a2)
             || journal_abbr1
                 .as_ref()
                 .zip(journal2.as_ref())
-                .map_or(false, |(a1, j2)| a1 == j2)
+                .is_some_and(|(a1, j2)| a1 == j2)
+    }
+
+    /// Normalizes the configured journal aliases so they can be looked up by
+    /// normalized journal name
+    fn normalized_journal_aliases(&self) -> HashMap<String, String> {
+        self.config
+            .journal_aliases
+            .iter()
+            .filter_map(|(abbr, full)| {
+                Self::format_journal_name(Some(abbr)).zip(Self::format_journal_name(Some(full)))
+            })
+            .collect()
+    }
+
+    /// Normalizes a journal name, resolving abbreviations to their full title
+    fn normalize_journal(name: Option<&str>, aliases: &HashMap<String, String>) -> Option<String> {
+        Self::format_journal_name(name).map(|name| aliases.get(&name).cloned().unwrap_or(name))
     }
 
     fn format_journal_name(full_name: Option<&str>) -> Option<String> {
@@ -635,16 +2569,142 @@ impl Deduplicator {
         }
     }
 
-    fn match_issns(list1: &Vec<String>, list2: &Vec<String>) -> bool {
+    fn match_issns(list1: &[String], list2: &[String]) -> bool {
         list1
             .iter()
             .any(|isbn1| list2.iter().any(|isbn2| isbn1 == isbn2))
     }
 }
 
+/// Writes duplicate groups as CSV for manual review.
+///
+/// Each citation is written on its own row with the columns `group_id`, `role`
+/// (`unique` or `duplicate`), `id`, `source`, `title`, `year` and `doi`. Groups are
+/// numbered from 1 in the order they are given.
+///
+/// # Arguments
+///
+/// * `groups` - The duplicate groups, as returned by [`Deduplicator::find_duplicates`]
+/// * `writer` - Destination for the CSV output
+///
+/// # Errors
+///
+/// Returns `CitationError` if writing fails
+///
+/// # Examples
+///
+/// ```
+/// use biblib::dedupe::{groups_to_csv, Deduplicator};
+/// use biblib::Citation;
+///
+/// let citations = vec![Citation {
+///     id: "1".to_string(),
+///     title: "Example Title".to_string(),
+///     ..Default::default()
+/// }];
+/// let groups = Deduplicator::new().find_duplicates(&citations).unwrap();
+///
+/// let mut output = Vec::new();
+/// groups_to_csv(&groups, &mut output).unwrap();
+/// assert!(String::from_utf8(output).unwrap().starts_with("group_id,role,"));
+/// ```
+#[cfg(feature = "csv")]
+pub fn groups_to_csv<W: std::io::Write>(groups: &[DuplicateGroup], writer: W) -> crate::Result<()> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(["group_id", "role", "id", "source", "title", "year", "doi"])?;
+
+    for (index, group) in groups.iter().enumerate() {
+        let group_id = (index + 1).to_string();
+        let rows = std::iter::once(("unique", &group.unique))
+            .chain(group.duplicates.iter().map(|c| ("duplicate", c)));
+
+        for (role, citation) in rows {
+            writer.write_record([
+                group_id.as_str(),
+                role,
+                citation.id.as_str(),
+                citation.source.as_deref().unwrap_or_default(),
+                citation.title.as_str(),
+                &citation.year.map(|y| y.to_string()).unwrap_or_default(),
+                citation.doi.as_deref().unwrap_or_default(),
+            ])?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Deduplicates citations and writes one RIS record per unique work.
+///
+/// Each unique citation is completed with the metadata of its duplicates, see
+/// [`DuplicateGroup::merged`], so nothing only a duplicate had is lost. Records
+/// are written in input order, ready to import into a reference manager.
+///
+/// # Arguments
+///
+/// * `citations` - The citations to deduplicate
+/// * `config` - Deduplication settings
+/// * `writer` - Destination for the RIS output
+///
+/// # Errors
+///
+/// Returns `CitationError` if deduplication or writing fails
+///
+/// # Examples
+///
+/// ```
+/// use biblib::dedupe::{export_unique_ris, DeduplicatorConfig};
+/// use biblib::Citation;
+///
+/// let citation = Citation {
+///     id: "1".to_string(),
+///     title: "Example Title".to_string(),
+///     doi: Some("10.1234/example".to_string()),
+///     journal: Some("Example Journal".to_string()),
+///     ..Default::default()
+/// };
+/// let duplicate = Citation {
+///     id: "2".to_string(),
+///     year: Some(2020),
+///     ..citation.clone()
+/// };
+///
+/// let mut output = Vec::new();
+/// export_unique_ris(&[citation, duplicate], DeduplicatorConfig::default(), &mut output)
+///     .unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert_eq!(output.matches("ER  - ").count(), 1);
+/// assert!(output.contains("PY  - 2020"));
+/// ```
+#[cfg(feature = "ris")]
+pub fn export_unique_ris<W: std::io::Write>(
+    citations: &[Citation],
+    config: DeduplicatorConfig,
+    writer: W,
+) -> crate::Result<()> {
+    let groups = Deduplicator::new()
+        .with_config(config)
+        .find_duplicates(citations)
+        .map_err(|e| crate::CitationError::Other(e.to_string()))?;
+    let unique: Vec<Citation> = groups.iter().map(DuplicateGroup::merged).collect();
+    crate::ris::write_ris(&unique, writer)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{Author, AuthorRole};
+
+    /// A citation with the given id, title and year, for tests to fill in further
+    fn base_citation(id: &str, title: &str, year: i32) -> Citation {
+        Citation {
+            id: id.to_string(),
+            title: title.to_string(),
+            year: Some(year),
+            ..Default::default()
+        }
+    }
 
     #[test]
     fn test_group_by_year() {
@@ -1031,4 +3091,1462 @@ mod tests {
         assert_eq!(duplicate_groups[0].unique.id, "2"); // citation with abstract
         assert_eq!(duplicate_groups[0].duplicates[0].id, "1");
     }
+
+    #[test]
+    fn test_overrides() {
+        let citations = vec![
+            Citation {
+                id: "1".to_string(),
+                title: "Title 1".to_string(),
+                doi: Some("10.1234/abc".to_string()),
+                journal: Some("Journal 1".to_string()),
+                year: Some(2020),
+                ..Default::default()
+            },
+            Citation {
+                id: "2".to_string(),
+                title: "Title 1".to_string(),
+                doi: Some("10.1234/abc".to_string()),
+                journal: Some("Journal 1".to_string()),
+                year: Some(2020),
+                ..Default::default()
+            },
+            Citation {
+                id: "3".to_string(),
+                title: "Completely Different".to_string(),
+                year: Some(2020),
+                ..Default::default()
+            },
+        ];
+
+        // Identical records kept apart
+        let deduplicator = Deduplicator::new().with_overrides(vec![(
+            "2".to_string(),
+            "1".to_string(),
+            Decision::NotDuplicate,
+        )]);
+        let duplicate_groups = deduplicator.find_duplicates(&citations).unwrap();
+        assert_eq!(duplicate_groups.len(), 3);
+        assert!(duplicate_groups.iter().all(|g| g.duplicates.is_empty()));
+
+        // Unrelated records forced together
+        let deduplicator = Deduplicator::new().with_overrides(vec![(
+            "1".to_string(),
+            "3".to_string(),
+            Decision::Duplicate,
+        )]);
+        let duplicate_groups = deduplicator.find_duplicates(&citations).unwrap();
+        assert_eq!(duplicate_groups.len(), 1);
+        assert_eq!(duplicate_groups[0].duplicates.len(), 2);
+    }
+
+    #[test]
+    fn test_journal_aliases() {
+        let citations = vec![
+            Citation {
+                id: "1".to_string(),
+                title: "Outcomes of adjuvant therapy".to_string(),
+                journal: Some("Journal of Clinical Oncology".to_string()),
+                volume: Some("12".to_string()),
+                year: Some(1994),
+                ..Default::default()
+            },
+            Citation {
+                id: "2".to_string(),
+                title: "Outcomes of adjuvant therapy".to_string(),
+                journal_abbr: Some("J. Clin. Oncol.".to_string()),
+                volume: Some("12".to_string()),
+                year: Some(1994),
+                ..Default::default()
+            },
+        ];
+
+        let duplicate_groups = Deduplicator::new().find_duplicates(&citations).unwrap();
+        assert_eq!(duplicate_groups.len(), 2);
+
+        let config = DeduplicatorConfig {
+            group_by_year: true,
+            journal_aliases: HashMap::from([(
+                "J Clin Oncol".to_string(),
+                "Journal of Clinical Oncology".to_string(),
+            )]),
+            ..Default::default()
+        };
+        let deduplicator = Deduplicator::new().with_config(config);
+        let duplicate_groups = deduplicator.find_duplicates(&citations).unwrap();
+        assert_eq!(duplicate_groups.len(), 1);
+        assert_eq!(duplicate_groups[0].duplicates.len(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "csv")]
+    fn test_groups_to_csv() {
+        let groups = vec![
+            DuplicateGroup {
+                unique: Citation {
+                    id: "1".to_string(),
+                    title: "Title, with comma".to_string(),
+                    source: Some("PubMed".to_string()),
+                    year: Some(2020),
+                    doi: Some("10.1234/abc".to_string()),
+                    ..Default::default()
+                },
+                duplicates: vec![Citation {
+                    id: "2".to_string(),
+                    title: "Title with comma".to_string(),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+            DuplicateGroup {
+                unique: Citation {
+                    id: "3".to_string(),
+                    title: "Other".to_string(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ];
+
+        let mut output = Vec::new();
+        groups_to_csv(&groups, &mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "group_id,role,id,source,title,year,doi\n\
+             1,unique,1,PubMed,\"Title, with comma\",2020,10.1234/abc\n\
+             1,duplicate,2,,Title with comma,,\n\
+             2,unique,3,,Other,,\n"
+        );
+    }
+
+    #[test]
+    fn test_exact_duplicates() {
+        let citation = |id: &str, title: &str| Citation {
+            journal: Some("Journal 1".to_string()),
+            volume: Some("3".to_string()),
+            ..base_citation(id, title, 2020)
+        };
+        let citations = vec![
+            citation("1", "Title 1"),
+            citation("2", "Title 1."),
+            citation("3", "TITLE 1"),
+            citation("4", "Something else"),
+        ];
+
+        let duplicate_groups = Deduplicator::new().find_duplicates(&citations).unwrap();
+        assert_eq!(duplicate_groups.len(), 2);
+        let group = duplicate_groups
+            .iter()
+            .find(|g| g.unique.id == "1")
+            .unwrap();
+        let ids: Vec<_> = group.duplicates.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["2", "3"]);
+
+        // Identical records that the matching criteria would not group stay apart
+        let sparse = vec![
+            Citation {
+                id: "1".to_string(),
+                title: "Title 1".to_string(),
+                ..Default::default()
+            },
+            Citation {
+                id: "2".to_string(),
+                title: "Title 1".to_string(),
+                ..Default::default()
+            },
+        ];
+        let duplicate_groups = Deduplicator::new().find_duplicates(&sparse).unwrap();
+        assert_eq!(duplicate_groups.len(), 2);
+    }
+
+    #[test]
+    fn test_exact_duplicates_match_pairwise() {
+        let group_ids = |groups: Vec<DuplicateGroup>| -> Vec<Vec<String>> {
+            groups
+                .into_iter()
+                .map(|g| {
+                    std::iter::once(g.unique.id)
+                        .chain(g.duplicates.into_iter().map(|c| c.id))
+                        .collect()
+                })
+                .collect()
+        };
+        // Citations with an override skip the hashing pass, so overriding every
+        // citation against an absent one compares all of them pairwise
+        let compare = |config: DeduplicatorConfig, citations: &[Citation]| {
+            let hashed = Deduplicator::new()
+                .with_config(config.clone())
+                .find_duplicates(citations)
+                .unwrap();
+            let overrides = citations
+                .iter()
+                .map(|c| (c.id.clone(), "absent".to_string(), Decision::NotDuplicate))
+                .collect();
+            let pairwise = Deduplicator::new()
+                .with_config(config)
+                .with_overrides(overrides)
+                .find_duplicates(citations)
+                .unwrap();
+            let hashed = group_ids(hashed);
+            assert_eq!(hashed, group_ids(pairwise));
+            hashed
+        };
+
+        let author = |id: &str, family: &str, given: &str| Citation {
+            authors: vec![Author {
+                family_name: family.to_string(),
+                given_name: given.to_string(),
+                ..Default::default()
+            }],
+            ..base_citation(id, "Sleep and memory in adolescents", 2020)
+        };
+        let citations = [
+            author("1", "Garcia", "J"),
+            author("2", "García", "J"),
+            author("3", "García", "P"),
+        ];
+        let config = DeduplicatorConfig {
+            match_on_authors: true,
+            ..Default::default()
+        };
+        assert_eq!(compare(config, &citations), vec![vec!["1", "2"], vec!["3"]]);
+
+        let titled = |id: &str, title: &str| Citation {
+            journal: Some("Age and Ageing".to_string()),
+            volume: Some("48".to_string()),
+            ..base_citation(id, title, 2019)
+        };
+        let citations = [
+            titled("1", "Hip fracture rehabilitation"),
+            titled(
+                "2",
+                "Hip fracture rehabilitation a randomised controlled trial",
+            ),
+            titled(
+                "3",
+                "Hip fracture rehabilitation: a randomised controlled trial",
+            ),
+        ];
+        let config = DeduplicatorConfig {
+            compare_main_titles: true,
+            ..Default::default()
+        };
+        assert_eq!(compare(config, &citations), vec![vec!["1", "3"], vec!["2"]]);
+    }
+
+    #[test]
+    fn test_config_presets() {
+        let author = Author {
+            family_name: "Smith".to_string(),
+            given_name: "J".to_string(),
+            ..Default::default()
+        };
+        let citations = vec![
+            Citation {
+                id: "1".to_string(),
+                title: "Outcomes of adjuvant therapy in breast cancer".to_string(),
+                authors: vec![author.clone()],
+                year: Some(1994),
+                ..Default::default()
+            },
+            Citation {
+                id: "2".to_string(),
+                title: "Outcomes of adjuvant therapy in breast cancer patients".to_string(),
+                authors: vec![author],
+                year: Some(1994),
+                ..Default::default()
+            },
+        ];
+
+        let groups = Deduplicator::new().find_duplicates(&citations).unwrap();
+        assert_eq!(groups.len(), 2);
+
+        let aggressive = Deduplicator::new().with_config(DeduplicatorConfig::aggressive());
+        let groups = aggressive.find_duplicates(&citations).unwrap();
+        assert_eq!(groups.len(), 1);
+
+        let conservative = Deduplicator::new().with_config(DeduplicatorConfig::conservative());
+        let groups = conservative.find_duplicates(&citations).unwrap();
+        assert_eq!(groups.len(), 2);
+    }
+
+    #[test]
+    fn test_conservative_title_threshold() {
+        let citations = vec![
+            Citation {
+                id: "1".to_string(),
+                title: "Outcomes of adjuvant therapy in breast cancer".to_string(),
+                journal: Some("Journal of Clinical Oncology".to_string()),
+                volume: Some("12".to_string()),
+                year: Some(1994),
+                ..Default::default()
+            },
+            Citation {
+                id: "2".to_string(),
+                title: "Outcomes of adjuvant therapy in breast cancer patients".to_string(),
+                journal: Some("Journal of Clinical Oncology".to_string()),
+                volume: Some("12".to_string()),
+                year: Some(1994),
+                ..Default::default()
+            },
+        ];
+
+        let groups = Deduplicator::new().find_duplicates(&citations).unwrap();
+        assert_eq!(groups.len(), 1);
+
+        let conservative = Deduplicator::new().with_config(DeduplicatorConfig::conservative());
+        let groups = conservative.find_duplicates(&citations).unwrap();
+        assert_eq!(groups.len(), 2);
+    }
+
+    #[test]
+    fn test_compare_main_titles() {
+        let citations = vec![
+            Citation {
+                id: "1".to_string(),
+                title: "Hip fracture rehabilitation: a randomised controlled trial".to_string(),
+                journal: Some("Age and Ageing".to_string()),
+                volume: Some("48".to_string()),
+                year: Some(2019),
+                ..Default::default()
+            },
+            Citation {
+                id: "2".to_string(),
+                title: "Hip fracture rehabilitation".to_string(),
+                journal: Some("Age and Ageing".to_string()),
+                volume: Some("48".to_string()),
+                year: Some(2019),
+                ..Default::default()
+            },
+        ];
+
+        let groups = Deduplicator::new().find_duplicates(&citations).unwrap();
+        assert_eq!(groups.len(), 2);
+
+        let config = DeduplicatorConfig {
+            group_by_year: true,
+            compare_main_titles: true,
+            ..Default::default()
+        };
+        let groups = Deduplicator::new()
+            .with_config(config)
+            .find_duplicates(&citations)
+            .unwrap();
+        assert_eq!(groups.len(), 1);
+    }
+
+    #[test]
+    fn test_find_duplicates_with_progress() {
+        let citations: Vec<Citation> = (0..12)
+            .map(|i| Citation {
+                journal: Some("Sleep".to_string()),
+                volume: Some((i / 2).to_string()),
+                ..base_citation(
+                    &i.to_string(),
+                    &format!("Study number {} of sleep", i / 2),
+                    2000 + i / 4,
+                )
+            })
+            .collect();
+        let deduplicator = Deduplicator::new().with_config(DeduplicatorConfig {
+            group_by_year: true,
+            run_in_parallel: true,
+            parallel_threshold: 0,
+            ..Default::default()
+        });
+
+        let mut reported = Vec::new();
+        let mut progress = Vec::new();
+        let groups = deduplicator
+            .find_duplicates_with_progress(
+                &citations,
+                |group| reported.push(group.unique.id.clone()),
+                |processed, total| progress.push((processed, total)),
+            )
+            .unwrap();
+
+        assert_eq!(groups.len(), 6);
+        reported.sort();
+        let mut unique: Vec<String> = groups.iter().map(|g| g.unique.id.clone()).collect();
+        unique.sort();
+        assert_eq!(reported, unique);
+        assert_eq!(progress.len(), 6);
+        assert!(progress.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(progress.last(), Some(&(12, 12)));
+
+        let groups = deduplicator
+            .find_duplicates_with_progress(
+                &[],
+                |_| panic!("no groups"),
+                |_, _| panic!("no progress"),
+            )
+            .unwrap();
+        assert!(groups.is_empty());
+    }
+
+    #[test]
+    fn test_deduplicate() {
+        let citation = |id: &str, title: &str, year: i32| Citation {
+            doi: Some(format!("10.1000/{}", title.len())),
+            journal: Some("Test Journal".to_string()),
+            ..base_citation(id, title, year)
+        };
+        let citations = vec![
+            citation("1", "A study of cats", 2020),
+            citation("2", "A study of dogs and wolves", 2018),
+            citation("3", "A study of cats", 2020),
+            citation("4", "Birds", 2019),
+        ];
+
+        let deduplicator = Deduplicator::new();
+        let unique = deduplicator.deduplicate(&citations).unwrap();
+        let ids: Vec<&str> = unique.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2", "4"]);
+
+        assert!(deduplicator.deduplicate(&[]).unwrap().is_empty());
+        assert!(unique[0].extra("merged_ids").is_none());
+    }
+
+    #[test]
+    fn test_record_merged_ids() {
+        let citation = |id: &str, source: Option<&str>| Citation {
+            doi: Some("10.1000/cats".to_string()),
+            journal: Some("Test Journal".to_string()),
+            source: source.map(String::from),
+            ..base_citation(id, "A study of cats", 2020)
+        };
+        let citations = vec![
+            citation("1", Some("PubMed")),
+            citation("2", Some("Embase")),
+            citation("3", None),
+        ];
+
+        let unique = Deduplicator::new()
+            .with_config(DeduplicatorConfig {
+                group_by_year: true,
+                record_merged_ids: true,
+                ..Default::default()
+            })
+            .deduplicate(&citations)
+            .unwrap();
+        assert_eq!(unique.len(), 1);
+        assert_eq!(unique[0].id, "1");
+        assert_eq!(
+            unique[0].extra("merged_ids"),
+            Some(&["2".to_string(), "3".to_string()][..])
+        );
+        assert_eq!(
+            unique[0].extra("merged_sources"),
+            Some(&["Embase".to_string(), String::new()][..])
+        );
+    }
+
+    #[test]
+    fn test_abstract_weight() {
+        let citation = |id: &str, abstract_text: &str| Citation {
+            journal: Some("Test Journal".to_string()),
+            volume: Some("3".to_string()),
+            abstract_text: Some(abstract_text.to_string()),
+            ..base_citation(id, "Annual report of the committee", 2010)
+        };
+        let citations = vec![
+            citation(
+                "1",
+                "Membership grew by ten percent and two new chapters opened.",
+            ),
+            citation(
+                "2",
+                "We review funding decisions on rare disease research in Europe.",
+            ),
+            citation(
+                "3",
+                "Membership grew by ten percent and two new chapters opened!",
+            ),
+        ];
+
+        let groups = Deduplicator::new().find_duplicates(&citations).unwrap();
+        assert_eq!(groups.len(), 1);
+
+        let config = DeduplicatorConfig {
+            group_by_year: true,
+            abstract_weight: 0.5,
+            ..Default::default()
+        };
+        let mut groups = Deduplicator::new()
+            .with_config(config)
+            .find_duplicates(&citations)
+            .unwrap();
+        groups.sort_by(|a, b| a.unique.id.cmp(&b.unique.id));
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].unique.id, "1");
+        assert_eq!(groups[0].duplicates[0].id, "3");
+    }
+
+    #[test]
+    fn test_link_preprints() {
+        let author = |family: &str| Author {
+            family_name: family.to_string(),
+            given_name: "A".to_string(),
+            ..Default::default()
+        };
+        let citations = vec![
+            Citation {
+                id: "preprint".to_string(),
+                title: "Gut microbiome shifts in early sepsis".to_string(),
+                authors: vec![author("Nguyen")],
+                journal: Some("bioRxiv".to_string()),
+                year: Some(2021),
+                doi: Some("10.1101/2021.01.01.123456".to_string()),
+                ..Default::default()
+            },
+            Citation {
+                id: "published".to_string(),
+                title: "Gut microbiome shifts in early sepsis".to_string(),
+                authors: vec![author("Nguyen")],
+                journal: Some("Nature Medicine".to_string()),
+                year: Some(2022),
+                volume: Some("28".to_string()),
+                doi: Some("10.1038/s41591-022-00001-1".to_string()),
+                ..Default::default()
+            },
+            Citation {
+                id: "other".to_string(),
+                title: "Gut microbiome shifts in early sepsis".to_string(),
+                authors: vec![author("Garcia")],
+                journal: Some("medRxiv".to_string()),
+                year: Some(2021),
+                ..Default::default()
+            },
+        ];
+
+        let groups = Deduplicator::new().find_duplicates(&citations).unwrap();
+        assert_eq!(groups.len(), 3);
+
+        let deduplicator = Deduplicator::new().with_config(DeduplicatorConfig {
+            link_preprints: true,
+            ..Default::default()
+        });
+        let groups = deduplicator.find_duplicates(&citations).unwrap();
+        assert_eq!(groups.len(), 2);
+        let linked = groups.iter().find(|g| !g.duplicates.is_empty()).unwrap();
+        assert_eq!(linked.unique.id, "published");
+        assert_eq!(linked.duplicates[0].id, "preprint");
+        assert_eq!(linked.preprint_ids, vec!["preprint"]);
+    }
+
+    #[test]
+    fn test_any_shared_doi_matches() {
+        let citation = |id: &str, doi: &str, all_dois: &[&str]| Citation {
+            journal: Some("Ecology".to_string()),
+            doi: Some(doi.to_string()),
+            all_dois: all_dois.iter().map(|d| d.to_string()).collect(),
+            ..base_citation(id, "Shared data in ecology", 2020)
+        };
+        let citations = vec![
+            citation("1", "10.1000/eco.1", &["10.1000/eco.1", "10.5061/dryad.1"]),
+            citation("2", "10.5061/dryad.1", &["10.5061/dryad.1"]),
+            citation("3", "10.1000/eco.2", &["10.1000/eco.2"]),
+        ];
+
+        let groups = Deduplicator::new().find_duplicates(&citations).unwrap();
+        assert_eq!(groups.len(), 2);
+        let group = groups.iter().find(|g| g.unique.id == "1").unwrap();
+        assert_eq!(group.duplicates[0].id, "2");
+    }
+
+    #[test]
+    fn test_max_pairwise_limits_to_exact_matching() {
+        use std::sync::{Arc, Mutex};
+
+        let citation = |id: &str, title: &str| Citation {
+            journal: Some("Journal of Tests".to_string()),
+            volume: Some("1".to_string()),
+            ..base_citation(id, title, 2020)
+        };
+        let citations = vec![
+            citation("1", "Effects of exercise on sleep quality"),
+            citation("2", "Effects of exercise on sleep quality"),
+            citation("3", "Effect of exercise on sleep quality"),
+        ];
+
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&warnings);
+        let groups = Deduplicator::new()
+            .with_config(DeduplicatorConfig {
+                max_pairwise: Some(2),
+                ..Default::default()
+            })
+            .with_warning_handler(move |message| sink.lock().unwrap().push(message.to_string()))
+            .find_duplicates(&citations)
+            .unwrap();
+
+        // Only the exact duplicates are grouped
+        assert_eq!(groups.len(), 2);
+        assert_eq!(warnings.lock().unwrap().len(), 1);
+        assert!(warnings.lock().unwrap()[0].contains("max_pairwise"));
+
+        let groups = Deduplicator::new()
+            .with_config(DeduplicatorConfig {
+                max_pairwise: Some(3),
+                ..Default::default()
+            })
+            .find_duplicates(&citations)
+            .unwrap();
+        assert_eq!(groups.len(), 1);
+    }
+
+    #[test]
+    fn test_link_corrections() {
+        let citation = |id: &str, title: &str, source: &str| Citation {
+            journal: Some("Journal of Sleep Research".to_string()),
+            volume: Some("12".to_string()),
+            source: Some(source.to_string()),
+            ..base_citation(id, title, 2020)
+        };
+        let citations = vec![
+            citation(
+                "erratum",
+                "Erratum: Sleep duration and cardiovascular risk",
+                "PubMed",
+            ),
+            citation(
+                "original",
+                "Sleep duration and cardiovascular risk",
+                "Embase",
+            ),
+            citation("other", "Exercise and cardiovascular risk", "Embase"),
+        ];
+
+        // Corrections are kept apart by default
+        let groups = Deduplicator::new().find_duplicates(&citations).unwrap();
+        assert_eq!(groups.len(), 3);
+
+        let deduplicator = Deduplicator::new().with_config(DeduplicatorConfig {
+            link_corrections: true,
+            source_preferences: vec!["PubMed".to_string()],
+            ..Default::default()
+        });
+        let groups = deduplicator.find_duplicates(&citations).unwrap();
+        assert_eq!(groups.len(), 2);
+        let linked = groups.iter().find(|g| !g.duplicates.is_empty()).unwrap();
+        assert_eq!(linked.unique.id, "original");
+        assert_eq!(linked.correction_ids, vec!["erratum"]);
+    }
+
+    #[test]
+    fn test_leading_articles() {
+        let citation = |id: &str, title: &str| Citation {
+            journal: Some("Journal of Tests".to_string()),
+            volume: Some("3".to_string()),
+            ..base_citation(id, title, 2020)
+        };
+        let citations = vec![
+            citation("1", "The effects of light on sleep"),
+            citation("2", "Effects of light on sleep"),
+            citation("3", "L'effet de la lumière"),
+            citation("4", "Effet de la lumière"),
+        ];
+
+        let groups = Deduplicator::new().find_duplicates(&citations).unwrap();
+        assert_eq!(groups.len(), 2);
+
+        let deduplicator = Deduplicator::new().with_config(DeduplicatorConfig {
+            leading_articles: Vec::new(),
+            ..Default::default()
+        });
+        let groups = deduplicator.find_duplicates(&citations).unwrap();
+        assert_eq!(groups.len(), 4);
+
+        // A title made only of an article is kept as it is
+        assert_eq!(Deduplicator::new().strip_leading_article("The"), "The");
+        assert_eq!(Deduplicator::new().strip_leading_article("A"), "A");
+        assert_eq!(
+            Deduplicator::new().strip_leading_article("An apple"),
+            "apple"
+        );
+        assert_eq!(
+            Deduplicator::new().strip_leading_article("Analysis"),
+            "Analysis"
+        );
+    }
+
+    #[test]
+    fn test_same_accession_number_and_source() {
+        let citation = |id: &str, title: &str, source: &str| Citation {
+            accession_number: Some("2011234567".to_string()),
+            source: Some(source.to_string()),
+            ..base_citation(id, title, 2021)
+        };
+        let citations = vec![
+            citation("1", "Sleep in adolescents", "Embase"),
+            citation("2", "Sleep in adolescents: a cohort", "embase"),
+            citation("3", "Unrelated title", "MEDLINE"),
+        ];
+
+        let groups = Deduplicator::new().find_duplicates(&citations).unwrap();
+        assert_eq!(groups.len(), 2);
+        let group = groups.iter().find(|g| g.unique.id == "1").unwrap();
+        assert_eq!(group.duplicates[0].id, "2");
+    }
+
+    #[test]
+    fn test_author_order_insensitive() {
+        let author = |family: &str| Author {
+            family_name: family.to_string(),
+            given_name: "A".to_string(),
+            ..Default::default()
+        };
+        let citation = |id: &str, authors: Vec<Author>| Citation {
+            authors,
+            ..base_citation(id, "Sleep and memory consolidation", 2018)
+        };
+        let citations = vec![
+            citation(
+                "1",
+                vec![author("Zhang"), author("Alvarez"), author("Brown")],
+            ),
+            citation(
+                "2",
+                vec![author("Alvarez"), author("Brown"), author("Zhang")],
+            ),
+            citation("3", vec![author("Brown"), author("Nguyen")]),
+        ];
+
+        let config = DeduplicatorConfig {
+            match_on_authors: true,
+            ..Default::default()
+        };
+        let groups = Deduplicator::new()
+            .with_config(config.clone())
+            .find_duplicates(&citations)
+            .unwrap();
+        assert_eq!(groups.len(), 3);
+
+        let groups = Deduplicator::new()
+            .with_config(DeduplicatorConfig {
+                first_author_must_match: false,
+                ..config
+            })
+            .find_duplicates(&citations)
+            .unwrap();
+        assert_eq!(groups.len(), 2);
+        let group = groups.iter().find(|g| g.unique.id == "1").unwrap();
+        assert_eq!(group.duplicates[0].id, "2");
+    }
+
+    #[test]
+    fn test_editors_are_not_first_authors() {
+        let person = |name: &str, role: Option<AuthorRole>| Author {
+            family_name: name.to_string(),
+            given_name: "A".to_string(),
+            role,
+            ..Default::default()
+        };
+        let citation = |id: &str, authors: Vec<Author>| Citation {
+            authors,
+            ..base_citation(id, "Sleep and memory consolidation", 2018)
+        };
+        let citations = vec![
+            citation(
+                "1",
+                vec![
+                    person("Doe", Some(AuthorRole::Editor)),
+                    person("Zhang", None),
+                ],
+            ),
+            citation("2", vec![person("Zhang", None)]),
+        ];
+
+        let groups = Deduplicator::new()
+            .with_config(DeduplicatorConfig {
+                match_on_authors: true,
+                ..Default::default()
+            })
+            .find_duplicates(&citations)
+            .unwrap();
+        assert_eq!(groups.len(), 1);
+    }
+
+    #[test]
+    fn test_dedupe_sources() {
+        let citation = |id: &str, title: &str, doi: &str| Citation {
+            doi: Some(doi.to_string()),
+            journal: Some("Test Journal".to_string()),
+            ..base_citation(id, title, 2020)
+        };
+        let result = Deduplicator::new()
+            .dedupe_sources(vec![
+                (
+                    "PubMed".to_string(),
+                    vec![
+                        citation("1", "A study of cats", "10.1000/cats"),
+                        citation("2", "Dogs in the clinic", "10.1000/dogs"),
+                    ],
+                ),
+                (
+                    "Embase".to_string(),
+                    vec![citation("3", "A study of cats", "10.1000/cats")],
+                ),
+                (
+                    "PubMed".to_string(),
+                    vec![citation("4", "Birds at home", "10.1000/birds")],
+                ),
+            ])
+            .unwrap();
+
+        let ids: Vec<&str> = result.unique.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, ["1", "2", "4"]);
+        assert_eq!(result.unique[0].source.as_deref(), Some("PubMed"));
+        assert_eq!(result.groups.len(), 3);
+        assert_eq!(result.total_records(), 4);
+        assert_eq!(result.duplicates_removed(), 1);
+        assert_eq!(
+            result.sources,
+            vec![
+                SourceSummary {
+                    source: "PubMed".to_string(),
+                    records: 3,
+                    kept: 3,
+                    duplicates_removed: 0,
+                    only_in_source: 2,
+                },
+                SourceSummary {
+                    source: "Embase".to_string(),
+                    records: 1,
+                    kept: 0,
+                    duplicates_removed: 1,
+                    only_in_source: 0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parallel_threshold() {
+        let deduplicator = |parallel_threshold: usize| {
+            Deduplicator::new().with_config(DeduplicatorConfig {
+                group_by_year: true,
+                run_in_parallel: true,
+                parallel_threshold,
+                ..Default::default()
+            })
+        };
+        assert!(!deduplicator(PARALLEL_THRESHOLD).runs_in_parallel(20));
+        assert!(deduplicator(PARALLEL_THRESHOLD).runs_in_parallel(PARALLEL_THRESHOLD));
+        assert!(deduplicator(0).runs_in_parallel(20));
+        assert!(!Deduplicator::new().runs_in_parallel(PARALLEL_THRESHOLD));
+    }
+
+    #[test]
+    fn test_parallel_output_order() {
+        let citations: Vec<Citation> = (0..40)
+            .map(|i| Citation {
+                doi: Some(format!("10.1000/{}", i % 20)),
+                journal: Some("Test Journal".to_string()),
+                ..base_citation(
+                    &i.to_string(),
+                    &format!("Study number {} of many", i % 20),
+                    2000 + (i * 7) % 13,
+                )
+            })
+            .collect();
+        let by_year = |run_in_parallel: bool| {
+            Deduplicator::new().with_config(DeduplicatorConfig {
+                group_by_year: true,
+                run_in_parallel,
+                parallel_threshold: 0,
+                ..Default::default()
+            })
+        };
+        let group_ids = |groups: Vec<DuplicateGroup>| -> Vec<Vec<String>> {
+            groups
+                .into_iter()
+                .map(|g| {
+                    std::iter::once(g.unique.id)
+                        .chain(g.duplicates.into_iter().map(|c| c.id))
+                        .collect()
+                })
+                .collect()
+        };
+
+        let sequential = by_year(false).deduplicate(&citations).unwrap();
+        let parallel = by_year(true).deduplicate(&citations).unwrap();
+        assert_eq!(format!("{:?}", sequential), format!("{:?}", parallel));
+
+        let sequential = group_ids(by_year(false).find_duplicates(&citations).unwrap());
+        let parallel = group_ids(by_year(true).find_duplicates(&citations).unwrap());
+        assert_eq!(sequential, parallel);
+        let firsts: Vec<usize> = parallel
+            .iter()
+            .map(|ids| ids.iter().map(|id| id.parse().unwrap()).min().unwrap())
+            .collect();
+        assert!(firsts.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_output_order_without_unique_ids() {
+        // Records merged from several files often share ids, or have none
+        let citations: Vec<Citation> = (0..12)
+            .map(|i| Citation {
+                title: format!("Distinct study {i} on a topic"),
+                journal: Some("Test Journal".to_string()),
+                year: Some(2000 + (i * 5) % 7),
+                ..Default::default()
+            })
+            .collect();
+        let deduplicator = Deduplicator::new().with_config(DeduplicatorConfig {
+            group_by_year: true,
+            ..Default::default()
+        });
+
+        let titles: Vec<String> = deduplicator
+            .deduplicate(&citations)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.title)
+            .collect();
+        let expected: Vec<String> = citations.iter().map(|c| c.title.clone()).collect();
+        assert_eq!(titles, expected);
+
+        let groups = deduplicator.group_duplicates(&citations).unwrap();
+        let titles: Vec<String> = groups.into_iter().map(|g| g.unique.title).collect();
+        assert_eq!(titles, expected);
+    }
+
+    #[test]
+    fn test_normalize_surnames() {
+        assert_eq!(normalize_surname("García-López"), "garcialopez");
+        assert_eq!(normalize_surname("GARCIA LOPEZ"), "garcialopez");
+        assert_eq!(normalize_surname("Müller"), "muller");
+        assert_eq!(normalize_surname("Lefe\u{300}vre"), "lefevre");
+        assert_ne!(
+            normalize_surname("García-López"),
+            normalize_surname("García")
+        );
+
+        let citation = |id: &str, family: &str, given: &str| Citation {
+            authors: vec![Author {
+                family_name: family.to_string(),
+                given_name: given.to_string(),
+                ..Default::default()
+            }],
+            ..base_citation(id, "Sleep and memory consolidation", 2018)
+        };
+        let citations = vec![
+            citation("1", "García-López", "María"),
+            citation("2", "Garcia Lopez", "M."),
+            citation("3", "GarciaLopez", ""),
+            // Same name once normalized, but a different initial
+            citation("4", "Garcia-Lopez", "J"),
+        ];
+        let config = DeduplicatorConfig {
+            match_on_authors: true,
+            ..Default::default()
+        };
+
+        let groups = Deduplicator::new()
+            .with_config(config.clone())
+            .find_duplicates(&citations)
+            .unwrap();
+        assert_eq!(groups.len(), 2);
+        let group = groups.iter().find(|g| g.unique.id == "1").unwrap();
+        let ids: Vec<_> = group.duplicates.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["2", "3"]);
+
+        let groups = Deduplicator::new()
+            .with_config(DeduplicatorConfig {
+                normalize_surnames: false,
+                ..config
+            })
+            .find_duplicates(&citations)
+            .unwrap();
+        assert_eq!(groups.len(), 4);
+    }
+
+    #[test]
+    fn test_arxiv_id_matching() {
+        let citation = |id: &str, title: &str, arxiv_id: &str| Citation {
+            arxiv_id: Some(arxiv_id.to_string()),
+            ..base_citation(id, title, 2017)
+        };
+        let citations = vec![
+            citation("1", "Attention is all you need", "1706.03762v1"),
+            citation("2", "Attention is all you need (revised)", "1706.03762v5"),
+            citation("3", "Attention is all you need", "1706.03763"),
+        ];
+
+        let duplicate_groups = Deduplicator::new().find_duplicates(&citations).unwrap();
+        assert_eq!(duplicate_groups.len(), 2);
+        let group = duplicate_groups
+            .iter()
+            .find(|g| g.unique.id == "1")
+            .unwrap();
+        let ids: Vec<_> = group.duplicates.iter().map(|c| c.id.as_str()).collect();
+        assert_eq!(ids, vec!["2"]);
+    }
+
+    #[test]
+    fn test_respect_type_differences() {
+        let citation = |id: &str, citation_type: &[&str], pages: &str| Citation {
+            citation_type: citation_type.iter().map(|t| t.to_string()).collect(),
+            journal: Some("Biological Psychiatry".to_string()),
+            volume: Some("85".to_string()),
+            pages: Some(pages.to_string()),
+            ..base_citation(id, "Ketamine for treatment-resistant depression", 2019)
+        };
+        let citations = vec![
+            citation("abstract", &["Journal Article", "Meeting Abstract"], "S12"),
+            citation("paper", &["Journal Article"], "S12-S20"),
+            citation("poster", &["ABST"], "S12"),
+            citation("untyped", &[], "S12"),
+        ];
+
+        // The abstracts match each other and the untyped record, but not the paper
+        let groups = Deduplicator::new().find_duplicates(&citations).unwrap();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].unique.id, "abstract");
+        assert_eq!(groups[0].duplicates.len(), 2);
+        assert_eq!(groups[1].unique.id, "paper");
+        assert!(groups[1].duplicates.is_empty());
+
+        let deduplicator = Deduplicator::new().with_config(DeduplicatorConfig {
+            respect_type_differences: false,
+            ..Default::default()
+        });
+        let groups = deduplicator.find_duplicates(&citations).unwrap();
+        assert_eq!(groups.len(), 1);
+
+        // Databases label the same record differently; a shared DOI decides
+        let mut embase = citation("embase", &["Conference Abstract"], "S12-S20");
+        embase.doi = Some("10.1016/j.biopsych.2019.03.027".to_string());
+        let mut scopus = citation("scopus", &["JOUR"], "S12-S20");
+        scopus.doi = Some("10.1016/J.BIOPSYCH.2019.03.027".to_string());
+        let groups = Deduplicator::new()
+            .find_duplicates(&[embase.clone(), scopus.clone()])
+            .unwrap();
+        assert_eq!(groups.len(), 1);
+
+        // As does a shared accession number
+        embase.doi = None;
+        scopus.doi = None;
+        embase.accession_number = Some("627654321".to_string());
+        scopus.accession_number = Some("627654321".to_string());
+        let groups = Deduplicator::new()
+            .find_duplicates(&[embase, scopus])
+            .unwrap();
+        assert_eq!(groups.len(), 1);
+    }
+
+    #[test]
+    fn test_match_identical_abstracts() {
+        let text = "Background: We studied sleep in 200 adolescents over two years. \
+                    Results: Short sleep predicted lower grades in mathematics and reading.";
+        let citation = |id: &str, title: &str, abstract_text: Option<&str>| Citation {
+            abstract_text: abstract_text.map(str::to_string),
+            ..base_citation(id, title, 2019)
+        };
+        let citations = vec![
+            citation("1", "Sleep and grades in adolescents", Some(text)),
+            citation(
+                "2",
+                "SLEEP &amp; GRADES: A COHORT",
+                Some(&text.to_uppercase()),
+            ),
+            citation("3", "Exercise and grades", Some("No abstract available")),
+            citation("4", "Diet and grades", Some("No abstract available.")),
+            citation("5", "Screen time", None),
+            citation("6", "Reading habits", None),
+        ];
+
+        let groups = Deduplicator::new().find_duplicates(&citations).unwrap();
+        assert!(groups.iter().all(|g| g.duplicates.is_empty()));
+
+        let groups = Deduplicator::new()
+            .with_config(DeduplicatorConfig {
+                match_identical_abstracts: true,
+                ..Default::default()
+            })
+            .find_duplicates(&citations)
+            .unwrap();
+        assert_eq!(groups.len(), 5);
+        let group = groups.iter().find(|g| g.unique.id == "1").unwrap();
+        assert_eq!(group.duplicates[0].id, "2");
+
+        // Also in a block too large to compare pairwise
+        let groups = Deduplicator::new()
+            .with_config(DeduplicatorConfig {
+                match_identical_abstracts: true,
+                max_pairwise: Some(1),
+                ..Default::default()
+            })
+            .find_duplicates(&citations)
+            .unwrap();
+        assert_eq!(groups.len(), 5);
+        let group = groups.iter().find(|g| g.unique.id == "1").unwrap();
+        assert_eq!(group.duplicates[0].id, "2");
+    }
+
+    #[test]
+    fn test_similarity_backends() {
+        assert_eq!(JaroWinkler.score("sleep", "sleep"), 1.0);
+        assert_eq!(Levenshtein.score("sleep", "sleet"), 0.8);
+        assert_eq!(Levenshtein.score("", ""), 1.0);
+        assert_eq!(BigramCosine.score("abab", "abab"), 1.0);
+        assert_eq!(BigramCosine.score("ab", "cd"), 0.0);
+        assert_eq!(BigramCosine.score("a", "b"), 0.0);
+        assert!(
+            BigramCosine.score("memorysleepand", "sleepandmemory")
+                > JaroWinkler.score("memorysleepand", "sleepandmemory")
+        );
+
+        struct Always(f64);
+        impl Similarity for Always {
+            fn score(&self, _: &str, _: &str) -> f64 {
+                self.0
+            }
+        }
+
+        let citation = |id: &str, title: &str| Citation {
+            journal: Some("Sleep".to_string()),
+            volume: Some("12".to_string()),
+            ..base_citation(id, title, 2020)
+        };
+        let citations = vec![
+            citation("1", "Sleep and memory"),
+            citation("2", "Diet and exercise"),
+        ];
+        let groups = Deduplicator::new().find_duplicates(&citations).unwrap();
+        assert_eq!(groups.len(), 2);
+        let groups = Deduplicator::new()
+            .with_similarity(Always(1.0))
+            .find_duplicates(&citations)
+            .unwrap();
+        assert_eq!(groups.len(), 1);
+
+        let same = vec![
+            citation("1", "Sleep and memory"),
+            citation("2", "Sleep and memory"),
+        ];
+        let groups = Deduplicator::new()
+            .with_similarity(Always(0.0))
+            .find_duplicates(&same)
+            .unwrap();
+        assert_eq!(groups.len(), 2);
+    }
+
+    #[test]
+    fn test_find_against() {
+        let citation = |id: &str, title: &str, doi: Option<&str>, year: i32| Citation {
+            doi: doi.map(String::from),
+            journal: Some("Sleep".to_string()),
+            volume: Some("12".to_string()),
+            ..base_citation(id, title, year)
+        };
+        let master = vec![
+            citation("m1", "Sleep and memory", Some("10.1/a"), 2020),
+            citation("m2", "Diet and exercise", None, 2019),
+            citation("m3", "Sleep and memory", Some("10.1/a"), 2020),
+        ];
+        let new = vec![
+            citation("n1", "Screen time in adolescents", None, 2021),
+            citation("n2", "Sleep and memory.", Some("10.1/a"), 2020),
+            citation("n3", "Diet and exercise", None, 2019),
+            // Duplicates n3, but the new batch is not deduplicated internally
+            citation("n4", "Diet and exercise", None, 2019),
+        ];
+
+        let matches = Deduplicator::new().find_against(&new, &master).unwrap();
+        assert_eq!(
+            matches,
+            vec![(0, vec![]), (1, vec![0, 2]), (2, vec![1]), (3, vec![1])]
+        );
+
+        let parallel = Deduplicator::new()
+            .with_config(DeduplicatorConfig {
+                group_by_year: true,
+                run_in_parallel: true,
+                parallel_threshold: 0,
+                ..Default::default()
+            })
+            .with_overrides(vec![(
+                "n3".to_string(),
+                "m2".to_string(),
+                Decision::NotDuplicate,
+            )])
+            .find_against(&new, &master)
+            .unwrap();
+        assert_eq!(
+            parallel,
+            vec![(0, vec![]), (1, vec![0, 2]), (2, vec![]), (3, vec![1])]
+        );
+
+        assert!(Deduplicator::new()
+            .find_against(&new, &[])
+            .unwrap()
+            .iter()
+            .all(|(_, duplicates)| duplicates.is_empty()));
+    }
+
+    #[test]
+    fn test_weight_title_tokens() {
+        let citation = |id: &str, title: &str, pages: &str| Citation {
+            journal: Some("Test Journal".to_string()),
+            volume: Some("1".to_string()),
+            pages: Some(pages.to_string()),
+            ..base_citation(id, title, 2020)
+        };
+        let mut citations = vec![
+            citation("intro1", "Introduction", "1"),
+            citation("intro2", "Introduction", "5"),
+            citation(
+                "aspirin1",
+                "Aspirin resistance in coronary disease",
+                "10-20",
+            ),
+            citation(
+                "aspirin2",
+                "Aspirin Resistance in Coronary Disease",
+                "10-20",
+            ),
+        ];
+        citations.extend((0..20).map(|i| Citation {
+            id: format!("filler{}", i),
+            title: format!("Introduction to chapter {}", i),
+            ..Default::default()
+        }));
+        let duplicate_ids = |weight_title_tokens: bool| -> Vec<String> {
+            Deduplicator::new()
+                .with_config(DeduplicatorConfig {
+                    weight_title_tokens,
+                    ..Default::default()
+                })
+                .find_duplicates(&citations)
+                .unwrap()
+                .into_iter()
+                .flat_map(|group| group.duplicates.into_iter().map(|c| c.id))
+                .collect()
+        };
+
+        assert_eq!(duplicate_ids(false), ["intro2", "aspirin2"]);
+        assert_eq!(duplicate_ids(true), ["aspirin2"]);
+
+        // Too few citations to tell common words from rare ones
+        let matches = Deduplicator::new()
+            .with_config(DeduplicatorConfig {
+                weight_title_tokens: true,
+                ..Default::default()
+            })
+            .find_against(&citations[..1], &citations[1..2])
+            .unwrap();
+        assert_eq!(matches, vec![(0, vec![0])]);
+    }
+
+    #[test]
+    fn test_weight_title_tokens_single_topic() {
+        let citation = |id: &str, title: &str| Citation {
+            doi: Some(format!("10.1000/{}", id)),
+            journal: Some("Sleep Medicine".to_string()),
+            volume: Some(id.len().to_string()),
+            pages: Some(format!("{}-10", id.len())),
+            ..base_citation(id, title, 2020)
+        };
+        let groups = ["adolescents", "older adults", "students", "shift workers"];
+        let designs = [
+            "a cohort study",
+            "a cross-sectional study",
+            "a meta-analysis",
+        ];
+        let mut citations: Vec<Citation> = (0..40)
+            .map(|i| {
+                citation(
+                    &format!("filler{}", i),
+                    &format!(
+                        "Sleep quality and depression in {}: {} {}",
+                        groups[i % 4],
+                        designs[i % 3],
+                        i
+                    ),
+                )
+            })
+            .collect();
+        let mut typo = citation(
+            "typo",
+            "Sleep quality and depresion in adolescents: a cohort study",
+        );
+        let mut original = citation(
+            "original",
+            "Sleep quality and depression in adolescents: a cohort study",
+        );
+        for c in [&mut typo, &mut original] {
+            c.doi = Some("10.1000/shared".to_string());
+            c.volume = Some("12".to_string());
+            c.pages = Some("101-109".to_string());
+        }
+        citations.push(original);
+        citations.push(typo);
+
+        let groups = Deduplicator::new()
+            .with_config(DeduplicatorConfig {
+                weight_title_tokens: true,
+                ..Default::default()
+            })
+            .find_duplicates(&citations)
+            .unwrap();
+        let merged: Vec<_> = groups
+            .iter()
+            .filter(|group| !group.duplicates.is_empty())
+            .map(|group| (group.unique.id.as_str(), group.duplicates[0].id.as_str()))
+            .collect();
+        assert_eq!(merged, [("original", "typo")]);
+    }
+
+    #[test]
+    fn test_find_duplicates_with_nearmisses() {
+        let citation = |id: &str, title: &str, doi: Option<&str>| Citation {
+            doi: doi.map(String::from),
+            journal: Some("Journal of Tests".to_string()),
+            volume: Some("1".to_string()),
+            ..base_citation(id, title, 2020)
+        };
+        let citations = vec![
+            citation("1", "Effects of aspirin on platelet aggregation", None),
+            citation("2", "Effects of aspirin on platelet aggregation.", None),
+            citation("3", "Effects of aspirin on platelet function", None),
+            citation("4", "A survey of hospital staff", None),
+        ];
+        let deduplicator = Deduplicator::new();
+
+        let (groups, near_misses) = deduplicator
+            .find_duplicates_with_nearmisses(&citations, 0.1)
+            .unwrap();
+        assert_eq!(groups.len(), 3);
+        let pairs: Vec<(&str, &str)> = near_misses
+            .iter()
+            .map(|m| (m.first.as_str(), m.second.as_str()))
+            .collect();
+        assert_eq!(pairs, vec![("1", "3"), ("2", "3")]);
+        for near_miss in &near_misses {
+            assert_eq!(
+                near_miss.threshold,
+                DeduplicatorConfig::default().title_threshold
+            );
+            assert!(near_miss.score >= near_miss.threshold - 0.1);
+            assert!(near_miss.score < near_miss.threshold);
+        }
+
+        let (_, near_misses) = deduplicator
+            .find_duplicates_with_nearmisses(&citations, 0.0)
+            .unwrap();
+        assert!(near_misses.is_empty());
+        assert!(matches!(
+            deduplicator.find_duplicates_with_nearmisses(&citations, -0.1),
+            Err(DedupeError::ConfigError(_))
+        ));
+
+        // Citations with DOIs are held to the DOI title threshold
+        let citations = vec![
+            citation("1", "Platelet aggregation after aspirin", Some("10.1000/a")),
+            citation("2", "Aspirin and platelet aggregation", Some("10.1000/a")),
+        ];
+        let (_, near_misses) = deduplicator
+            .find_duplicates_with_nearmisses(&citations, 1.0)
+            .unwrap();
+        assert_eq!(near_misses.len(), 1);
+        assert_eq!(
+            near_misses[0].threshold,
+            DeduplicatorConfig::default().doi_title_threshold
+        );
+    }
+
+    #[test]
+    fn test_first_page_matching() {
+        let citation = |id: &str, title: &str, volume: &str, pages: &str| Citation {
+            journal: Some("Journal of Tests".to_string()),
+            volume: Some(volume.to_string()),
+            pages: Some(pages.to_string()),
+            ..base_citation(id, title, 2020)
+        };
+        let title = "Effects of aspirin on platelet aggregation in coronary disease";
+        let citations = vec![
+            citation("full", title, "12", "123-130"),
+            citation("abbreviated", title, "", "123-30"),
+            citation("first", "Effects of aspirin on platelets", "12", "123"),
+            citation("elocator", title, "", "e123"),
+            citation("other", "Effects of aspirin on platelets", "12", "140-145"),
+        ];
+
+        let groups = Deduplicator::new().find_duplicates(&citations).unwrap();
+        let ids: Vec<(&str, Vec<&str>)> = groups
+            .iter()
+            .map(|group| {
+                let duplicates = group.duplicates.iter().map(|c| c.id.as_str()).collect();
+                (group.unique.id.as_str(), duplicates)
+            })
+            .collect();
+        // The shortened title only matches on journal, volume and first page
+        assert_eq!(
+            ids,
+            [
+                ("full", vec!["abbreviated", "first"]),
+                ("elocator", vec![]),
+                ("other", vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_edges() {
+        let citation = |id: &str, title: &str, year: i32| Citation {
+            doi: Some("10.1000/shared".to_string()),
+            journal: Some("Journal of Tests".to_string()),
+            ..base_citation(id, title, year)
+        };
+        let title = "Effects of aspirin on platelet aggregation";
+        let citations = vec![
+            citation("a", title, 2020),
+            citation(
+                "b",
+                "Effects of aspirin on platelet aggregation in adults",
+                2020,
+            ),
+            citation("c", title, 2020),
+            citation("d", "An unrelated study of sleep", 2020),
+            citation("e", title, 2021),
+        ];
+        let ids = |edges: Vec<(CitationId, CitationId, f64)>| -> Vec<(String, String)> {
+            edges.into_iter().map(|(a, b, _)| (a, b)).collect()
+        };
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+
+        let edges = Deduplicator::new().duplicate_edges(&citations).unwrap();
+        assert!(edges.iter().all(|&(_, _, score)| score >= 0.85));
+        assert_eq!(ids(edges), [pair("a", "b"), pair("a", "c"), pair("b", "c")]);
+
+        // Only citations of the same year are compared, and overrides apply
+        let deduplicator = Deduplicator::new()
+            .with_config(DeduplicatorConfig {
+                group_by_year: true,
+                ..Default::default()
+            })
+            .with_overrides(vec![
+                ("c".to_string(), "a".to_string(), Decision::NotDuplicate),
+                ("d".to_string(), "a".to_string(), Decision::Duplicate),
+                ("e".to_string(), "a".to_string(), Decision::Duplicate),
+            ]);
+        assert_eq!(
+            ids(deduplicator.duplicate_edges(&citations).unwrap()),
+            [pair("a", "b"), pair("a", "d"), pair("b", "c")]
+        );
+
+        // Oversized blocks only link exact duplicates
+        let deduplicator = Deduplicator::new().with_config(DeduplicatorConfig {
+            max_pairwise: Some(2),
+            ..Default::default()
+        });
+        assert_eq!(
+            ids(deduplicator.duplicate_edges(&citations).unwrap()),
+            [pair("a", "c")]
+        );
+    }
 }
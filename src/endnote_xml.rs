@@ -20,20 +20,39 @@
 //! assert_eq!(citations[0].title, "Example Title");
 //! assert_eq!(citations[0].source.as_deref(), Some("Embase"));
 //! ```
+//!
+//! Citations are written back as EndNote XML with [`write_endnote_xml`].
 
 use nanoid::nanoid;
+use quick_xml::escape::escape;
 use quick_xml::events::Event;
 use quick_xml::name::QName;
 use quick_xml::reader::Reader;
-use std::io::BufRead;
+use std::io::{BufRead, Write};
+
+use crate::utils::{
+    clean_input, extract_year, format_page_numbers, parse_author_name, push_capped,
+    split_serial_numbers, ParserOptions, WarningHandler,
+};
+use crate::{Author, AuthorRole, Citation, CitationError, CitationParser, Result};
 
-use crate::utils::{format_doi, format_page_numbers, parse_author_name, split_issns};
-use crate::{Author, Citation, CitationError, CitationParser, Result};
+/// EndNote's generic custom fields, kept in `extra_fields` under their names
+const CUSTOM_FIELDS: [&str; 8] = [
+    "custom1", "custom2", "custom3", "custom4", "custom5", "custom6", "custom7", "custom8",
+];
 
 /// Parser for EndNote XML format citations.
+///
+/// The custom fields `<custom1>` to `<custom8>`, often used for screening
+/// decisions and notes, are kept in `extra_fields` under `"custom1"` to
+/// `"custom8"`. A `<custom2>` holding a PMC ID is also read as `pmc_id`.
+///
+/// Names under `<secondary-authors>` and `<tertiary-authors>` are read as
+/// editors, and under `<subsidiary-authors>` and `<translated-authors>` as
+/// translators; both are kept in `authors` with their [`AuthorRole`].
 #[derive(Debug, Default, Clone)]
 pub struct EndNoteXmlParser {
-    source: Option<String>,
+    options: ParserOptions,
 }
 
 impl EndNoteXmlParser {
@@ -47,30 +66,113 @@ impl EndNoteXmlParser {
     /// ```
     #[must_use]
     pub fn new() -> Self {
-        Self { source: None }
+        Self::default()
     }
 
+    /// Sets the source of the parsed citations.
+    ///
+    /// Each citation's record is also named under
+    /// [`SOURCE_RECORD_KEY`](crate::SOURCE_RECORD_KEY).
     #[must_use]
     pub fn with_source(mut self, source: &str) -> Self {
-        self.source = Some(source.to_string());
+        self.options.source = Some(source.to_string());
+        self
+    }
+
+    /// Stops parsing once `limit` citations have been read.
+    ///
+    /// Useful for previewing large inputs. A limit of `0` returns no citations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::EndNoteXmlParser;
+    /// let parser = EndNoteXmlParser::new().with_limit(10);
+    /// ```
+    #[must_use]
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.options.limit = Some(limit);
+        self
+    }
+
+    /// Sets the longest field, in bytes, kept from the input.
+    ///
+    /// Longer fields are truncated at a character boundary, as they are read,
+    /// and a warning is reported through [`EndNoteXmlParser::with_warning_handler`].
+    /// Defaults to [`DEFAULT_MAX_FIELD_LENGTH`](crate::DEFAULT_MAX_FIELD_LENGTH).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::EndNoteXmlParser;
+    /// let parser = EndNoteXmlParser::new().with_max_field_length(64 * 1024);
+    /// ```
+    #[must_use]
+    pub fn with_max_field_length(mut self, max: usize) -> Self {
+        self.options.max_field_length = Some(max);
+        self
+    }
+
+    /// Sets the function receiving warnings, which are ignored otherwise.
+    ///
+    /// Warnings are reported when input is altered, for example when a field
+    /// exceeds the [maximum length](EndNoteXmlParser::with_max_field_length).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::EndNoteXmlParser;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let warnings = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = Arc::clone(&warnings);
+    /// let parser = EndNoteXmlParser::new()
+    ///     .with_warning_handler(move |message| sink.lock().unwrap().push(message.to_string()));
+    /// ```
+    #[must_use]
+    pub fn with_warning_handler(mut self, handler: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.options.warning_handler = WarningHandler::new(handler);
+        self
+    }
+
+    /// Sets whether whitespace is normalized in every field.
+    ///
+    /// When enabled, runs of whitespace, including non-breaking spaces and
+    /// line breaks, become a single space and zero-width characters are
+    /// removed, as by
+    /// [`normalize_whitespace`](crate::transform::normalize_whitespace). Off by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::EndNoteXmlParser;
+    /// let parser = EndNoteXmlParser::new().with_whitespace_normalization(true);
+    /// ```
+    #[must_use]
+    pub fn with_whitespace_normalization(mut self, enabled: bool) -> Self {
+        self.options.normalize_whitespace = enabled;
         self
     }
 
     /// Extracts text content from XML events until the closing tag is found
     fn extract_text<B: BufRead>(
+        &self,
         reader: &mut Reader<B>,
         buf: &mut Vec<u8>,
         closing_tag: &[u8],
     ) -> Result<String> {
+        let max = self.options.max_field_length();
         let mut text = String::new();
+        let mut truncated = false;
         let closing_tag_str = String::from_utf8_lossy(closing_tag);
 
         loop {
             match reader.read_event_into(buf) {
                 Ok(Event::Text(e)) => {
-                    text.push_str(&e.unescape().map_err(|e| {
+                    let more = e.unescape().map_err(|e| {
                         CitationError::InvalidFormat(format!("Invalid XML text content: {}", e))
-                    })?);
+                    })?;
+                    truncated |= push_capped(&mut text, &more, max);
                 }
                 Ok(Event::End(e)) if e.name() == QName(closing_tag) => break,
                 Ok(Event::Eof) => {
@@ -85,6 +187,11 @@ impl EndNoteXmlParser {
             buf.clear();
         }
 
+        if truncated {
+            self.options.warning_handler.warn(&format!(
+                "truncated <{closing_tag_str}> field to {max} bytes"
+            ));
+        }
         Ok(text.trim().to_string())
     }
 
@@ -94,10 +201,13 @@ impl EndNoteXmlParser {
         reader: &mut Reader<B>,
         buf: &mut Vec<u8>,
     ) -> Result<Citation> {
-        let mut citation = Citation::default();
-        citation.id = nanoid!();
-        citation.citation_type.push("Journal Article".to_string()); // Set default type
-        citation.source = self.source.clone(); // Now we can access self.source
+        let mut citation = Citation {
+            id: nanoid!(),
+            citation_type: vec!["Journal Article".to_string()], // Set default type
+            source: self.options.source.clone(),
+            ..Default::default()
+        };
+        let mut role = None;
 
         loop {
             match reader.read_event_into(buf) {
@@ -115,79 +225,149 @@ impl EndNoteXmlParser {
                             }
                         }
                     }
+                    b"rec-number" => {
+                        let rec_number = self.extract_text(reader, buf, b"rec-number")?;
+                        citation
+                            .extra_fields
+                            .entry("rec-number".to_string())
+                            .or_default()
+                            .push(rec_number);
+                    }
+                    b"key" => {
+                        // Foreign keys identify the record within its EndNote library
+                        let mut db_id = None;
+                        for attr in e.attributes() {
+                            let attr = attr.map_err(CitationError::from)?;
+                            if attr.key.as_ref() == b"db-id" {
+                                db_id = Some(
+                                    attr.unescape_value()
+                                        .map_err(CitationError::from)?
+                                        .into_owned(),
+                                );
+                            }
+                        }
+                        let key = self.extract_text(reader, buf, b"key")?;
+                        citation
+                            .extra_fields
+                            .entry("foreign-keys".to_string())
+                            .or_default()
+                            .push(key);
+                        if let Some(db_id) = db_id {
+                            citation
+                                .extra_fields
+                                .entry("db-id".to_string())
+                                .or_default()
+                                .push(db_id);
+                        }
+                    }
                     b"title" => {
-                        citation.title = Self::extract_text(reader, buf, b"title")?;
+                        citation.title = self.extract_text(reader, buf, b"title")?;
+                    }
+                    b"authors" => role = None,
+                    b"secondary-authors" | b"tertiary-authors" => {
+                        role = Some(AuthorRole::Editor);
+                    }
+                    b"subsidiary-authors" | b"translated-authors" => {
+                        role = Some(AuthorRole::Translator);
                     }
                     b"author" => {
-                        let author_str = Self::extract_text(reader, buf, b"author")?;
+                        let author_str = self.extract_text(reader, buf, b"author")?;
                         let (family, given) = parse_author_name(&author_str);
                         citation.authors.push(Author {
                             family_name: family,
                             given_name: given,
                             affiliation: None,
+                            orcid: None,
+                            role,
                         });
                     }
                     b"secondary-title" => {
                         citation.journal =
-                            Some(Self::extract_text(reader, buf, b"secondary-title")?);
+                            Some(self.extract_text(reader, buf, b"secondary-title")?);
+                    }
+                    b"tertiary-title" => {
+                        citation.series =
+                            Some(self.extract_text(reader, buf, b"tertiary-title")?);
                     }
                     b"alt-title" => {
                         citation.journal_abbr =
-                            Some(Self::extract_text(reader, buf, b"alt-title")?);
+                            Some(self.extract_text(reader, buf, b"alt-title")?);
                     }
-                    b"custom2" => {
-                        let text = Self::extract_text(reader, buf, b"custom2")?;
-                        if text.contains("PMC") {
-                            citation.pmc_id = Some(text);
+                    tag @ (b"custom1" | b"custom2" | b"custom3" | b"custom4" | b"custom5"
+                    | b"custom6" | b"custom7" | b"custom8") => {
+                        let tag = String::from_utf8_lossy(tag).into_owned();
+                        let text = self.extract_text(reader, buf, tag.as_bytes())?;
+                        if tag == "custom2" && text.contains("PMC") {
+                            citation.pmc_id = Some(text.clone());
                         }
+                        citation.extra_fields.entry(tag).or_default().push(text);
                     }
                     b"volume" => {
-                        citation.volume = Some(Self::extract_text(reader, buf, b"volume")?);
+                        citation.volume = Some(self.extract_text(reader, buf, b"volume")?);
                     }
                     b"number" => {
-                        citation.issue = Some(Self::extract_text(reader, buf, b"number")?);
+                        citation.issue = Some(self.extract_text(reader, buf, b"number")?);
                     }
                     b"pages" => {
-                        citation.pages = Some(format_page_numbers(&Self::extract_text(
-                            reader, buf, b"pages",
-                        )?));
+                        citation.pages = Some(format_page_numbers(
+                            &self.extract_text(reader, buf, b"pages")?,
+                        ));
                     }
                     b"electronic-resource-num" => {
-                        let doi = Self::extract_text(reader, buf, b"electronic-resource-num")?;
-                        if doi.starts_with("10.") || doi.contains("doi.org") {
-                            citation.doi = format_doi(&doi);
+                        let doi = self.extract_text(reader, buf, b"electronic-resource-num")?;
+                        for doi in doi.split(|c: char| c.is_whitespace() || c == ';') {
+                            if doi.starts_with("10.") || doi.contains("doi.org") {
+                                citation.add_doi(doi);
+                            }
                         }
                     }
                     b"url" => {
-                        let url = Self::extract_text(reader, buf, b"url")?;
+                        let url = self.extract_text(reader, buf, b"url")?;
                         if citation.doi.is_none() && url.contains("doi.org") {
-                            citation.doi = format_doi(&url);
+                            citation.add_doi(&url);
                         }
                         citation.urls.push(url);
                     }
                     b"year" => {
-                        if let Ok(year) = Self::extract_text(reader, buf, b"year")?.parse::<i32>() {
+                        if let Some(year) = extract_year(&self.extract_text(reader, buf, b"year")?)
+                        {
                             citation.year = Some(year);
                         }
                     }
+                    b"date" => {
+                        // Publication dates are only a fallback for a missing year
+                        let date = self.extract_text(reader, buf, b"date")?;
+                        if citation.year.is_none() {
+                            citation.year = extract_year(&date);
+                        }
+                    }
                     b"abstract" => {
                         citation.abstract_text =
-                            Some(Self::extract_text(reader, buf, b"abstract")?);
+                            Some(self.extract_text(reader, buf, b"abstract")?);
                     }
                     b"keyword" => {
                         citation
                             .keywords
-                            .push(Self::extract_text(reader, buf, b"keyword")?);
+                            .push(self.extract_text(reader, buf, b"keyword")?);
                     }
                     b"language" => {
-                        citation.language = Some(Self::extract_text(reader, buf, b"language")?);
+                        citation.language = Some(self.extract_text(reader, buf, b"language")?);
                     }
                     b"publisher" => {
-                        citation.publisher = Some(Self::extract_text(reader, buf, b"publisher")?);
+                        citation.publisher = Some(self.extract_text(reader, buf, b"publisher")?);
+                    }
+                    b"accession-num" => {
+                        citation.accession_number =
+                            Some(self.extract_text(reader, buf, b"accession-num")?);
+                    }
+                    b"pub-location" => {
+                        citation.place = Some(self.extract_text(reader, buf, b"pub-location")?);
                     }
                     b"isbn" => {
-                        let issns = Self::extract_text(reader, buf, b"isbn")?;
-                        citation.issn.extend(split_issns(&issns));
+                        let value = self.extract_text(reader, buf, b"isbn")?;
+                        let (issns, isbns) = split_serial_numbers(&value);
+                        citation.issn.extend(issns);
+                        citation.isbn.extend(isbns);
                     }
                     _ => (),
                 },
@@ -199,16 +379,31 @@ impl EndNoteXmlParser {
             buf.clear();
         }
 
+        // The secondary title of a conference paper is the conference name
+        if citation
+            .citation_type
+            .iter()
+            .any(|t| t.starts_with("Conference"))
+        {
+            citation.conference_name = citation.journal.clone();
+        }
+
         Ok(citation)
     }
 }
 
 impl CitationParser for EndNoteXmlParser {
     fn parse(&self, input: &str) -> Result<Vec<Citation>> {
+        let input: &str = &clean_input(input);
         if input.trim().is_empty() {
             return Err(CitationError::InvalidFormat("Empty input".into()));
         }
 
+        let limit = self.options.limit();
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
         let mut reader = Reader::from_str(input);
         reader.config_mut().trim_text(true);
 
@@ -218,7 +413,10 @@ impl CitationParser for EndNoteXmlParser {
         loop {
             match reader.read_event_into(&mut buf) {
                 Ok(Event::Start(ref e)) if e.name() == QName(b"record") => {
-                    citations.push(self.parse_record(&mut reader, &mut buf)?); // Changed to use self.parse_record
+                    citations.push(self.parse_record(&mut reader, &mut buf)?);
+                    if citations.len() >= limit {
+                        break;
+                    }
                 }
                 Ok(Event::Eof) => break,
                 Err(e) => return Err(CitationError::from(e)),
@@ -233,8 +431,236 @@ impl CitationParser for EndNoteXmlParser {
             ));
         }
 
+        self.options.finish(&mut citations);
         Ok(citations)
     }
+
+    fn format_name(&self) -> &'static str {
+        "EndNote XML"
+    }
+
+    fn can_parse(&self, input: &str) -> bool {
+        let input: &str = &clean_input(input);
+        let trimmed = input.trim();
+        (trimmed.starts_with("<?xml") || trimmed.starts_with("<xml>"))
+            && !trimmed.contains("biblStruct")
+    }
+}
+
+/// Writes citations as an EndNote XML document.
+///
+/// Each citation becomes one `<record>` holding the fields
+/// [`EndNoteXmlParser`] reads, so the output reads back into the same fields.
+/// The record type is taken from `citation_type`, as an EndNote type name such
+/// as `"Journal Article"` or a RIS code such as `JOUR`, falling back to
+/// `Generic`. Of the `extra_fields`, the EndNote record number, foreign key and
+/// `"custom1"` to `"custom8"` are written; a `pmc_id` is written as `<custom2>`
+/// when there is no `"custom2"`.
+///
+/// # Arguments
+///
+/// * `citations` - The citations to write
+/// * `writer` - Destination for the XML output
+///
+/// # Errors
+///
+/// Returns `CitationError::Io` if writing fails.
+///
+/// # Examples
+///
+/// ```
+/// use biblib::endnote_xml::write_endnote_xml;
+/// use biblib::{Citation, CitationParser, EndNoteXmlParser, ExtraFields};
+///
+/// let citation = Citation {
+///     title: "Example Title".to_string(),
+///     extra_fields: ExtraFields::from([("custom3".to_string(), vec!["Included".to_string()])]),
+///     ..Default::default()
+/// };
+///
+/// let mut output = Vec::new();
+/// write_endnote_xml(&[citation], &mut output).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.contains("<custom3>Included</custom3>"));
+///
+/// let citations = EndNoteXmlParser::new().parse(&output).unwrap();
+/// assert_eq!(citations[0].extra_first("custom3"), Some("Included"));
+/// ```
+pub fn write_endnote_xml<'a, W: Write>(
+    citations: impl IntoIterator<Item = &'a Citation>,
+    mut writer: W,
+) -> Result<()> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(writer, "<xml><records>")?;
+    for citation in citations {
+        write_record(citation, &mut writer)?;
+    }
+    writeln!(writer, "</records></xml>")?;
+    Ok(())
+}
+
+/// Writes a single citation as an EndNote XML record
+fn write_record<W: Write>(citation: &Citation, writer: &mut W) -> Result<()> {
+    let element = |tag: &str, value: &str| -> Option<String> {
+        let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+        (!value.is_empty()).then(|| format!("<{tag}>{}</{tag}>", escape(&value)))
+    };
+    let optional = |tag: &str, value: Option<&str>| -> String {
+        value
+            .and_then(|value| element(tag, value))
+            .unwrap_or_default()
+    };
+
+    writeln!(writer, "<record>")?;
+    if let Some(number) = citation.extra_first("rec-number") {
+        writeln!(
+            writer,
+            "{}",
+            element("rec-number", number).unwrap_or_default()
+        )?;
+    }
+    if let Some(key) = citation.extra_first("foreign-keys") {
+        let db_id = citation
+            .extra_first("db-id")
+            .map(|db_id| format!(r#" db-id="{}""#, escape(db_id)))
+            .unwrap_or_default();
+        writeln!(
+            writer,
+            r#"<foreign-keys><key app="EN"{}>{}</key></foreign-keys>"#,
+            db_id,
+            escape(key)
+        )?;
+    }
+    let (type_name, type_number) = ref_type(&citation.citation_type);
+    writeln!(
+        writer,
+        r#"<ref-type name="{}">{}</ref-type>"#,
+        type_name, type_number
+    )?;
+
+    let contributors: String = [
+        ("authors", None),
+        ("secondary-authors", Some(AuthorRole::Editor)),
+        ("subsidiary-authors", Some(AuthorRole::Translator)),
+    ]
+    .into_iter()
+    .filter_map(|(group, role)| {
+        let names: String = citation
+            .authors
+            .iter()
+            .filter(|author| match role {
+                None => author.is_author(),
+                Some(_) => author.role == role,
+            })
+            .filter_map(|author| match author.given_name.trim() {
+                "" => element("author", &author.family_name),
+                given => element(
+                    "author",
+                    &format!("{}, {}", author.family_name.trim(), given),
+                ),
+            })
+            .collect();
+        (!names.is_empty()).then(|| format!("<{group}>{names}</{group}>"))
+    })
+    .collect();
+    if !contributors.is_empty() {
+        writeln!(writer, "<contributors>{}</contributors>", contributors)?;
+    }
+
+    let secondary_title = citation
+        .journal
+        .as_deref()
+        .or(citation.conference_name.as_deref());
+    writeln!(
+        writer,
+        "<titles>{}{}{}{}</titles>",
+        element("title", &citation.title).unwrap_or_default(),
+        optional("secondary-title", secondary_title),
+        optional("tertiary-title", citation.series.as_deref()),
+        optional("alt-title", citation.journal_abbr.as_deref()),
+    )?;
+
+    let mut fields = String::new();
+    fields.push_str(&optional("pages", citation.pages.as_deref()));
+    fields.push_str(&optional("volume", citation.volume.as_deref()));
+    fields.push_str(&optional("number", citation.issue.as_deref()));
+    let keywords: String = citation
+        .keywords
+        .iter()
+        .filter_map(|keyword| element("keyword", keyword))
+        .collect();
+    if !keywords.is_empty() {
+        fields.push_str(&format!("<keywords>{}</keywords>", keywords));
+    }
+    if let Some(year) = citation.year {
+        fields.push_str(&format!("<dates><year>{}</year></dates>", year));
+    }
+    fields.push_str(&optional("pub-location", citation.place.as_deref()));
+    fields.push_str(&optional("publisher", citation.publisher.as_deref()));
+    let serial_numbers: Vec<&str> = citation
+        .isbn
+        .iter()
+        .chain(&citation.issn)
+        .map(String::as_str)
+        .collect();
+    fields.push_str(&element("isbn", &serial_numbers.join("; ")).unwrap_or_default());
+    fields.push_str(&optional(
+        "accession-num",
+        citation.accession_number.as_deref(),
+    ));
+    let dois: Vec<&str> = citation.dois().collect();
+    fields.push_str(&element("electronic-resource-num", &dois.join("; ")).unwrap_or_default());
+    fields.push_str(&optional("abstract", citation.abstract_text.as_deref()));
+    for tag in CUSTOM_FIELDS {
+        match citation
+            .extra_fields
+            .get(tag)
+            .and_then(|values| values.first())
+        {
+            Some(value) => fields.push_str(&element(tag, value).unwrap_or_default()),
+            None if tag == "custom2" => fields.push_str(&optional(tag, citation.pmc_id.as_deref())),
+            None => {}
+        }
+    }
+    fields.push_str(&optional("language", citation.language.as_deref()));
+    let urls: String = citation
+        .urls
+        .iter()
+        .filter_map(|url| element("url", url))
+        .collect();
+    if !urls.is_empty() {
+        fields.push_str(&format!(
+            "<urls><related-urls>{}</related-urls></urls>",
+            urls
+        ));
+    }
+    if !fields.is_empty() {
+        writeln!(writer, "{}", fields)?;
+    }
+    writeln!(writer, "</record>")?;
+    Ok(())
+}
+
+/// Picks the EndNote reference type name and number for a citation's types
+fn ref_type(types: &[String]) -> (&'static str, u32) {
+    const REF_TYPES: [(&str, &str, u32); 8] = [
+        ("Journal Article", "JOUR", 17),
+        ("Book", "BOOK", 6),
+        ("Book Section", "CHAP", 5),
+        ("Conference Paper", "CPAPER", 47),
+        ("Conference Proceedings", "CONF", 10),
+        ("Thesis", "THES", 32),
+        ("Report", "RPRT", 27),
+        ("Electronic Article", "EJOUR", 43),
+    ];
+    types
+        .iter()
+        .find_map(|t| {
+            REF_TYPES
+                .iter()
+                .find(|(name, code, _)| t.eq_ignore_ascii_case(name) || t == code)
+        })
+        .map_or(("Generic", 13), |&(name, _, number)| (name, number))
 }
 
 #[cfg(test)]
@@ -341,4 +767,199 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_parse_record_identifiers() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <xml><records><record>
+        <database name="Library.enl" path="Library.enl">Library.enl</database>
+        <source-app name="EndNote" version="20.0">EndNote</source-app>
+        <rec-number>42</rec-number>
+        <foreign-keys><key app="EN" db-id="x9fd2ttsq">42</key></foreign-keys>
+        <titles><title>Test Article</title></titles>
+        </record></records></xml>"#;
+
+        let parser = EndNoteXmlParser::new();
+        let result = parser.parse(input).unwrap();
+
+        assert_eq!(result[0].extra_first("rec-number"), Some("42"));
+        assert_eq!(result[0].extra_first("foreign-keys"), Some("42"));
+        assert_eq!(result[0].extra_first("db-id"), Some("x9fd2ttsq"));
+        assert_eq!(result[0].title, "Test Article");
+    }
+
+    #[test]
+    fn test_with_limit() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <xml><records>
+        <record><titles><title>First</title></titles></record>
+        <record><titles><title>Second</title></titles></record>
+        <record><titles><title>Third</title></titles></record>
+        </records></xml>"#;
+
+        let result = EndNoteXmlParser::new().with_limit(2).parse(input).unwrap();
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[1].title, "Second");
+    }
+
+    #[test]
+    fn test_parse_conference_paper() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <xml><records><record>
+        <ref-type name="Conference Paper">47</ref-type>
+        <titles>
+            <title>Test Paper</title>
+            <secondary-title>Test Conference 2020</secondary-title>
+        </titles>
+        <pub-location>Vienna, Austria</pub-location>
+        </record></records></xml>"#;
+
+        let parser = EndNoteXmlParser::new();
+        let result = parser.parse(input).unwrap();
+        assert_eq!(result[0].place.as_deref(), Some("Vienna, Austria"));
+        assert_eq!(
+            result[0].conference_name.as_deref(),
+            Some("Test Conference 2020")
+        );
+    }
+
+    #[test]
+    fn test_tertiary_title_as_series() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <xml><records><record>
+        <ref-type name="Book">6</ref-type>
+        <titles>
+            <title>Test Book</title>
+            <tertiary-title>Methods in Testing</tertiary-title>
+        </titles>
+        </record></records></xml>"#;
+
+        let result = EndNoteXmlParser::new().parse(input).unwrap();
+        assert_eq!(result[0].series.as_deref(), Some("Methods in Testing"));
+    }
+
+    #[test]
+    fn test_year_from_pub_dates() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <xml><records><record>
+        <titles><title>Test Article</title></titles>
+        <dates><pub-dates><date>Mar-Apr 2018</date></pub-dates></dates>
+        </record></records></xml>"#;
+
+        let parser = EndNoteXmlParser::new();
+        let result = parser.parse(input).unwrap();
+        assert_eq!(result[0].year, Some(2018));
+    }
+
+    #[test]
+    fn test_parse_isbn() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <xml><records><record>
+        <titles><title>Test Book</title></titles>
+        <isbn>978-0-306-40615-7 (hbk.)\r0047-1852 (Print)</isbn>
+        </record></records></xml>"#;
+
+        let result = EndNoteXmlParser::new().parse(input).unwrap();
+        assert_eq!(result[0].isbn, vec!["978-0-306-40615-7 (hbk.)"]);
+        assert_eq!(result[0].issn, vec!["0047-1852 (Print)"]);
+    }
+
+    #[test]
+    fn test_custom_fields_round_trip() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <xml><records><record>
+        <rec-number>7</rec-number>
+        <ref-type name="Book">6</ref-type>
+        <contributors><authors><author>Smith, John</author></authors></contributors>
+        <titles><title>Screened &amp; Included</title></titles>
+        <custom1>Include</custom1>
+        <custom2>PMC1234567</custom2>
+        <custom8><style face="normal" font="default" size="100%">Checked by AB</style></custom8>
+        </record></records></xml>"#;
+
+        let citations = EndNoteXmlParser::new().parse(input).unwrap();
+        let citation = &citations[0];
+        assert_eq!(citation.extra_first("custom1"), Some("Include"));
+        assert_eq!(citation.extra_first("custom2"), Some("PMC1234567"));
+        assert_eq!(citation.pmc_id.as_deref(), Some("PMC1234567"));
+        assert_eq!(citation.extra_first("custom8"), Some("Checked by AB"));
+
+        let mut output = Vec::new();
+        write_endnote_xml(&citations, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(r#"<ref-type name="Book">6</ref-type>"#));
+        assert!(output.contains("<title>Screened &amp; Included</title>"));
+
+        let reparsed = EndNoteXmlParser::new().parse(&output).unwrap();
+        let copy = &reparsed[0];
+        assert_eq!(copy.title, citation.title);
+        assert_eq!(copy.authors, citation.authors);
+        assert_eq!(copy.citation_type, vec!["Book"]);
+        assert_eq!(copy.pmc_id, citation.pmc_id);
+        for field in ["rec-number", "custom1", "custom2", "custom8"] {
+            assert_eq!(
+                copy.extra_fields.get(field),
+                citation.extra_fields.get(field)
+            );
+        }
+    }
+
+    #[cfg(feature = "ris")]
+    #[test]
+    fn test_write_endnote_xml_round_trip() {
+        let input = "TY  - JOUR\nTI  - Round Trip\nAU  - Smith, John\nJF  - Journal of Tests\nJA  - J Tests\nPY  - 2021\nVL  - 4\nIS  - 2\nSP  - 10\nEP  - 20\nSN  - 1234-5678\nDO  - 10.1000/one\nAB  - An abstract.\nKW  - testing\nUR  - https://example.com\nLA  - English\nER  - \n";
+        let citations = crate::RisParser::new().parse(input).unwrap();
+
+        let mut output = Vec::new();
+        write_endnote_xml(&citations, &mut output).unwrap();
+        let reparsed = EndNoteXmlParser::new()
+            .parse(&String::from_utf8(output).unwrap())
+            .unwrap();
+        let (original, copy) = (&citations[0], &reparsed[0]);
+        assert_eq!(copy.citation_type, vec!["Journal Article"]);
+        assert_eq!(copy.title, original.title);
+        assert_eq!(copy.authors, original.authors);
+        assert_eq!(copy.journal, original.journal);
+        assert_eq!(copy.journal_abbr, original.journal_abbr);
+        assert_eq!(copy.year, original.year);
+        assert_eq!(copy.volume, original.volume);
+        assert_eq!(copy.issue, original.issue);
+        assert_eq!(copy.pages, original.pages);
+        assert_eq!(copy.issn, original.issn);
+        assert_eq!(copy.doi, original.doi);
+        assert_eq!(copy.abstract_text, original.abstract_text);
+        assert_eq!(copy.keywords, original.keywords);
+        assert_eq!(copy.urls, original.urls);
+        assert_eq!(copy.language, original.language);
+    }
+
+    #[test]
+    fn test_contributor_roles() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <xml><records><record>
+        <ref-type name="Book Section">5</ref-type>
+        <contributors>
+            <authors><author>Smith, John</author></authors>
+            <secondary-authors><author>Doe, Jane</author></secondary-authors>
+            <translated-authors><author>Poe, Pat</author></translated-authors>
+        </contributors>
+        <titles><title>A Chapter</title></titles>
+        </record></records></xml>"#;
+
+        let citations = EndNoteXmlParser::new().parse(input).unwrap();
+        let roles: Vec<_> = citations[0].authors.iter().map(|a| a.role).collect();
+        assert_eq!(
+            roles,
+            vec![None, Some(AuthorRole::Editor), Some(AuthorRole::Translator)]
+        );
+
+        let mut output = Vec::new();
+        write_endnote_xml(&citations, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(
+            output.contains("<secondary-authors><author>Doe, Jane</author></secondary-authors>")
+        );
+        let reparsed = EndNoteXmlParser::new().parse(&output).unwrap();
+        assert_eq!(reparsed[0].authors, citations[0].authors);
+    }
 }
@@ -0,0 +1,147 @@
+//! Batch export of citation collections.
+//!
+//! [`write_by_source`] splits citations by their [`Citation::source`] and writes
+//! each group with its own writer, e.g. one file per database after
+//! deduplication. The serialization itself is supplied by the caller.
+//!
+//! # Example
+//!
+//! ```
+//! use biblib::export::write_by_source;
+//! use biblib::Citation;
+//! use std::fs::File;
+//! use std::io::Write;
+//!
+//! let citation = |title: &str, source: &str| Citation {
+//!     title: title.to_string(),
+//!     source: Some(source.to_string()),
+//!     ..Default::default()
+//! };
+//! let citations = vec![
+//!     citation("First", "PubMed"),
+//!     citation("Second", "Embase"),
+//!     citation("Third", "PubMed"),
+//! ];
+//!
+//! let dir = std::env::temp_dir();
+//! write_by_source(
+//!     &citations,
+//!     |source| {
+//!         let name = format!("biblib-{}.txt", source.unwrap_or("unknown"));
+//!         Ok(File::create(dir.join(name))?)
+//!     },
+//!     |file, group| {
+//!         for citation in group {
+//!             writeln!(file, "{}", citation.title)?;
+//!         }
+//!         Ok(())
+//!     },
+//! )
+//! .unwrap();
+//!
+//! let pubmed = std::fs::read_to_string(dir.join("biblib-PubMed.txt")).unwrap();
+//! assert_eq!(pubmed, "First\nThird\n");
+//! ```
+
+use std::io::Write;
+
+use crate::{Citation, Result};
+
+/// Groups citations by their source, keeping the order of first appearance.
+///
+/// Citations without a source are grouped under `None`.
+///
+/// # Arguments
+///
+/// * `citations` - The citations to group
+///
+/// # Examples
+///
+/// ```
+/// use biblib::export::group_by_source;
+/// use biblib::Citation;
+///
+/// let citations = vec![
+///     Citation { source: Some("PubMed".to_string()), ..Default::default() },
+///     Citation::default(),
+///     Citation { source: Some("PubMed".to_string()), ..Default::default() },
+/// ];
+///
+/// let groups = group_by_source(&citations);
+/// assert_eq!(groups.len(), 2);
+/// assert_eq!(groups[0].0, Some("PubMed"));
+/// assert_eq!(groups[0].1.len(), 2);
+/// assert_eq!(groups[1].0, None);
+/// ```
+#[must_use]
+pub fn group_by_source(citations: &[Citation]) -> Vec<(Option<&str>, Vec<&Citation>)> {
+    let mut groups: Vec<(Option<&str>, Vec<&Citation>)> = Vec::new();
+    for citation in citations {
+        let source = citation.source.as_deref();
+        match groups.iter_mut().find(|(s, _)| *s == source) {
+            Some((_, group)) => group.push(citation),
+            None => groups.push((source, vec![citation])),
+        }
+    }
+    groups
+}
+
+/// Writes the citations of each source with a separate writer.
+///
+/// For every source, in order of first appearance, a writer is created with
+/// `open`, the source's citations are written to it with `write`, and it is
+/// flushed. Citations without a source are passed to `open` with `None`.
+///
+/// # Arguments
+///
+/// * `citations` - The citations to write
+/// * `open` - Creates the writer for a source, e.g. a file named after it
+/// * `write` - Serializes one group of citations to its writer
+///
+/// # Errors
+///
+/// Returns the first error from `open`, `write` or flushing. Groups
+/// after the failing one are not written.
+pub fn write_by_source<W: Write>(
+    citations: &[Citation],
+    mut open: impl FnMut(Option<&str>) -> Result<W>,
+    mut write: impl FnMut(&mut W, &[&Citation]) -> Result<()>,
+) -> Result<()> {
+    for (source, group) in group_by_source(citations) {
+        let mut writer = open(source)?;
+        write(&mut writer, &group)?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CitationError;
+
+    #[test]
+    fn test_write_by_source_stops_on_error() {
+        let citation = |source: &str| Citation {
+            source: Some(source.to_string()),
+            ..Default::default()
+        };
+        let citations = vec![citation("A"), citation("B"), citation("C")];
+
+        let mut opened = Vec::new();
+        let result = write_by_source(
+            &citations,
+            |source| {
+                opened.push(source.map(String::from));
+                Ok(Vec::new())
+            },
+            |_, group| match group[0].source.as_deref() {
+                Some("B") => Err(CitationError::Other("write failed".into())),
+                _ => Ok(()),
+            },
+        );
+
+        assert!(result.is_err());
+        assert_eq!(opened, [Some("A".to_string()), Some("B".to_string())]);
+    }
+}
@@ -11,7 +11,25 @@
 //! - `pubmed` - Enable PubMed/MEDLINE format support (enabled by default)  
 //! - `xml` - Enable EndNote XML support (enabled by default)
 //! - `ris` - Enable RIS format support (enabled by default)
+//! - `refer` - Enable refer (`%A`, `%T`, ...) format support (enabled by default)
+//! - `tei` - Enable TEI XML support for GROBID reference lists (enabled by default)
+//! - `crossref` - Enable Crossref REST API JSON support (enabled by default)
 //! - `dedupe` - Enable citation deduplication (enabled by default)
+//! - `std` - Everything that needs the standard library, which all of the
+//!   above enable (enabled by default)
+//! - `core` - The data model alone: [`Citation`], [`Author`], [`Grant`],
+//!   merging with [`Citation::merge_from`], and the normalization helpers
+//!   [`normalize_orcid`], [`normalize_isbn`], [`format_page_numbers`] and
+//!   [`normalize_publication_status`]
+//!
+//! Without `std` the crate is `no_std` and only needs `alloc`, for use in WASM
+//! or embedded targets. The parsers, deduplication, formatting and methods
+//! relying on regular expressions are then unavailable:
+//!
+//! ```toml
+//! [dependencies]
+//! biblib = { version = "0.2.0", default-features = false, features = ["core"] }
+//! ```
 //!
 //! To use only specific features, disable default features and enable just what you need:
 //!
@@ -26,6 +44,9 @@
 //!   - RIS (Research Information Systems)
 //!   - PubMed/MEDLINE
 //!   - EndNote XML
+//!   - refer
+//!   - TEI XML (GROBID reference lists)
+//!   - Crossref JSON (REST API works)
 //!   - CSV with configurable mappings
 //!
 //! - **Source Tracking**: Each parser can track the source of citations
@@ -101,6 +122,7 @@
 //!     group_by_year: true,
 //!     run_in_parallel: true,
 //!     source_preferences: vec!["PubMed".to_string(), "Cochrane".to_string()],
+//!     ..Default::default()
 //! };
 //!
 //! let deduplicator = Deduplicator::new().with_config(config);
@@ -138,45 +160,97 @@
 //!
 //! # Thread Safety
 //!
-//! All parser implementations are thread-safe and can be shared between threads.
-//! The deduplicator supports parallel processing through the `run_in_parallel` option.
+//! All parser implementations are `Send + Sync + Clone` and hold no interior
+//! mutability, so a single parser can be shared between threads or cheaply cloned
+//! into each one. See `examples/concurrent_parsing.rs`.
+//! The deduplicator supports parallel processing through the `run_in_parallel` option,
+//! and with the `parallel` feature `RisParser::with_parallel` converts records in
+//! parallel.
+//!
+//! Output order never depends on parallelism: parsers return citations in input
+//! order, also in parallel, and the deduplicator returns unique citations and duplicate groups in
+//! input order whether or not `run_in_parallel` is set.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(any(feature = "xml", feature = "tei"))]
 use quick_xml::events::attributes::AttrError;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::fs::File;
+#[cfg(feature = "std")]
+use std::io::{BufRead, Read};
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
+#[cfg(feature = "csv")]
 extern crate csv as csv_crate;
 
+#[cfg(feature = "bibtex")]
+pub mod bibtex;
+#[cfg(feature = "crossref")]
+pub mod crossref;
 #[cfg(feature = "csv")]
 pub mod csv;
 #[cfg(feature = "dedupe")]
 pub mod dedupe;
 #[cfg(feature = "xml")]
 pub mod endnote_xml;
+#[cfg(feature = "std")]
+pub mod export;
 #[cfg(feature = "pubmed")]
 pub mod pubmed;
+#[cfg(feature = "std")]
+pub mod query;
+#[cfg(feature = "refer")]
+pub mod refer;
 #[cfg(feature = "ris")]
 pub mod ris;
+#[cfg(feature = "std")]
+pub mod style;
+#[cfg(feature = "tei")]
+pub mod tei;
+#[cfg(feature = "std")]
+pub mod transform;
 
 // Reexports
+#[cfg(feature = "crossref")]
+pub use crossref::CrossrefParser;
 #[cfg(feature = "csv")]
 pub use csv::CsvParser;
 #[cfg(feature = "xml")]
 pub use endnote_xml::EndNoteXmlParser;
 #[cfg(feature = "pubmed")]
 pub use pubmed::PubMedParser;
+#[cfg(feature = "refer")]
+pub use refer::ReferParser;
 #[cfg(feature = "ris")]
 pub use ris::RisParser;
+#[cfg(feature = "std")]
+pub use style::CiteStyle;
+#[cfg(feature = "tei")]
+pub use tei::TeiParser;
+pub use utils::{
+    collapse_whitespace, format_page_numbers, normalize_arxiv_id, normalize_isbn, normalize_orcid,
+    normalize_publication_status,
+};
+#[cfg(feature = "std")]
+pub use utils::{extract_arxiv_ids, extract_dois, extract_pmids, extract_year};
 
 mod utils;
 
 /// A specialized Result type for citation operations.
-pub type Result<T> = std::result::Result<T, CitationError>;
+pub type Result<T> = core::result::Result<T, CitationError>;
 
 /// Represents errors that can occur during citation parsing.
 #[derive(Error, Debug)]
 pub enum CitationError {
+    #[cfg(feature = "std")]
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -197,26 +271,36 @@ pub enum CitationError {
 }
 
 // Add From implementations for common error types
+#[cfg(feature = "csv")]
 impl From<csv_crate::Error> for CitationError {
     fn from(err: csv_crate::Error) -> Self {
         CitationError::InvalidFormat(err.to_string())
     }
 }
 
+#[cfg(any(feature = "xml", feature = "tei"))]
 impl From<quick_xml::Error> for CitationError {
     fn from(err: quick_xml::Error) -> Self {
         CitationError::InvalidFormat(err.to_string())
     }
 }
 
+#[cfg(any(feature = "xml", feature = "tei"))]
 impl From<AttrError> for CitationError {
     fn from(err: AttrError) -> Self {
         CitationError::InvalidFormat(err.to_string())
     }
 }
 
+#[cfg(feature = "std")]
+impl From<serde_json::Error> for CitationError {
+    fn from(err: serde_json::Error) -> Self {
+        CitationError::InvalidFormat(err.to_string())
+    }
+}
+
 /// Represents an author of a citation.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct Author {
     /// The author's family name (surname)
     pub family_name: String,
@@ -224,11 +308,325 @@ pub struct Author {
     pub given_name: String,
     /// Optional affiliation
     pub affiliation: Option<String>,
+    /// Optional ORCID iD in the canonical `0000-0000-0000-0000` form
+    #[serde(default)]
+    pub orcid: Option<String>,
+    /// The person's role in the work, when the source states one.
+    ///
+    /// Primary authors are usually left as `None`; see [`Author::is_author`].
+    #[serde(default)]
+    pub role: Option<AuthorRole>,
+}
+
+/// The role of a person listed in [`Citation::authors`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AuthorRole {
+    /// An author of the work
+    Author,
+    /// The corresponding author
+    Corresponding,
+    /// An editor of the work or of the book or series containing it
+    Editor,
+    /// A translator of the work
+    Translator,
+    /// A collaborator listed as an investigator, e.g. PubMed `FIR` lines
+    Investigator,
+}
+
+impl Author {
+    /// Returns `true` unless the author is listed with a role other than
+    /// author, such as editor or translator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{Author, AuthorRole};
+    ///
+    /// let author = |role: Option<AuthorRole>| Author {
+    ///     family_name: "Smith".to_string(),
+    ///     role,
+    ///     ..Default::default()
+    /// };
+    /// assert!(author(None).is_author());
+    /// assert!(author(Some(AuthorRole::Corresponding)).is_author());
+    /// assert!(!author(Some(AuthorRole::Editor)).is_author());
+    /// ```
+    #[must_use]
+    pub fn is_author(&self) -> bool {
+        matches!(
+            self.role,
+            None | Some(AuthorRole::Author | AuthorRole::Corresponding)
+        )
+    }
+
+    /// Returns the initials of the given name, without punctuation.
+    ///
+    /// Each part of the given name, including each side of a hyphen, gives one
+    /// initial. Parts already written as initials, e.g. `"JM"`, are kept: as in
+    /// [`Author::name_case`], these are capitals with at most two letters or
+    /// without vowels, so `"ANN"` is a name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::Author;
+    ///
+    /// let author = |given: &str| Author {
+    ///     given_name: given.to_string(),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(author("John Michael").to_initials(), "JM");
+    /// assert_eq!(author("Jean-Paul").to_initials(), "JP");
+    /// assert_eq!(author("J. M.").to_initials(), "JM");
+    /// assert_eq!(author("JM").to_initials(), "JM");
+    /// assert_eq!(author("EVA MARIA").to_initials(), "EM");
+    /// ```
+    #[must_use]
+    pub fn to_initials(&self) -> String {
+        self.given_name
+            .split(|c: char| c.is_whitespace() || c == '.' || c == '-')
+            .filter(|part| !part.is_empty())
+            .flat_map(|part| {
+                let already_initials = part.chars().all(|c| c.is_alphabetic() && c.is_uppercase())
+                    && utils::is_capital_initials(part);
+                let initials: Vec<char> = if already_initials {
+                    part.chars().collect()
+                } else {
+                    part.chars().take(1).flat_map(char::to_uppercase).collect()
+                };
+                initials
+            })
+            .collect()
+    }
+
+    /// Returns a copy of the author with a name written in capitals re-cased.
+    ///
+    /// Legacy databases often store names as `"SMITH"`, `"JOHN"`. Each word is
+    /// capitalized, including after hyphens and apostrophes and in `Mc`
+    /// prefixes, and family name particles such as `"van"` or `"de"` are
+    /// lowercased. Given names of at most two letters or without vowels, such
+    /// as `"JM"`, are kept as initials. Names already in mixed case are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::Author;
+    ///
+    /// let author = |family: &str, given: &str| Author {
+    ///     family_name: family.to_string(),
+    ///     given_name: given.to_string(),
+    ///     ..Default::default()
+    /// };
+    /// let cased = author("O'BRIEN-MCDONALD", "MARY JM").name_case();
+    /// assert_eq!(cased.family_name, "O'Brien-McDonald");
+    /// assert_eq!(cased.given_name, "Mary JM");
+    /// assert_eq!(author("VAN DER BERG", "J").name_case().family_name, "van der Berg");
+    /// assert_eq!(author("DeVries", "Anna").name_case(), author("DeVries", "Anna"));
+    /// ```
+    #[must_use]
+    pub fn name_case(&self) -> Author {
+        Author {
+            family_name: utils::name_case(&self.family_name, false),
+            given_name: utils::name_case(&self.given_name, true),
+            ..self.clone()
+        }
+    }
+}
+
+/// A grant funding the work described by a citation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Grant {
+    /// Grant number, e.g. `"R01 HL123456"`
+    pub id: Option<String>,
+    /// Funding agency, e.g. `"NHLBI NIH HHS"`
+    pub agency: Option<String>,
+    /// Country of the funding agency
+    pub country: Option<String>,
+}
+
+/// An article related to a citation, such as an erratum, a comment or a
+/// retraction.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelatedArticle {
+    /// How the article relates to the citation, as a PubMed `RefType`, e.g.
+    /// `"RetractionIn"` for the notice retracting the citation, or
+    /// `"ErratumFor"` when the citation corrects the article
+    pub ref_type: String,
+    /// PubMed ID of the related article
+    pub pmid: String,
+}
+
+/// The kind of work a citation describes, classified from
+/// [`Citation::citation_type`].
+///
+/// # Required fields
+///
+/// [`Citation::missing_required_fields`] checks the fields each kind needs,
+/// as listed by [`CitationType::required_fields`]:
+///
+/// | Kind              | Required fields                                        |
+/// |-------------------|--------------------------------------------------------|
+/// | `JournalArticle`  | `title`, `authors`, `journal`, `year`                  |
+/// | `Book`            | `title`, `authors`, `publisher`, `year`                |
+/// | `BookChapter`     | `title`, `authors`, `journal` (the book), `publisher`, `year` |
+/// | `ConferencePaper` | `title`, `authors`, `conference_name`, `year`          |
+/// | `Thesis`          | `title`, `authors`, `publisher` (the institution), `year` |
+/// | `Report`          | `title`, `authors`, `publisher` (the institution), `year` |
+/// | `Other`           | `title`                                                |
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CitationType {
+    /// A journal article, including meeting abstracts and articles in press
+    JournalArticle,
+    /// A whole book
+    Book,
+    /// A chapter or section of a book
+    BookChapter,
+    /// A paper in conference proceedings
+    ConferencePaper,
+    /// A thesis or dissertation
+    Thesis,
+    /// A technical or other report
+    Report,
+    /// Any other or unknown kind of work
+    Other,
+}
+
+/// Fields each [`CitationType`] needs, named as in [`Citation`]
+const REQUIRED_FIELDS: [(CitationType, &[&str]); 7] = [
+    (
+        CitationType::JournalArticle,
+        &["title", "authors", "journal", "year"],
+    ),
+    (
+        CitationType::Book,
+        &["title", "authors", "publisher", "year"],
+    ),
+    (
+        CitationType::BookChapter,
+        &["title", "authors", "journal", "publisher", "year"],
+    ),
+    (
+        CitationType::ConferencePaper,
+        &["title", "authors", "conference_name", "year"],
+    ),
+    (
+        CitationType::Thesis,
+        &["title", "authors", "publisher", "year"],
+    ),
+    (
+        CitationType::Report,
+        &["title", "authors", "publisher", "year"],
+    ),
+    (CitationType::Other, &["title"]),
+];
+
+impl CitationType {
+    /// Classifies a list of type names or RIS type codes.
+    ///
+    /// The first type that is recognized decides, e.g. `"JOUR"` or
+    /// `"Journal Article"`, `"CHAP"` or `"Book Section"`, `"CONF"` or
+    /// `"Conference Proceedings"`. Returns [`CitationType::Other`] if none is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::CitationType;
+    ///
+    /// let types = |types: &[&str]| types.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+    /// assert_eq!(
+    ///     CitationType::from_types(&types(&["Journal Article"])),
+    ///     CitationType::JournalArticle
+    /// );
+    /// assert_eq!(CitationType::from_types(&types(&["CHAP"])), CitationType::BookChapter);
+    /// assert_eq!(
+    ///     CitationType::from_types(&types(&["Dataset", "Doctoral Thesis"])),
+    ///     CitationType::Thesis
+    /// );
+    /// assert_eq!(CitationType::from_types(&[]), CitationType::Other);
+    /// ```
+    #[must_use]
+    pub fn from_types(types: &[String]) -> Self {
+        for name in types.iter().map(|t| t.to_lowercase()) {
+            let kind = match name.as_str() {
+                "jour" | "ejour" | "abst" | "inpr" => Self::JournalArticle,
+                "conf" | "cpaper" => Self::ConferencePaper,
+                "chap" => Self::BookChapter,
+                "book" | "ebook" | "edbook" => Self::Book,
+                "thes" => Self::Thesis,
+                "rprt" => Self::Report,
+                _ if name.contains("chapter") || name.contains("section") => Self::BookChapter,
+                _ if name.contains("conference") || name.contains("proceedings") => {
+                    Self::ConferencePaper
+                }
+                _ if name.contains("book") => Self::Book,
+                _ if name.contains("thesis") || name.contains("dissertation") => Self::Thesis,
+                _ if name.contains("report") => Self::Report,
+                _ if name.contains("journal") || name.contains("article") => Self::JournalArticle,
+                _ => continue,
+            };
+            return kind;
+        }
+        Self::Other
+    }
+
+    /// Returns the names of the [`Citation`] fields this kind of work needs,
+    /// see [Required fields](CitationType#required-fields).
+    #[must_use]
+    pub fn required_fields(self) -> &'static [&'static str] {
+        REQUIRED_FIELDS
+            .iter()
+            .find(|(kind, _)| *kind == self)
+            .map_or(&[], |(_, fields)| fields)
+    }
+}
+
+/// Fields renamed since the first serialized form, as `(version, old, new)`.
+///
+/// `version` is the first [`Citation::SCHEMA_VERSION`] using the new name. No
+/// field has been renamed yet.
+#[cfg(feature = "std")]
+const RENAMED_FIELDS: &[(u32, &str, &str)] = &[];
+
+/// Moves fields renamed after `version` to their new names, unless the new
+/// name is already present.
+#[cfg(feature = "std")]
+fn rename_fields(
+    map: &mut serde_json::Map<String, serde_json::Value>,
+    version: u32,
+    renames: &[(u32, &str, &str)],
+) {
+    for &(since, old, new) in renames {
+        if version < since {
+            if let Some(value) = map.remove(old) {
+                map.entry(new).or_insert(value);
+            }
+        }
+    }
 }
 
 /// Represents a single citation with its metadata.
+///
+/// # Serialized form
+///
+/// Citations serialize to JSON objects with one key per field. The form is
+/// versioned by [`Citation::SCHEMA_VERSION`], which
+/// [`Citation::to_json_value`] stores under `schema_version`, and changes to it
+/// follow a migration contract:
+///
+/// - fields are added with a default, so older records without them still
+///   deserialize, and are never removed
+/// - renaming a field bumps the version, and
+///   [`Citation::from_json_migrating`] accepts the old name in records of
+///   earlier versions
+/// - records without `schema_version`, including those written with plain
+///   serde, are treated as version 0
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Citation {
+    /// Identifier of the citation. Parsers generate a unique id for every
+    /// record, except that the CSV parser takes it from an id column when
+    /// there is one, see `csv::IdPolicy`, and the RIS parser from the `ID`
+    /// line with `RisParser::with_ris_id`
     pub id: String,
     /// Type of the citation
     pub citation_type: Vec<String>,
@@ -240,8 +638,14 @@ pub struct Citation {
     pub journal: Option<String>,
     /// Journal abbreviation
     pub journal_abbr: Option<String>,
-    /// Publication year
+    /// Publication year. For articles published online ahead of print this is
+    /// the year of the electronic publication until the print year is known
     pub year: Option<i32>,
+    /// Publication status, normalized to PubMed's codes such as
+    /// `"aheadofprint"`, `"epublish"` or `"ppublish"`, see
+    /// [`normalize_publication_status`]
+    #[serde(default)]
+    pub status: Option<String>,
     /// Volume number
     pub volume: Option<String>,
     /// Issue number
@@ -250,203 +654,2656 @@ pub struct Citation {
     pub pages: Option<String>,
     /// ISSN of the journal
     pub issn: Vec<String>,
-    /// Digital Object Identifier
+    /// ISBN of the book, as written in the record
+    #[serde(default)]
+    pub isbn: Vec<String>,
+    /// Digital Object Identifier of the work itself, chosen from `all_dois`
     pub doi: Option<String>,
+    /// All DOIs listed in the record, such as those of the article, a dataset
+    /// or an erratum, in the order they appear
+    #[serde(default)]
+    pub all_dois: Vec<String>,
     /// PubMed ID
     pub pmid: Option<String>,
     /// PMC ID
     pub pmc_id: Option<String>,
+    /// arXiv identifier, e.g. `"2101.12345v2"` or `"hep-th/9901001"`, see
+    /// [`normalize_arxiv_id`]
+    #[serde(default)]
+    pub arxiv_id: Option<String>,
     /// Abstract text
     pub abstract_text: Option<String>,
+    /// Copyright statement of the abstract, e.g. `"© 2020 Elsevier Ltd."`
+    #[serde(default)]
+    pub copyright: Option<String>,
     /// Keywords
     pub keywords: Vec<String>,
     /// URLs
     pub urls: Vec<String>,
     /// Language
     pub language: Option<String>,
-    /// MeSH Terms
+    /// MeSH descriptors, kept apart from the author `keywords`
     pub mesh_terms: Vec<String>,
     /// Publisher
     pub publisher: Option<String>,
+    /// Place of publication, or the location of a conference
+    #[serde(default)]
+    pub place: Option<String>,
+    /// Name of the conference, for conference papers and proceedings
+    #[serde(default)]
+    pub conference_name: Option<String>,
+    /// Title of the series a book or report belongs to
+    #[serde(default)]
+    pub series: Option<String>,
+    /// Accession number of the record in its database, e.g. an Embase or Web
+    /// of Science identifier
+    #[serde(default)]
+    pub accession_number: Option<String>,
+    /// Number of times the work has been cited, as reported by the source
+    #[serde(default)]
+    pub cited_by_count: Option<u32>,
+    /// Number of references in the work's bibliography
+    #[serde(default)]
+    pub reference_count: Option<u32>,
+    /// Grants funding the work, currently read from PubMed only
+    #[serde(default)]
+    pub grants: Vec<Grant>,
+    /// Related articles such as errata, comments and retractions, currently
+    /// read from PubMed only
+    #[serde(default)]
+    pub related: Vec<RelatedArticle>,
     /// Additional fields not covered by standard fields
-    pub extra_fields: HashMap<String, Vec<String>>,
+    pub extra_fields: ExtraFields,
     /// Source of the citation (e.g. pubmed, ris, etc.)
     pub source: Option<String>,
 }
 
-/// Represents a group of duplicate citations with one unique citation
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DuplicateGroup {
-    /// The unique (original) citation
-    pub unique: Citation,
-    /// The duplicate citations
-    pub duplicates: Vec<Citation>,
-}
+/// Map of [`Citation::extra_fields`], ordered by key
+///
+/// A `BTreeMap` with and without the `std` feature, so code written against
+/// `core` alone keeps compiling when another crate enables `std`.
+pub type ExtraFields = alloc::collections::BTreeMap<String, Vec<String>>;
 
-/// Trait for implementing citation parsers.
-pub trait CitationParser {
-    /// Parse a string containing one or more citations.
+impl Citation {
+    /// Version of the serialized form of a citation, see
+    /// [Serialized form](Citation#serialized-form).
+    pub const SCHEMA_VERSION: u32 = 1;
+
+    /// Serializes the citation to a JSON value tagged with
+    /// [`Citation::SCHEMA_VERSION`] under `schema_version`.
     ///
-    /// # Arguments
+    /// # Examples
     ///
-    /// * `input` - The string containing citation data
+    /// ```
+    /// use biblib::Citation;
     ///
-    /// # Returns
+    /// let citation = Citation {
+    ///     title: "Example Title".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// let value = citation.to_json_value();
+    /// assert_eq!(value["schema_version"], Citation::SCHEMA_VERSION);
+    /// assert_eq!(value["title"], "Example Title");
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn to_json_value(&self) -> serde_json::Value {
+        let mut value = serde_json::to_value(self).unwrap_or_default();
+        if let Some(map) = value.as_object_mut() {
+            map.insert("schema_version".to_string(), Self::SCHEMA_VERSION.into());
+        }
+        value
+    }
+
+    /// Deserializes a citation from JSON written by this or any earlier
+    /// version of biblib.
     ///
-    /// A Result containing a vector of parsed Citations or a CitationError
+    /// Fields renamed since the record's `schema_version` are read under their
+    /// old names, and missing or `null` fields take their default value. Keys
+    /// that are not fields are ignored.
     ///
     /// # Errors
     ///
-    /// Returns `CitationError` if the input is malformed
-    fn parse(&self, input: &str) -> Result<Vec<Citation>>;
-}
+    /// Returns an error if the value is not an object, if `schema_version` is
+    /// newer than [`Citation::SCHEMA_VERSION`], or if a field has the wrong
+    /// type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::Citation;
+    ///
+    /// // A record written before `isbn`, `series` and most other fields existed
+    /// let old = r#"{"id": "1", "title": "Old Record", "year": 2019, "keywords": null}"#;
+    /// let value = serde_json::from_str(old).unwrap();
+    /// let citation = Citation::from_json_migrating(value).unwrap();
+    /// assert_eq!(citation.title, "Old Record");
+    /// assert_eq!(citation.year, Some(2019));
+    /// assert!(citation.keywords.is_empty());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn from_json_migrating(value: serde_json::Value) -> Result<Citation> {
+        let serde_json::Value::Object(mut map) = value else {
+            return Err(CitationError::InvalidFormat(
+                "a citation must be a JSON object".to_string(),
+            ));
+        };
+        let version = match map.remove("schema_version") {
+            None | Some(serde_json::Value::Null) => 0,
+            Some(version) => version
+                .as_u64()
+                .and_then(|version| u32::try_from(version).ok())
+                .ok_or_else(|| CitationError::InvalidFieldValue {
+                    field: "schema_version".to_string(),
+                    message: format!("{} is not a version number", version),
+                })?,
+        };
+        if version > Self::SCHEMA_VERSION {
+            return Err(CitationError::InvalidFieldValue {
+                field: "schema_version".to_string(),
+                message: format!(
+                    "version {} is newer than the supported version {}",
+                    version,
+                    Self::SCHEMA_VERSION
+                ),
+            });
+        }
 
-/// Format detection and automatic parsing of citation files
-///
-/// # Arguments
-///
-/// * `content` - The content of the file to parse
-/// * `source` - Source of citations
-///
-/// # Returns
-///
-/// A Result containing a vector of parsed Citations and the detected format,
-/// or a CitationError if parsing fails
-///
-/// # Examples
-///
-/// ```
-/// use biblib::detect_and_parse;
-/// 
-/// let content = r#"TY  - JOUR
-/// TI  - Example Title
-/// ER  -"#;
-///
-/// let (citations, format) = detect_and_parse(content, "Cochrane").unwrap();
-/// assert_eq!(format, "RIS");
-/// assert_eq!(citations[0].title, "Example Title");
-/// assert_eq!(citations[0].source.as_deref(), Some("Cochrane"));
-/// ```
-pub fn detect_and_parse(content: &str, source: &str) -> Result<(Vec<Citation>, &'static str)> {
-    let trimmed = content.trim();
-    
-    // Empty content check
-    if trimmed.is_empty() {
-        return Err(CitationError::InvalidFormat("Empty content".into()));
+        rename_fields(&mut map, version, RENAMED_FIELDS);
+        if let serde_json::Value::Object(defaults) = serde_json::to_value(Citation::default())? {
+            for (field, default) in defaults {
+                let value = map.entry(field).or_insert(serde_json::Value::Null);
+                if value.is_null() {
+                    *value = default;
+                }
+            }
+        }
+        Ok(serde_json::from_value(serde_json::Value::Object(map))?)
     }
 
-    // Try to detect format based on content patterns
-    if trimmed.starts_with("<?xml") || trimmed.starts_with("<xml>") {
-        // EndNote XML format
-        #[cfg(feature = "xml")]
-        {
-            let parser = EndNoteXmlParser::new().with_source(source);
-            return parser.parse(content).map(|citations| (citations, "EndNote XML"));
-        }
-        #[cfg(not(feature = "xml"))]
-        return Err(CitationError::Other("EndNote XML support not enabled".into()));
+    /// Returns the people listed in `authors` who wrote the work, skipping
+    /// editors and translators, see [`Author::is_author`].
+    ///
+    /// Matching uses these, so an edited volume listing its editors first is
+    /// still compared by its first author.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{Author, AuthorRole, Citation};
+    ///
+    /// let citation = Citation {
+    ///     authors: vec![
+    ///         Author {
+    ///             family_name: "Doe".to_string(),
+    ///             role: Some(AuthorRole::Editor),
+    ///             ..Default::default()
+    ///         },
+    ///         Author {
+    ///             family_name: "Smith".to_string(),
+    ///             ..Default::default()
+    ///         },
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    /// let authors: Vec<&str> = citation.authors_only().map(|a| a.family_name.as_str()).collect();
+    /// assert_eq!(authors, ["Smith"]);
+    /// ```
+    pub fn authors_only(&self) -> impl Iterator<Item = &Author> {
+        self.authors.iter().filter(|author| author.is_author())
     }
 
-    // Check for RIS format (starts with TY or has TY  - pattern)
-    if trimmed.starts_with("TY  -") || trimmed.contains("\nTY  -") {
-        #[cfg(feature = "ris")]
-        {
-            let parser = RisParser::new().with_source(source);
-            return parser.parse(content).map(|citations| (citations, "RIS"));
-        }
-        #[cfg(not(feature = "ris"))]
-        return Err(CitationError::Other("RIS support not enabled".into()));
+    /// Returns the people listed in `authors` with the editor role.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{Author, AuthorRole, Citation};
+    ///
+    /// let citation = Citation {
+    ///     authors: vec![
+    ///         Author {
+    ///             family_name: "Smith".to_string(),
+    ///             ..Default::default()
+    ///         },
+    ///         Author {
+    ///             family_name: "Doe".to_string(),
+    ///             role: Some(AuthorRole::Editor),
+    ///             ..Default::default()
+    ///         },
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    /// let editors: Vec<&str> = citation.editors().map(|a| a.family_name.as_str()).collect();
+    /// assert_eq!(editors, ["Doe"]);
+    /// ```
+    pub fn editors(&self) -> impl Iterator<Item = &Author> {
+        self.authors
+            .iter()
+            .filter(|author| author.role == Some(AuthorRole::Editor))
     }
 
-    // Check for PubMed format (starts with PMID- or has PMID- pattern)
-    if trimmed.starts_with("PMID-") || trimmed.contains("\nPMID-") {
-        #[cfg(feature = "pubmed")]
-        {
-            let parser = PubMedParser::new().with_source(source);
-            return parser.parse(content).map(|citations| (citations, "PubMed"));
+    /// Looks up an entry in `extra_fields`, ignoring the case of the key.
+    ///
+    /// Parsers do not agree on how field names are cased, so `"Keywords"` and
+    /// `"keywords"` are treated as the same key. An exact match is preferred;
+    /// otherwise the lexicographically smallest matching key wins, so the result
+    /// never depends on the map's iteration order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::Citation;
+    ///
+    /// let mut citation = Citation::default();
+    /// citation
+    ///     .extra_fields
+    ///     .insert("M3".to_string(), vec!["Article".to_string()]);
+    ///
+    /// assert_eq!(citation.extra("m3"), Some(&["Article".to_string()][..]));
+    /// assert_eq!(citation.extra("missing"), None);
+    /// ```
+    #[must_use]
+    pub fn extra(&self, key: &str) -> Option<&[String]> {
+        if let Some(values) = self.extra_fields.get(key) {
+            return Some(values);
         }
-        #[cfg(not(feature = "pubmed"))]
-        return Err(CitationError::Other("PubMed support not enabled".into()));
+
+        self.extra_fields
+            .iter()
+            .filter(|(k, _)| k.eq_ignore_ascii_case(key))
+            .min_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, values)| values.as_slice())
     }
 
-    Err(CitationError::InvalidFormat(
-        "Unable to detect citation format".into(),
-    ))
-}
+    /// Returns the first value of an `extra_fields` entry, ignoring the case of the key.
+    ///
+    /// See [`Citation::extra`] for how the key is matched.
+    #[must_use]
+    pub fn extra_first(&self, key: &str) -> Option<&str> {
+        self.extra(key)
+            .and_then(|values| values.first())
+            .map(String::as_str)
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Checks whether two citations describe the same work.
+    ///
+    /// Unlike structural equality, this ignores differences in formatting and
+    /// metadata coverage between sources. Identifiers are checked in order of
+    /// precedence, and the first one present on both citations decides:
+    ///
+    /// 1. DOI, compared case-insensitively and without URL prefixes
+    /// 2. PMID
+    /// 3. Otherwise, the normalized title, the year and the first author's
+    ///    family name must all match
+    ///
+    /// So two citations with different DOIs are never the same work, even if
+    /// their titles match, as happens with errata and replies.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::Citation;
+    ///
+    /// let a = Citation {
+    ///     title: "Example Title".to_string(),
+    ///     doi: Some("10.1000/EXAMPLE".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// let b = Citation {
+    ///     title: "Example title.".to_string(),
+    ///     doi: Some("https://doi.org/10.1000/example".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// assert!(a.same_work(&b));
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn same_work(&self, other: &Citation) -> bool {
+        let dois = |c: &Citation| c.dois().filter_map(utils::format_doi).collect::<Vec<_>>();
+        let (a, b) = (dois(self), dois(other));
+        if !a.is_empty() && !b.is_empty() {
+            return a.iter().any(|doi| b.contains(doi));
+        }
 
-    #[test]
-    fn test_citation_error_display() {
-        let error = CitationError::InvalidFormat("Invalid line".to_string());
-        assert_eq!(error.to_string(), "Parse error: Invalid line");
-    }
+        fn pmid(c: &Citation) -> Option<&str> {
+            c.pmid
+                .as_deref()
+                .map(str::trim)
+                .filter(|pmid| !pmid.is_empty())
+        }
+        if let (Some(a), Some(b)) = (pmid(self), pmid(other)) {
+            return a == b;
+        }
 
-    #[test]
-    fn test_author_equality() {
-        let author1 = Author {
-            family_name: "Smith".to_string(),
-            given_name: "John".to_string(),
-            affiliation: None,
+        let first_author = |c: &Citation| {
+            c.authors_only()
+                .next()
+                .map(|author| author.family_name.trim().to_lowercase())
         };
-        let author2 = Author {
-            family_name: "Smith".to_string(),
-            given_name: "John".to_string(),
-            affiliation: None,
+        let title = utils::normalize_title(&self.title);
+        !title.is_empty()
+            && title == utils::normalize_title(&other.title)
+            && self.year == other.year
+            && first_author(self) == first_author(other)
+    }
+
+    /// Returns a fingerprint of the work that is stable across program runs.
+    ///
+    /// The fingerprint is a 64-bit FNV-1a hash of the normalized title, the
+    /// year and the first author's family name, compared as in
+    /// [`Citation::same_work`]. A citation without a title is fingerprinted by
+    /// its DOI instead, and one with neither by its PMID. The value does not
+    /// depend on the platform or Rust version, so it can be stored and compared
+    /// by later runs.
+    ///
+    /// Citations for which `same_work` holds usually share a fingerprint, with
+    /// exceptions:
+    ///
+    /// - citations matched by DOI or PMID alone, whose titles, years or first
+    ///   authors differ, get different fingerprints
+    /// - citations with equal titles, years and first authors but different
+    ///   DOIs, such as an article and its erratum with a copied title, share a
+    ///   fingerprint although they are not the same work
+    /// - as with any 64-bit hash, unrelated citations collide very rarely
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::Citation;
+    ///
+    /// let a = Citation {
+    ///     title: "Example Title".to_string(),
+    ///     year: Some(2020),
+    ///     ..Default::default()
+    /// };
+    /// let b = Citation {
+    ///     title: "EXAMPLE TITLE.".to_string(),
+    ///     year: Some(2020),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(a.fingerprint(), b.fingerprint());
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn fingerprint(&self) -> u64 {
+        let title = utils::normalize_title(&self.title);
+        let key = if !title.is_empty() {
+            let year = self.year.map(|year| year.to_string()).unwrap_or_default();
+            let author = self
+                .authors_only()
+                .next()
+                .map(|author| author.family_name.trim().to_lowercase())
+                .unwrap_or_default();
+            format!("title\u{1f}{}\u{1f}{}\u{1f}{}", title, year, author)
+        } else if let Some(doi) = self.dois().find_map(utils::format_doi) {
+            format!("doi\u{1f}{}", doi)
+        } else {
+            format!(
+                "pmid\u{1f}{}",
+                self.pmid.as_deref().unwrap_or_default().trim()
+            )
         };
-        assert_eq!(author1, author2);
+        utils::fnv1a_hash(key.as_bytes())
     }
 
-    #[test]
-    fn test_detect_and_parse_ris() {
-        let content = r#"TY  - JOUR
-TI  - Test Title
-AU  - Smith, John
-ER  -"#;
+    /// Returns all DOIs of the citation, starting with the primary `doi`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::Citation;
+    ///
+    /// let citation = Citation {
+    ///     doi: Some("10.1000/article".to_string()),
+    ///     all_dois: vec!["10.5061/dryad.data".to_string(), "10.1000/article".to_string()],
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let dois: Vec<&str> = citation.dois().collect();
+    /// assert_eq!(dois, ["10.1000/article", "10.5061/dryad.data"]);
+    /// ```
+    pub fn dois(&self) -> impl Iterator<Item = &str> {
+        let primary = self.doi.as_deref();
+        primary.into_iter().chain(
+            self.all_dois
+                .iter()
+                .map(String::as_str)
+                .filter(move |doi| Some(*doi) != primary),
+        )
+    }
 
-        let (citations, format) = detect_and_parse(content, "Google Scholar").unwrap();
-        assert_eq!(format, "RIS");
-        assert_eq!(citations[0].title, "Test Title");
-        assert_eq!(citations[0].source.as_deref(), Some("Google Scholar"));
+    /// Returns the arXiv abstract page of the citation, if it has an arXiv ID.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::Citation;
+    ///
+    /// let citation = Citation {
+    ///     arxiv_id: Some("hep-th/9711200".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(
+    ///     citation.arxiv_url().as_deref(),
+    ///     Some("https://arxiv.org/abs/hep-th/9711200")
+    /// );
+    /// assert_eq!(Citation::default().arxiv_url(), None);
+    /// ```
+    #[must_use]
+    pub fn arxiv_url(&self) -> Option<String> {
+        self.arxiv_id.as_deref().map(|id| {
+            let mut url = String::from("https://arxiv.org/abs/");
+            url.push_str(id);
+            url
+        })
     }
 
-    #[test]
-    fn test_detect_and_parse_pubmed() {
-        let content = r#"PMID- 12345678
-TI  - Test Title
-FAU - Smith, John"#;
+    /// Returns the valid ISBNs of the citation as bare digits, without repeats.
+    ///
+    /// ISBNs whose check digit does not match are left out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::Citation;
+    ///
+    /// let citation = Citation {
+    ///     isbn: vec![
+    ///         "978-0-306-40615-7".to_string(),
+    ///         "ISBN 9780306406157".to_string(),
+    ///         "978-0-306-40615-8".to_string(),
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(citation.valid_isbns(), ["9780306406157"]);
+    /// ```
+    #[must_use]
+    pub fn valid_isbns(&self) -> Vec<String> {
+        let mut isbns = Vec::new();
+        for isbn in self
+            .isbn
+            .iter()
+            .filter_map(|isbn| utils::normalize_isbn(isbn))
+        {
+            if !isbns.contains(&isbn) {
+                isbns.push(isbn);
+            }
+        }
+        isbns
+    }
 
-        let (citations, format) = detect_and_parse(content, "Pubmed").unwrap();
-        assert_eq!(format, "PubMed");
-        assert_eq!(citations[0].title, "Test Title");
-        assert_eq!(citations[0].source.as_deref(), Some("Pubmed"));
+    /// Adds a DOI found in a record to `all_dois` and updates the primary `doi`.
+    ///
+    /// Values that are not DOIs are ignored.
+    #[cfg(any(
+        feature = "csv",
+        feature = "pubmed",
+        feature = "xml",
+        feature = "ris",
+        feature = "refer",
+        feature = "tei",
+        feature = "crossref"
+    ))]
+    pub(crate) fn add_doi(&mut self, value: &str) {
+        if let Some(doi) = utils::format_doi(value) {
+            if !self.all_dois.contains(&doi) {
+                self.all_dois.push(doi);
+            }
+            self.doi = utils::primary_doi(&self.all_dois).cloned();
+        }
     }
 
-    #[test]
-    fn test_detect_and_parse_endnote() {
-        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
-<xml><records><record>
-<titles><title>Test Title</title></titles>
-</record></records></xml>"#;
+    /// Checks whether the citation is a correction notice, such as an erratum or
+    /// a retraction, based on its title.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::Citation;
+    ///
+    /// let erratum = Citation {
+    ///     title: "Erratum: Sleep and cardiovascular health".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// assert!(erratum.is_correction());
+    /// assert_eq!(erratum.corrected_title(), Some("Sleep and cardiovascular health"));
+    ///
+    /// let article = Citation {
+    ///     title: "Correction of scoliosis: a review".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// assert!(!article.is_correction());
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn is_correction(&self) -> bool {
+        self.corrected_title().is_some()
+    }
 
-        let (citations, format) = detect_and_parse(content, "Embase").unwrap();
-        assert_eq!(format, "EndNote XML");
-        assert_eq!(citations[0].title, "Test Title");
-        assert_eq!(citations[0].source.as_deref(), Some("Embase"));
+    /// Returns the title of the work a correction notice refers to.
+    ///
+    /// Titles prefixed with `"Erratum:"`, `"Correction to:"`, `"Corrigendum:"`,
+    /// `"Retraction of:"`, `"RETRACTED ARTICLE:"`, `"Expression of concern:"`
+    /// and similar are recognized. The returned title is empty when the notice
+    /// does not name the work. Returns `None` if the citation is not a correction.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn corrected_title(&self) -> Option<&str> {
+        utils::correction_target(&self.title)
     }
 
-    #[test]
-    fn test_detect_and_parse_empty() {
-        let result = detect_and_parse("", "Any Source");
-        assert!(matches!(result, Err(CitationError::InvalidFormat(_))));
+    /// Splits the title into its main title and subtitle.
+    ///
+    /// The title is split at the first colon that is followed by whitespace, as in
+    /// `"Main title: an informative subtitle"`. Colons in ratios and times such as
+    /// `1:2` or `10:30` are not treated as separators. Returns `None` for the
+    /// subtitle when the title has no separator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::Citation;
+    ///
+    /// let citation = Citation {
+    ///     title: "Deep learning: a review".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(
+    ///     citation.title_parts(),
+    ///     ("Deep learning".to_string(), Some("a review".to_string()))
+    /// );
+    /// ```
+    #[must_use]
+    pub fn title_parts(&self) -> (String, Option<String>) {
+        for (i, _) in self.title.match_indices(':') {
+            let main = self.title[..i].trim();
+            let rest = &self.title[i + 1..];
+            let subtitle = rest.trim();
+
+            let followed_by_space = rest.starts_with(char::is_whitespace);
+            let is_ratio = main.ends_with(|c: char| c.is_ascii_digit())
+                && subtitle.starts_with(|c: char| c.is_ascii_digit());
+            if followed_by_space && !is_ratio && !main.is_empty() && !subtitle.is_empty() {
+                return (main.to_string(), Some(subtitle.to_string()));
+            }
+        }
+        (self.title.trim().to_string(), None)
     }
 
-    #[test]
-    fn test_detect_and_parse_unknown() {
-        let content = "Some random content\nthat doesn't match\nany known format";
-        let result = detect_and_parse(content, "Unknown");
-        assert!(matches!(result, Err(CitationError::InvalidFormat(_))));
+    /// Returns the title, title-cased if it is written entirely in capitals.
+    ///
+    /// Meant for display of records from legacy databases that store titles as
+    /// `"THE EFFECTS OF..."`. Small words such as `"of"` and `"the"` are
+    /// lowercased within the title, while known acronyms (`"DNA"`, `"COVID"`,
+    /// `"mRNA"`), Roman numerals and words containing digits keep their
+    /// capitals. Titles in mixed or lower case are returned unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::Citation;
+    ///
+    /// let citation = |title: &str| Citation {
+    ///     title: title.to_string(),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(
+    ///     citation("THE EFFECTS OF MRNA VACCINES ON COVID-19: A PHASE II TRIAL").title_case(),
+    ///     "The Effects of mRNA Vaccines on COVID-19: A Phase II Trial"
+    /// );
+    /// assert_eq!(
+    ///     citation("The effects of DNA methylation").title_case(),
+    ///     "The effects of DNA methylation"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn title_case(&self) -> String {
+        utils::title_case(&self.title)
+    }
+
+    /// Returns the kind of work, classified from `citation_type`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{Citation, CitationType};
+    ///
+    /// let citation = Citation {
+    ///     citation_type: vec!["Book Section".to_string()],
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(citation.kind(), CitationType::BookChapter);
+    /// ```
+    #[must_use]
+    pub fn kind(&self) -> CitationType {
+        CitationType::from_types(&self.citation_type)
+    }
+
+    /// Returns the fields this kind of work needs but the citation lacks.
+    ///
+    /// The fields checked depend on [`Citation::kind`], see
+    /// [Required fields](CitationType#required-fields). Fields are named as in
+    /// `Citation` and listed in that order. Text fields holding only
+    /// whitespace count as missing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{Author, Citation};
+    ///
+    /// let book = Citation {
+    ///     citation_type: vec!["BOOK".to_string()],
+    ///     title: "Systematic Reviews in Health Care".to_string(),
+    ///     authors: vec![Author {
+    ///         family_name: "Egger".to_string(),
+    ///         ..Default::default()
+    ///     }],
+    ///     year: Some(2001),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(book.missing_required_fields(), vec!["publisher"]);
+    /// assert!(!book.is_valid_for_type());
+    /// ```
+    #[must_use]
+    pub fn missing_required_fields(&self) -> Vec<String> {
+        let present = |value: &Option<String>| {
+            value
+                .as_deref()
+                .is_some_and(|value| !value.trim().is_empty())
+        };
+        let has_field = |field: &str| match field {
+            "title" => !self.title.trim().is_empty(),
+            "authors" => !self.authors.is_empty(),
+            "journal" => present(&self.journal),
+            "year" => self.year.is_some(),
+            "publisher" => present(&self.publisher),
+            "conference_name" => present(&self.conference_name),
+            field => unreachable!("no check for required field {field}"),
+        };
+        self.kind()
+            .required_fields()
+            .iter()
+            .filter(|field| !has_field(field))
+            .map(|field| field.to_string())
+            .collect()
+    }
+
+    /// Returns `true` if the citation has every field its kind of work needs,
+    /// see [`Citation::missing_required_fields`].
+    #[must_use]
+    pub fn is_valid_for_type(&self) -> bool {
+        self.missing_required_fields().is_empty()
+    }
+
+    /// Returns the number of words in the abstract, or 0 without one.
+    ///
+    /// Words are runs of letters and digits, so punctuation is not counted and
+    /// `"well-known"` or `"3.5"` count once. Each Chinese or Japanese ideograph
+    /// or kana counts as a word.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::Citation;
+    ///
+    /// let citation = Citation {
+    ///     abstract_text: Some("Background: a well-known effect (n = 12).".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(citation.abstract_word_count(), 6);
+    /// assert_eq!(Citation::default().abstract_word_count(), 0);
+    /// ```
+    #[must_use]
+    pub fn abstract_word_count(&self) -> usize {
+        self.abstract_text
+            .as_deref()
+            .map_or(0, |text| utils::words(text).len())
+    }
+
+    /// Returns the Flesch reading-ease score of the abstract.
+    ///
+    /// Higher scores are easier to read: around 60 to 70 is plain English,
+    /// and scientific abstracts typically score below 30. Syllables are
+    /// estimated with English spelling rules, so the score is only meaningful
+    /// for English text. Returns `None` without an abstract or when it has
+    /// no words.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::Citation;
+    ///
+    /// let citation = Citation {
+    ///     abstract_text: Some("The cat sat on the mat. It was warm.".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// assert!(citation.abstract_reading_ease().unwrap() > 90.0);
+    /// assert_eq!(Citation::default().abstract_reading_ease(), None);
+    /// ```
+    #[must_use]
+    pub fn abstract_reading_ease(&self) -> Option<f64> {
+        let text = self.abstract_text.as_deref()?;
+        let words = utils::words(text);
+        if words.is_empty() {
+            return None;
+        }
+        let syllables: usize = words.iter().map(|word| utils::count_syllables(word)).sum();
+        let words_per_sentence = words.len() as f64 / utils::count_sentences(text) as f64;
+        let syllables_per_word = syllables as f64 / words.len() as f64;
+        Some(206.835 - 1.015 * words_per_sentence - 84.6 * syllables_per_word)
+    }
+
+    /// Returns the JSON Schema (draft 2020-12) describing the serialized form
+    /// of a citation.
+    ///
+    /// Consumers of biblib's JSON output in other languages can use it to
+    /// validate records or generate types. The schema is also shipped as
+    /// `schema/citation.schema.json`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::Citation;
+    ///
+    /// let schema = Citation::json_schema();
+    /// assert!(schema.contains("\"title\": \"Citation\""));
+    /// ```
+    #[cfg(feature = "schema")]
+    #[must_use]
+    pub fn json_schema() -> &'static str {
+        include_str!("../schema/citation.schema.json")
+    }
+
+    /// Formats the citation as a RIS record.
+    ///
+    /// The output is the same as [`ris::write_ris`] writes for this citation
+    /// alone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::Citation;
+    ///
+    /// let citation = Citation {
+    ///     citation_type: vec!["JOUR".to_string()],
+    ///     title: "Example Title".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(citation.to_ris_string(), "TY  - JOUR\nTI  - Example Title\nER  - \n\n");
+    /// ```
+    #[cfg(feature = "ris")]
+    #[must_use]
+    pub fn to_ris_string(&self) -> String {
+        let mut output = Vec::new();
+        ris::write_ris([self], &mut output).expect("writing to a Vec cannot fail");
+        String::from_utf8(output).expect("RIS output is valid UTF-8")
+    }
+
+    /// Formats the citation as a BibTeX entry.
+    ///
+    /// The output is the same as [`bibtex::write_bibtex`] writes for this
+    /// citation alone.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::Citation;
+    ///
+    /// let citation = Citation {
+    ///     id: "example".to_string(),
+    ///     title: "Example Title".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(
+    ///     citation.to_bibtex_string(),
+    ///     "@misc{example,\n  title = {Example Title},\n}\n\n"
+    /// );
+    /// ```
+    #[cfg(feature = "bibtex")]
+    #[must_use]
+    pub fn to_bibtex_string(&self) -> String {
+        let mut output = Vec::new();
+        bibtex::write_bibtex([self], &mut output).expect("writing to a Vec cannot fail");
+        String::from_utf8(output).expect("BibTeX output is valid UTF-8")
+    }
+
+    /// Formats the citation as a reference string for display.
+    ///
+    /// See [`CiteStyle`] for the supported styles. Missing fields are left
+    /// out along with their punctuation.
+    ///
+    /// # Arguments
+    ///
+    /// * `style` - The citation style to use
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{Citation, CiteStyle};
+    ///
+    /// let citation = Citation {
+    ///     title: "Example Title".to_string(),
+    ///     journal: Some("Journal of Examples".to_string()),
+    ///     year: Some(2023),
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(
+    ///     citation.format(CiteStyle::Vancouver),
+    ///     "Example Title. Journal of Examples. 2023."
+    /// );
+    /// ```
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn format(&self, style: CiteStyle) -> String {
+        style::format_citation(self, style)
+    }
+}
+
+/// Represents a group of duplicate citations with one unique citation
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    /// The unique (original) citation
+    pub unique: Citation,
+    /// The duplicate citations
+    pub duplicates: Vec<Citation>,
+    /// Ids of the citations in the group that are preprints, such as bioRxiv or
+    /// arXiv records, of the same work as the published citations
+    #[serde(default)]
+    pub preprint_ids: Vec<String>,
+    /// Ids of the citations in the group that are correction notices, such as
+    /// errata or retractions, of the unique citation
+    #[serde(default)]
+    pub correction_ids: Vec<String>,
+}
+
+impl DuplicateGroup {
+    /// Returns the distinct sources of all citations in the group.
+    ///
+    /// The unique citation's source comes first, followed by the sources of the
+    /// duplicates in order. Citations without a source are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{Citation, DuplicateGroup};
+    ///
+    /// let group = DuplicateGroup {
+    ///     unique: Citation {
+    ///         source: Some("PubMed".to_string()),
+    ///         ..Default::default()
+    ///     },
+    ///     duplicates: vec![Citation {
+    ///         source: Some("Embase".to_string()),
+    ///         ..Default::default()
+    ///     }],
+    ///     ..Default::default()
+    /// };
+    /// assert_eq!(group.sources(), vec!["PubMed", "Embase"]);
+    /// ```
+    #[must_use]
+    pub fn sources(&self) -> Vec<&str> {
+        let mut sources = Vec::new();
+        for citation in core::iter::once(&self.unique).chain(&self.duplicates) {
+            if let Some(source) = citation.source.as_deref() {
+                if !sources.contains(&source) {
+                    sources.push(source);
+                }
+            }
+        }
+        sources
+    }
+
+    /// Returns the unique citation completed with the metadata of its
+    /// duplicates.
+    ///
+    /// Fields the unique citation lacks are taken from the first duplicate that
+    /// has them, and list fields, such as keywords, URLs and `extra_fields`,
+    /// gain the values of the duplicates they lack. When the author lists are
+    /// the same length, missing affiliations and ORCID iDs are filled in by
+    /// position. Values the unique citation already has are kept, as are its
+    /// `id` and `source`: each duplicate is merged in with
+    /// [`Citation::merge_from`] and [`MergePolicy::FillEmpty`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{Citation, DuplicateGroup};
+    ///
+    /// let group = DuplicateGroup {
+    ///     unique: Citation {
+    ///         title: "Example Title".to_string(),
+    ///         keywords: vec!["one".to_string()],
+    ///         ..Default::default()
+    ///     },
+    ///     duplicates: vec![Citation {
+    ///         title: "Example title".to_string(),
+    ///         abstract_text: Some("An abstract.".to_string()),
+    ///         keywords: vec!["one".to_string(), "two".to_string()],
+    ///         ..Default::default()
+    ///     }],
+    ///     ..Default::default()
+    /// };
+    /// let merged = group.merged();
+    /// assert_eq!(merged.title, "Example Title");
+    /// assert_eq!(merged.abstract_text.as_deref(), Some("An abstract."));
+    /// assert_eq!(merged.keywords, vec!["one", "two"]);
+    /// ```
+    #[must_use]
+    pub fn merged(&self) -> Citation {
+        let mut merged = self.unique.clone();
+        for duplicate in &self.duplicates {
+            merged.merge_from(duplicate, MergePolicy::FillEmpty);
+        }
+        merged
+    }
+}
+
+/// How [`Citation::merge_from`] settles fields both citations have.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Only fill what this citation lacks: missing fields and an empty title
+    /// or author list. This is how [`DuplicateGroup::merged`] merges.
+    #[default]
+    FillEmpty,
+    /// Keep this citation's values, but take the other author list when it is
+    /// longer or more complete
+    PreferSelf,
+    /// Take the other citation's values wherever it has one, and its author
+    /// list unless this one is longer or more complete
+    PreferOther,
+}
+
+impl Citation {
+    /// Merges `other` into this citation in place.
+    ///
+    /// Single-valued fields are settled by `policy`. List fields, such as
+    /// `keywords`, `urls` and `grants`, and each `extra_fields` entry gain the
+    /// values of `other` they lack, whatever the policy. An author list is more
+    /// complete than another of the same length when more of its authors have
+    /// an affiliation or ORCID iD; when both lists are the same length, missing
+    /// affiliations and ORCID iDs of the kept list are filled in by position.
+    /// The `id` and `source` of this citation are always kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{Citation, MergePolicy};
+    ///
+    /// let other = Citation {
+    ///     title: "Corrected title".to_string(),
+    ///     pages: Some("1-10".to_string()),
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let mut kept = Citation {
+    ///     title: "Title".to_string(),
+    ///     ..Default::default()
+    /// };
+    /// kept.merge_from(&other, MergePolicy::FillEmpty);
+    /// assert_eq!(kept.title, "Title");
+    /// assert_eq!(kept.pages.as_deref(), Some("1-10"));
+    ///
+    /// kept.merge_from(&other, MergePolicy::PreferOther);
+    /// assert_eq!(kept.title, "Corrected title");
+    /// ```
+    pub fn merge_from(&mut self, other: &Citation, policy: MergePolicy) {
+        let replace = policy == MergePolicy::PreferOther;
+        if !other.title.is_empty() && (self.title.is_empty() || replace) {
+            self.title.clone_from(&other.title);
+        }
+        self.merge_authors(&other.authors, policy);
+
+        fn merge<T: Clone>(field: &mut Option<T>, value: &Option<T>, replace: bool) {
+            if value.is_some() && (field.is_none() || replace) {
+                field.clone_from(value);
+            }
+        }
+        for (field, value) in [
+            (&mut self.journal, &other.journal),
+            (&mut self.journal_abbr, &other.journal_abbr),
+            (&mut self.volume, &other.volume),
+            (&mut self.issue, &other.issue),
+            (&mut self.pages, &other.pages),
+            (&mut self.status, &other.status),
+            (&mut self.doi, &other.doi),
+            (&mut self.pmid, &other.pmid),
+            (&mut self.pmc_id, &other.pmc_id),
+            (&mut self.arxiv_id, &other.arxiv_id),
+            (&mut self.abstract_text, &other.abstract_text),
+            (&mut self.copyright, &other.copyright),
+            (&mut self.language, &other.language),
+            (&mut self.publisher, &other.publisher),
+            (&mut self.place, &other.place),
+            (&mut self.conference_name, &other.conference_name),
+            (&mut self.series, &other.series),
+            (&mut self.accession_number, &other.accession_number),
+        ] {
+            merge(field, value, replace);
+        }
+        merge(&mut self.year, &other.year, replace);
+        merge(&mut self.cited_by_count, &other.cited_by_count, replace);
+        merge(&mut self.reference_count, &other.reference_count, replace);
+
+        fn extend<T: Clone + PartialEq>(values: &mut Vec<T>, others: &[T]) {
+            for value in others {
+                if !values.contains(value) {
+                    values.push(value.clone());
+                }
+            }
+        }
+        for (values, others) in [
+            (&mut self.citation_type, &other.citation_type),
+            (&mut self.issn, &other.issn),
+            (&mut self.isbn, &other.isbn),
+            (&mut self.all_dois, &other.all_dois),
+            (&mut self.keywords, &other.keywords),
+            (&mut self.urls, &other.urls),
+            (&mut self.mesh_terms, &other.mesh_terms),
+        ] {
+            extend(values, others);
+        }
+        extend(&mut self.grants, &other.grants);
+        extend(&mut self.related, &other.related);
+        for (key, others) in &other.extra_fields {
+            extend(self.extra_fields.entry(key.clone()).or_default(), others);
+        }
+    }
+
+    /// Chooses between this author list and `others` as described in
+    /// [`Citation::merge_from`]
+    fn merge_authors(&mut self, others: &[Author], policy: MergePolicy) {
+        let completeness = |authors: &[Author]| {
+            let details = authors
+                .iter()
+                .map(|a| usize::from(a.affiliation.is_some()) + usize::from(a.orcid.is_some()))
+                .sum::<usize>();
+            (authors.len(), details)
+        };
+        let take_others = match policy {
+            MergePolicy::FillEmpty => self.authors.is_empty(),
+            MergePolicy::PreferSelf => completeness(others) > completeness(&self.authors),
+            MergePolicy::PreferOther => completeness(others) >= completeness(&self.authors),
+        };
+        let replaced;
+        let others = if take_others {
+            replaced = core::mem::replace(&mut self.authors, others.to_vec());
+            &replaced
+        } else {
+            others
+        };
+        if self.authors.len() == others.len() {
+            for (author, other) in self.authors.iter_mut().zip(others) {
+                if author.affiliation.is_none() {
+                    author.affiliation.clone_from(&other.affiliation);
+                }
+                if author.orcid.is_none() {
+                    author.orcid.clone_from(&other.orcid);
+                }
+            }
+        }
+    }
+}
+
+/// Default longest field, in bytes, that parsers keep: 1 MiB.
+///
+/// Longer fields, e.g. from corrupt or malicious files, are truncated with a
+/// warning. Parsers change it with their `with_max_field_length` method.
+pub const DEFAULT_MAX_FIELD_LENGTH: usize = 1024 * 1024;
+
+/// Key of the `extra_fields` entry naming the parsed record a citation came
+/// from, as `"<source>:<id>"`.
+///
+/// Parsers given a source with their `with_source` method write it. Since
+/// [`Citation::merge_from`] keeps the `extra_fields` values of both citations,
+/// a [merged](DuplicateGroup::merged) citation lists every record it was built
+/// from, even after its duplicates are dropped.
+pub const SOURCE_RECORD_KEY: &str = "__source_record";
+
+/// Trait for implementing citation parsers.
+#[cfg(feature = "std")]
+pub trait CitationParser {
+    /// Parse a string containing one or more citations.
+    ///
+    /// Citations are returned in the order their records appear in the input.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The string containing citation data
+    ///
+    /// # Returns
+    ///
+    /// A Result containing a vector of parsed Citations or a CitationError
+    ///
+    /// # Errors
+    ///
+    /// Returns `CitationError` if the input is malformed
+    fn parse(&self, input: &str) -> Result<Vec<Citation>>;
+
+    /// Parse a string containing exactly one citation.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The string containing citation data
+    ///
+    /// # Errors
+    ///
+    /// Returns `CitationError` if the input is malformed, and
+    /// `CitationError::InvalidFormat` if it holds no record or more than one
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{CitationParser, RisParser};
+    ///
+    /// let parser = RisParser::new();
+    /// let citation = parser
+    ///     .parse_one("TY  - JOUR\nTI  - Example Title\nER  -\n")
+    ///     .unwrap();
+    /// assert_eq!(citation.title, "Example Title");
+    ///
+    /// let two = "TY  - JOUR\nTI  - First\nER  -\nTY  - JOUR\nTI  - Second\nER  -\n";
+    /// assert!(parser.parse_one(two).is_err());
+    /// ```
+    fn parse_one(&self, input: &str) -> Result<Citation> {
+        let mut citations = self.parse(input)?;
+        if citations.len() == 1 {
+            Ok(citations.remove(0))
+        } else {
+            Err(CitationError::InvalidFormat(format!(
+                "Expected one record, found {}",
+                citations.len()
+            )))
+        }
+    }
+
+    /// Parse citations from a buffered reader, yielding them one at a time.
+    ///
+    /// The default implementation reads the whole input into memory and calls
+    /// [`CitationParser::parse`]. Parsers may override it to parse incrementally,
+    /// as the RIS parser does, yielding each record as soon as it has been read.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader providing citation data
+    ///
+    /// # Returns
+    ///
+    /// An iterator over parsed Citations. If reading or parsing fails, the
+    /// iterator yields the error and then ends.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{CitationParser, RisParser};
+    ///
+    /// let input = "TY  - JOUR\nTI  - Example Title\nER  -\n";
+    /// let citations: Vec<_> = RisParser::new()
+    ///     .parse_stream(input.as_bytes())
+    ///     .collect::<biblib::Result<_>>()
+    ///     .unwrap();
+    /// assert_eq!(citations[0].title, "Example Title");
+    /// ```
+    ///
+    /// Any `BufRead` works, including a locked stdin, so a tool can sit in a
+    /// pipeline such as `cat refs.ris | tool > recent.ris`. Writing and flushing
+    /// each citation as it is parsed keeps the output flowing, and keeping the
+    /// records' own `ID`s lets the output be matched back to the input:
+    ///
+    /// ```
+    /// use biblib::ris::write_ris;
+    /// use biblib::{CitationParser, RisParser};
+    /// use std::io::{self, BufRead, Write};
+    ///
+    /// fn recent(input: impl BufRead, mut output: impl Write) -> biblib::Result<()> {
+    ///     for citation in RisParser::new().with_ris_id(true).parse_stream(input) {
+    ///         let citation = citation?;
+    ///         if citation.year >= Some(2020) {
+    ///             write_ris([&citation], &mut output)?;
+    ///             output.flush()?;
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    ///
+    /// // In a command-line tool
+    /// let _run = || recent(io::stdin().lock(), io::stdout().lock());
+    ///
+    /// let input = "TY  - JOUR\nID  - 1\nTI  - Old\nPY  - 2010\nER  -\nTY  - JOUR\nID  - 2\nTI  - New\nPY  - 2021\nER  -\n";
+    /// let mut output = Vec::new();
+    /// recent(input.as_bytes(), &mut output).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(output).unwrap(),
+    ///     "TY  - JOUR\nTI  - New\nPY  - 2021\nID  - 2\nER  - \n\n"
+    /// );
+    /// ```
+    fn parse_stream<R: BufRead>(&self, mut reader: R) -> impl Iterator<Item = Result<Citation>>
+    where
+        Self: Sized,
+    {
+        let mut input = String::new();
+        let parsed = reader
+            .read_to_string(&mut input)
+            .map_err(CitationError::from)
+            .and_then(|_| self.parse(&input));
+
+        let (citations, error) = match parsed {
+            Ok(citations) => (citations, None),
+            Err(e) => (Vec::new(), Some(Err(e))),
+        };
+        error.into_iter().chain(citations.into_iter().map(Ok))
+    }
+
+    /// Name of the format read by the parser, e.g. `"RIS"`.
+    ///
+    /// This is the name [`detect_and_parse`] reports for input read with the
+    /// parser. The default implementation returns the parser's type name; the
+    /// built-in parsers return the name of their format.
+    fn format_name(&self) -> &'static str {
+        core::any::type_name::<Self>()
+    }
+
+    /// Checks whether the input looks like this parser's format.
+    ///
+    /// Implementations sniff the start or structure of the input without fully
+    /// parsing it, so a front-end can pick a parser from several candidates.
+    /// The default implementation tries to parse the input.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The string containing citation data
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{CitationParser, PubMedParser, RisParser};
+    ///
+    /// let parsers: Vec<Box<dyn CitationParser>> =
+    ///     vec![Box::new(RisParser::new()), Box::new(PubMedParser::new())];
+    ///
+    /// let input = "PMID- 12345678\nTI  - Example Title\n";
+    /// let parser = parsers.iter().find(|p| p.can_parse(input)).unwrap();
+    /// assert_eq!(parser.format_name(), "PubMed");
+    /// ```
+    fn can_parse(&self, input: &str) -> bool {
+        self.parse(input)
+            .is_ok_and(|citations| !citations.is_empty())
+    }
+}
+
+/// Format detection and automatic parsing of citation files
+///
+/// # Arguments
+///
+/// * `content` - The content of the file to parse
+/// * `source` - Source of citations
+///
+/// # Returns
+///
+/// A Result containing a vector of parsed Citations and the detected format,
+/// or a CitationError if parsing fails
+///
+/// # Examples
+///
+/// ```
+/// use biblib::detect_and_parse;
+/// 
+/// let content = r#"TY  - JOUR
+/// TI  - Example Title
+/// ER  -"#;
+///
+/// let (citations, format) = detect_and_parse(content, "Cochrane").unwrap();
+/// assert_eq!(format, "RIS");
+/// assert_eq!(citations[0].title, "Example Title");
+/// assert_eq!(citations[0].source.as_deref(), Some("Cochrane"));
+/// ```
+#[cfg(feature = "std")]
+pub fn detect_and_parse(content: &str, source: &str) -> Result<(Vec<Citation>, &'static str)> {
+    // Empty content check
+    if content.trim().is_empty() {
+        return Err(CitationError::InvalidFormat("Empty content".into()));
+    }
+
+    match detection_parsers(source)
+        .into_iter()
+        .find(|parser| parser.can_parse(content))
+    {
+        Some(parser) => parser
+            .parse(content)
+            .map(|citations| (citations, parser.format_name())),
+        None => Err(CitationError::InvalidFormat(
+            "Unable to detect citation format".into(),
+        )),
+    }
+}
+
+/// Detects the format of citation data without parsing it
+///
+/// Formats are recognized as in [`detect_and_parse`], returning the name of
+/// the format, e.g. `"RIS"`, or `None` if no enabled parser recognizes it.
+///
+/// # Arguments
+///
+/// * `content` - The citation data to inspect
+///
+/// # Examples
+///
+/// ```
+/// use biblib::detect_format;
+///
+/// assert_eq!(detect_format("TY  - JOUR\nTI  - Title\nER  -"), Some("RIS"));
+/// assert_eq!(detect_format("plain text"), None);
+/// ```
+#[cfg(feature = "std")]
+#[must_use]
+pub fn detect_format(content: &str) -> Option<&'static str> {
+    detection_parsers("")
+        .into_iter()
+        .find(|parser| parser.can_parse(content))
+        .map(|parser| parser.format_name())
+}
+
+/// The enabled parsers tried by format detection, in order of precedence
+///
+/// TEI comes before EndNote XML as both are XML. CSV is not tried, as a first
+/// line of other formats can look like a header row.
+#[cfg(feature = "std")]
+#[allow(clippy::vec_init_then_push)]
+#[cfg_attr(
+    not(any(
+        feature = "tei",
+        feature = "xml",
+        feature = "ris",
+        feature = "pubmed",
+        feature = "refer",
+        feature = "crossref"
+    )),
+    allow(unused_mut, unused_variables)
+)]
+fn detection_parsers(source: &str) -> Vec<Box<dyn CitationParser>> {
+    let mut parsers: Vec<Box<dyn CitationParser>> = Vec::new();
+    #[cfg(feature = "tei")]
+    parsers.push(Box::new(TeiParser::new().with_source(source)));
+    #[cfg(feature = "xml")]
+    parsers.push(Box::new(EndNoteXmlParser::new().with_source(source)));
+    #[cfg(feature = "ris")]
+    parsers.push(Box::new(RisParser::new().with_source(source)));
+    #[cfg(feature = "pubmed")]
+    parsers.push(Box::new(PubMedParser::new().with_source(source)));
+    #[cfg(feature = "refer")]
+    parsers.push(Box::new(ReferParser::new().with_source(source)));
+    #[cfg(feature = "crossref")]
+    parsers.push(Box::new(CrossrefParser::new().with_source(source)));
+    parsers
+}
+
+/// Reads all input from a reader, detects its format and parses it
+///
+/// Accepts any [`Read`], so compressed exports can be parsed by wrapping the
+/// file in a decompressor, e.g. `flate2::read::GzDecoder`. Gzip data is
+/// decompressed with the `gzip` feature, and otherwise reported as an error
+/// rather than misdetected.
+///
+/// # Arguments
+///
+/// * `reader` - The reader to parse from
+/// * `source` - Source of citations
+///
+/// # Errors
+///
+/// Returns `CitationError` if reading fails, the input is not UTF-8 or it
+/// cannot be parsed. See [`detect_and_parse`].
+///
+/// # Examples
+///
+/// ```
+/// use biblib::detect_and_parse_reader;
+///
+/// let input = "TY  - JOUR\nTI  - Example Title\nER  -\n";
+/// let (citations, format) = detect_and_parse_reader(input.as_bytes(), "Cochrane").unwrap();
+/// assert_eq!(format, "RIS");
+/// assert_eq!(citations[0].title, "Example Title");
+/// ```
+#[cfg(feature = "std")]
+pub fn detect_and_parse_reader<R: Read>(
+    mut reader: R,
+    source: &str,
+) -> Result<(Vec<Citation>, &'static str)> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        #[cfg(feature = "gzip")]
+        {
+            let mut decompressed = Vec::new();
+            flate2::read::MultiGzDecoder::new(bytes.as_slice()).read_to_end(&mut decompressed)?;
+            bytes = decompressed;
+        }
+        #[cfg(not(feature = "gzip"))]
+        return Err(CitationError::InvalidFormat(
+            "Input is gzip-compressed; enable the gzip feature or wrap the reader in a gzip \
+             decoder first"
+                .into(),
+        ));
+    }
+
+    let content = String::from_utf8(bytes)
+        .map_err(|e| CitationError::InvalidFormat(format!("Input is not valid UTF-8: {}", e)))?;
+    detect_and_parse(&content, source)
+}
+
+/// Reads a file, detects its format and parses it
+///
+/// # Arguments
+///
+/// * `path` - Path of the file to parse
+/// * `source` - Source of citations
+///
+/// # Errors
+///
+/// Returns `CitationError` if the file cannot be read or parsed. See
+/// [`detect_and_parse_reader`].
+#[cfg(feature = "std")]
+pub fn detect_and_parse_file<P: AsRef<Path>>(
+    path: P,
+    source: &str,
+) -> Result<(Vec<Citation>, &'static str)> {
+    detect_and_parse_reader(File::open(path)?, source)
+}
+
+/// Decompresses a gzip file, such as `pubmed.nbib.gz`, detects its format and
+/// parses it
+///
+/// # Arguments
+///
+/// * `path` - Path of the file to parse
+/// * `source` - Source of citations
+///
+/// # Errors
+///
+/// Returns `CitationError` if the file cannot be read, is not gzip-compressed
+/// or cannot be parsed. See [`detect_and_parse_reader`].
+///
+/// # Examples
+///
+/// ```no_run
+/// let (citations, format) = biblib::parse_gz_file("pubmed.nbib.gz", "PubMed").unwrap();
+/// println!("Parsed {} {format} citations", citations.len());
+/// ```
+#[cfg(feature = "gzip")]
+pub fn parse_gz_file<P: AsRef<Path>>(
+    path: P,
+    source: &str,
+) -> Result<(Vec<Citation>, &'static str)> {
+    detect_and_parse_reader(flate2::read::MultiGzDecoder::new(File::open(path)?), source)
+}
+
+/// Citations imported from a directory with [`parse_dir`]
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct DirImport {
+    /// Citations from every file that could be parsed, in path order
+    pub citations: Vec<Citation>,
+    /// Files that could not be read or parsed, with the reason
+    pub errors: Vec<(PathBuf, CitationError)>,
+}
+
+/// Parses every citation file in a directory and its subdirectories
+///
+/// Each file's format is detected as in [`detect_and_parse_file`], and files
+/// with a `.csv` extension are read as CSV. The source of each citation is the
+/// file name without its extension, e.g. `"pubmed"` for `pubmed.nbib`; use
+/// [`parse_dir_with`] to name sources differently. Hidden files are skipped.
+///
+/// A file that cannot be parsed does not stop the import: it is reported in
+/// [`DirImport::errors`].
+///
+/// # Arguments
+///
+/// * `dir` - The directory to read
+///
+/// # Errors
+///
+/// Returns `CitationError::Io` if a directory cannot be listed.
+///
+/// # Examples
+///
+/// ```no_run
+/// let import = biblib::parse_dir("exports").unwrap();
+/// for (path, error) in &import.errors {
+///     eprintln!("Skipped {}: {}", path.display(), error);
+/// }
+/// println!("Imported {} citations", import.citations.len());
+/// ```
+#[cfg(feature = "std")]
+pub fn parse_dir<P: AsRef<Path>>(dir: P) -> Result<DirImport> {
+    parse_dir_with(dir, |path| {
+        path.file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    })
+}
+
+/// Parses every citation file in a directory, naming sources with `source_for`
+///
+/// See [`parse_dir`].
+///
+/// # Arguments
+///
+/// * `dir` - The directory to read
+/// * `source_for` - Returns the source for the citations of a file, given its path
+///
+/// # Errors
+///
+/// Returns `CitationError::Io` if a directory cannot be listed.
+#[cfg(feature = "std")]
+pub fn parse_dir_with<P: AsRef<Path>>(
+    dir: P,
+    mut source_for: impl FnMut(&Path) -> String,
+) -> Result<DirImport> {
+    let mut files = Vec::new();
+    collect_files(dir.as_ref(), &mut files)?;
+    files.sort();
+
+    let mut import = DirImport::default();
+    for path in files {
+        let source = source_for(&path);
+        match parse_file(&path, &source) {
+            Ok(citations) => import.citations.extend(citations),
+            Err(e) => import.errors.push((path, e)),
+        }
+    }
+    Ok(import)
+}
+
+/// Lists the non-hidden files under `dir`, recursively
+#[cfg(feature = "std")]
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            collect_files(&path, files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Parses a file for [`parse_dir_with`], reading `.csv` files as CSV
+#[cfg(feature = "std")]
+fn parse_file(path: &Path, source: &str) -> Result<Vec<Citation>> {
+    let is_csv = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+    if is_csv {
+        #[cfg(feature = "csv")]
+        {
+            let content = std::fs::read_to_string(path)?;
+            return CsvParser::new().with_source(source).parse(&content);
+        }
+        #[cfg(not(feature = "csv"))]
+        return Err(CitationError::Other("CSV support not enabled".into()));
+    }
+    detect_and_parse_file(path, source).map(|(citations, _)| citations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_citation_error_display() {
+        let error = CitationError::InvalidFormat("Invalid line".to_string());
+        assert_eq!(error.to_string(), "Parse error: Invalid line");
+    }
+
+    #[test]
+    fn test_author_equality() {
+        let author1 = Author {
+            family_name: "Smith".to_string(),
+            given_name: "John".to_string(),
+            affiliation: None,
+            orcid: None,
+            role: None,
+        };
+        let author2 = Author {
+            family_name: "Smith".to_string(),
+            given_name: "John".to_string(),
+            affiliation: None,
+            orcid: None,
+            role: None,
+        };
+        assert_eq!(author1, author2);
+    }
+
+    #[test]
+    fn test_extra_fields_case_insensitive() {
+        let mut citation = Citation::default();
+        citation.extra_fields.insert(
+            "Keywords".to_string(),
+            vec!["a".to_string(), "b".to_string()],
+        );
+        citation
+            .extra_fields
+            .insert("keywords".to_string(), vec!["c".to_string()]);
+
+        // Exact match wins over case-insensitive ones
+        assert_eq!(citation.extra_first("keywords"), Some("c"));
+        assert_eq!(citation.extra_first("Keywords"), Some("a"));
+        // Otherwise the smallest matching key is used
+        assert_eq!(citation.extra("KEYWORDS").map(<[String]>::len), Some(2));
+        assert_eq!(citation.extra("other"), None);
+        assert_eq!(citation.extra_first("other"), None);
+    }
+
+    #[test]
+    fn test_detect_and_parse_ris() {
+        let content = r#"TY  - JOUR
+TI  - Test Title
+AU  - Smith, John
+ER  -"#;
+
+        let (citations, format) = detect_and_parse(content, "Google Scholar").unwrap();
+        assert_eq!(format, "RIS");
+        assert_eq!(citations[0].title, "Test Title");
+        assert_eq!(citations[0].source.as_deref(), Some("Google Scholar"));
+    }
+
+    #[test]
+    fn test_detect_and_parse_pubmed() {
+        let content = r#"PMID- 12345678
+TI  - Test Title
+FAU - Smith, John"#;
+
+        let (citations, format) = detect_and_parse(content, "Pubmed").unwrap();
+        assert_eq!(format, "PubMed");
+        assert_eq!(citations[0].title, "Test Title");
+        assert_eq!(citations[0].source.as_deref(), Some("Pubmed"));
+    }
+
+    #[test]
+    fn test_detect_and_parse_endnote() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xml><records><record>
+<titles><title>Test Title</title></titles>
+</record></records></xml>"#;
+
+        let (citations, format) = detect_and_parse(content, "Embase").unwrap();
+        assert_eq!(format, "EndNote XML");
+        assert_eq!(citations[0].title, "Test Title");
+        assert_eq!(citations[0].source.as_deref(), Some("Embase"));
+    }
+
+    #[test]
+    fn test_detect_and_parse_refer() {
+        let content = "%A Smith, John\n%T Test Title\n%D 2020\n";
+
+        let (citations, format) = detect_and_parse(content, "refer").unwrap();
+        assert_eq!(format, "refer");
+        assert_eq!(citations[0].title, "Test Title");
+    }
+
+    #[test]
+    fn test_detect_and_parse_tei() {
+        let content = r#"<?xml version="1.0" encoding="UTF-8"?>
+<listBibl xmlns="http://www.tei-c.org/ns/1.0"><biblStruct>
+<analytic><title level="a">Test Title</title></analytic>
+</biblStruct></listBibl>"#;
+
+        let (citations, format) = detect_and_parse(content, "GROBID").unwrap();
+        assert_eq!(format, "TEI");
+        assert_eq!(citations[0].title, "Test Title");
+        assert_eq!(citations[0].source.as_deref(), Some("GROBID"));
+    }
+
+    #[test]
+    fn test_detect_and_parse_empty() {
+        let result = detect_and_parse("", "Any Source");
+        assert!(matches!(result, Err(CitationError::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn test_detect_and_parse_unknown() {
+        let content = "Some random content\nthat doesn't match\nany known format";
+        let result = detect_and_parse(content, "Unknown");
+        assert!(matches!(result, Err(CitationError::InvalidFormat(_))));
+    }
+
+    #[test]
+    fn test_format_name_default() {
+        struct LineParser;
+        impl CitationParser for LineParser {
+            fn parse(&self, input: &str) -> Result<Vec<Citation>> {
+                Ok(input
+                    .lines()
+                    .map(|line| Citation {
+                        title: line.to_string(),
+                        ..Default::default()
+                    })
+                    .collect())
+            }
+        }
+
+        assert!(LineParser.format_name().ends_with("LineParser"));
+        assert_eq!(RisParser::new().format_name(), "RIS");
+    }
+
+    #[test]
+    fn test_parse_stream_default() {
+        let input = "TY  - JOUR\nTI  - First\nER  -\n\nTY  - JOUR\nTI  - Second\nER  -\n";
+        let titles: Vec<String> = RisParser::new()
+            .parse_stream(input.as_bytes())
+            .map(|c| c.unwrap().title)
+            .collect();
+        assert_eq!(titles, vec!["First", "Second"]);
+
+        let parser = RisParser::new();
+        let mut results = parser.parse_stream("".as_bytes());
+        assert!(matches!(
+            results.next(),
+            Some(Err(CitationError::InvalidFormat(_)))
+        ));
+        assert!(results.next().is_none());
+    }
+
+    #[test]
+    fn test_missing_required_fields() {
+        let citation = |citation_type: &str| Citation {
+            citation_type: vec![citation_type.to_string()],
+            title: "Example Title".to_string(),
+            authors: vec![Author {
+                family_name: "Smith".to_string(),
+                ..Default::default()
+            }],
+            year: Some(2020),
+            journal: Some(" ".to_string()),
+            publisher: Some("Example Press".to_string()),
+            ..Default::default()
+        };
+
+        assert_eq!(citation("JOUR").missing_required_fields(), vec!["journal"]);
+        assert_eq!(
+            citation("Book Section").missing_required_fields(),
+            vec!["journal"]
+        );
+        assert!(citation("Book").is_valid_for_type());
+        assert!(citation("Thesis").is_valid_for_type());
+        assert_eq!(
+            citation("Conference Paper").missing_required_fields(),
+            vec!["conference_name"]
+        );
+        assert!(citation("Dataset").is_valid_for_type());
+        assert_eq!(Citation::default().missing_required_fields(), vec!["title"]);
+        assert_eq!(
+            Citation {
+                citation_type: vec!["Journal Article".to_string()],
+                ..Default::default()
+            }
+            .missing_required_fields(),
+            vec!["title", "authors", "journal", "year"]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_group_sources() {
+        let citation = |source: Option<&str>| Citation {
+            source: source.map(String::from),
+            ..Default::default()
+        };
+        let group = DuplicateGroup {
+            unique: citation(Some("Embase")),
+            duplicates: vec![
+                citation(Some("PubMed")),
+                citation(None),
+                citation(Some("Embase")),
+            ],
+            ..Default::default()
+        };
+        assert_eq!(group.sources(), vec!["Embase", "PubMed"]);
+    }
+
+    #[cfg(all(feature = "pubmed", feature = "ris"))]
+    #[test]
+    fn test_source_records_survive_merging() {
+        let pubmed = PubMedParser::new()
+            .with_source("PubMed")
+            .parse("PMID- 1\nTI  - Sleep and memory\n\n")
+            .unwrap();
+        let embase = RisParser::new()
+            .with_source("Embase")
+            .parse("TY  - JOUR\nTI  - Sleep and memory\nER  - \n")
+            .unwrap();
+        assert_eq!(
+            pubmed[0].extra(SOURCE_RECORD_KEY),
+            Some(&[format!("PubMed:{}", pubmed[0].id)][..])
+        );
+
+        let group = DuplicateGroup {
+            unique: pubmed[0].clone(),
+            duplicates: embase.clone(),
+            ..Default::default()
+        };
+        assert_eq!(
+            group.merged().extra(SOURCE_RECORD_KEY),
+            Some(
+                &[
+                    format!("PubMed:{}", pubmed[0].id),
+                    format!("Embase:{}", embase[0].id)
+                ][..]
+            )
+        );
+
+        let unstamped = RisParser::new()
+            .parse("TY  - JOUR\nTI  - Sleep and memory\nER  - \n")
+            .unwrap();
+        assert_eq!(unstamped[0].extra(SOURCE_RECORD_KEY), None);
+    }
+
+    #[test]
+    fn test_title_parts() {
+        let parts = |title: &str| {
+            Citation {
+                title: title.to_string(),
+                ..Default::default()
+            }
+            .title_parts()
+        };
+
+        assert_eq!(
+            parts("Main title: an informative subtitle"),
+            (
+                "Main title".to_string(),
+                Some("an informative subtitle".to_string())
+            )
+        );
+        assert_eq!(
+            parts("No subtitle here"),
+            ("No subtitle here".to_string(), None)
+        );
+        assert_eq!(parts("A 1:2 ratio"), ("A 1:2 ratio".to_string(), None));
+        assert_eq!(
+            parts("Meeting at 10:30: what happened"),
+            (
+                "Meeting at 10:30".to_string(),
+                Some("what happened".to_string())
+            )
+        );
+        assert_eq!(parts("Odds of 3: 1"), ("Odds of 3: 1".to_string(), None));
+        assert_eq!(
+            parts("Trailing colon:"),
+            ("Trailing colon:".to_string(), None)
+        );
+    }
+
+    #[test]
+    fn test_parsers_are_send_sync_clone() {
+        fn assert_thread_safe<T: Send + Sync + Clone>() {}
+
+        assert_thread_safe::<Citation>();
+        #[cfg(feature = "csv")]
+        assert_thread_safe::<CsvParser>();
+        #[cfg(feature = "xml")]
+        assert_thread_safe::<EndNoteXmlParser>();
+        #[cfg(feature = "pubmed")]
+        assert_thread_safe::<PubMedParser>();
+        #[cfg(feature = "ris")]
+        assert_thread_safe::<RisParser>();
+        #[cfg(feature = "refer")]
+        assert_thread_safe::<ReferParser>();
+        #[cfg(feature = "tei")]
+        assert_thread_safe::<TeiParser>();
+        #[cfg(feature = "crossref")]
+        assert_thread_safe::<CrossrefParser>();
+        #[cfg(feature = "dedupe")]
+        assert_thread_safe::<dedupe::Deduplicator>();
+    }
+
+    #[test]
+    fn test_duplicate_group_merged() {
+        let author = |affiliation: Option<&str>| Author {
+            family_name: "Smith".to_string(),
+            given_name: "J".to_string(),
+            affiliation: affiliation.map(String::from),
+            orcid: None,
+            role: None,
+        };
+        let group = DuplicateGroup {
+            unique: Citation {
+                id: "1".to_string(),
+                title: "Title".to_string(),
+                authors: vec![author(None)],
+                journal: Some("Journal".to_string()),
+                urls: vec!["https://a.example".to_string()],
+                source: Some("PubMed".to_string()),
+                ..Default::default()
+            },
+            duplicates: vec![
+                Citation {
+                    id: "2".to_string(),
+                    authors: vec![author(Some("University"))],
+                    journal: Some("Other Journal".to_string()),
+                    pages: Some("1-10".to_string()),
+                    urls: vec!["https://b.example".to_string()],
+                    extra_fields: ExtraFields::from([("N1".to_string(), vec!["Note".to_string()])]),
+                    source: Some("Embase".to_string()),
+                    ..Default::default()
+                },
+                Citation {
+                    id: "3".to_string(),
+                    pages: Some("1-9".to_string()),
+                    urls: vec!["https://a.example".to_string()],
+                    cited_by_count: Some(4),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let merged = group.merged();
+        assert_eq!(merged.id, "1");
+        assert_eq!(merged.source.as_deref(), Some("PubMed"));
+        assert_eq!(merged.journal.as_deref(), Some("Journal"));
+        assert_eq!(merged.pages.as_deref(), Some("1-10"));
+        assert_eq!(merged.cited_by_count, Some(4));
+        assert_eq!(merged.authors[0].affiliation.as_deref(), Some("University"));
+        assert_eq!(merged.urls, vec!["https://a.example", "https://b.example"]);
+        assert_eq!(merged.extra_first("N1"), Some("Note"));
+    }
+
+    #[test]
+    fn test_merge_from() {
+        let author = |orcid: Option<&str>| Author {
+            family_name: "Smith".to_string(),
+            orcid: orcid.map(String::from),
+            ..Default::default()
+        };
+        let base = Citation {
+            id: "1".to_string(),
+            title: "Title".to_string(),
+            authors: vec![author(None)],
+            year: Some(2020),
+            keywords: vec!["one".to_string()],
+            extra_fields: ExtraFields::from([("N1".to_string(), vec!["First".to_string()])]),
+            ..Default::default()
+        };
+        let other = Citation {
+            id: "2".to_string(),
+            title: "Other Title".to_string(),
+            authors: vec![author(Some("0000-0002-1825-0097")), author(None)],
+            year: Some(2021),
+            volume: Some("4".to_string()),
+            keywords: vec!["two".to_string()],
+            extra_fields: ExtraFields::from([("N1".to_string(), vec!["Second".to_string()])]),
+            ..Default::default()
+        };
+
+        let merged = |policy| {
+            let mut citation = base.clone();
+            citation.merge_from(&other, policy);
+            citation
+        };
+
+        let fill = merged(MergePolicy::FillEmpty);
+        assert_eq!(fill.id, "1");
+        assert_eq!(fill.title, "Title");
+        assert_eq!(fill.year, Some(2020));
+        assert_eq!(fill.volume.as_deref(), Some("4"));
+        assert_eq!(fill.authors, base.authors);
+        assert_eq!(fill.keywords, vec!["one", "two"]);
+        assert_eq!(
+            fill.extra("N1"),
+            Some(&["First".to_string(), "Second".to_string()][..])
+        );
+
+        let prefer_self = merged(MergePolicy::PreferSelf);
+        assert_eq!(prefer_self.title, "Title");
+        assert_eq!(prefer_self.year, Some(2020));
+        assert_eq!(prefer_self.authors, other.authors);
+
+        let prefer_other = merged(MergePolicy::PreferOther);
+        assert_eq!(prefer_other.id, "1");
+        assert_eq!(prefer_other.title, "Other Title");
+        assert_eq!(prefer_other.year, Some(2021));
+        assert_eq!(prefer_other.authors, other.authors);
+        assert_eq!(prefer_other.keywords, vec!["one", "two"]);
+
+        // A longer author list is kept, with details filled in when the lengths match
+        let mut longer = other.clone();
+        longer.merge_from(&base, MergePolicy::PreferOther);
+        assert_eq!(longer.authors, other.authors);
+        let mut same_length = base.clone();
+        same_length.merge_from(
+            &Citation {
+                authors: vec![author(Some("0000-0002-1825-0097"))],
+                ..Default::default()
+            },
+            MergePolicy::PreferSelf,
+        );
+        assert_eq!(
+            same_length.authors[0].orcid.as_deref(),
+            Some("0000-0002-1825-0097")
+        );
+    }
+
+    #[cfg(feature = "ris")]
+    #[test]
+    fn test_parse_one() {
+        let parser = RisParser::new();
+        let citation = parser.parse_one("TY  - JOUR\nTI  - Only\nER  -\n").unwrap();
+        assert_eq!(citation.title, "Only");
+
+        let error = |input: &str| match parser.parse_one(input) {
+            Err(CitationError::InvalidFormat(message)) => message,
+            other => panic!("expected an invalid format error, got {:?}", other),
+        };
+        assert_eq!(error("TY  - JOUR\nER  -\n"), "No valid citations found");
+        assert_eq!(
+            error("TY  - JOUR\nTI  - A\nER  -\nTY  - JOUR\nTI  - B\nER  -\n"),
+            "Expected one record, found 2"
+        );
+        assert_eq!(error(""), "Empty input");
+    }
+
+    #[test]
+    fn test_byte_order_mark_and_null_bytes() {
+        type Check = fn(&Citation) -> bool;
+        let mut cases: Vec<(Box<dyn CitationParser>, &str, Check)> = Vec::new();
+        #[cfg(feature = "ris")]
+        cases.push((
+            Box::new(RisParser::new()),
+            "TY  - JOUR\nTI  - Ti\0tle\nER  - \n",
+            |c| c.citation_type == ["JOUR"] && c.title == "Title",
+        ));
+        #[cfg(feature = "pubmed")]
+        cases.push((
+            Box::new(PubMedParser::new()),
+            "PMID- 12345\nTI  - Ti\0tle\n",
+            |c| c.pmid.as_deref() == Some("12345") && c.title == "Title",
+        ));
+        #[cfg(feature = "refer")]
+        cases.push(
+            (Box::new(ReferParser::new()), "%T Ti\0tle\n%D 2020\n", |c| {
+                c.title == "Title" && c.year == Some(2020)
+            }),
+        );
+        #[cfg(feature = "csv")]
+        cases.push((
+            Box::new(CsvParser::new()),
+            "Title,Year\nTi\0tle,2020\n",
+            |c| c.title == "Title" && c.year == Some(2020),
+        ));
+        #[cfg(feature = "xml")]
+        cases.push((
+            Box::new(EndNoteXmlParser::new()),
+            "<?xml version=\"1.0\"?><xml><records><record>\
+             <titles><title>Ti\0tle</title></titles></record></records></xml>",
+            |c| c.title == "Title",
+        ));
+        #[cfg(feature = "tei")]
+        cases.push((
+            Box::new(TeiParser::new()),
+            "<listBibl><biblStruct><analytic><title level=\"a\">Ti\0tle</title>\
+             </analytic></biblStruct></listBibl>",
+            |c| c.title == "Title",
+        ));
+        #[cfg(feature = "crossref")]
+        cases.push((
+            Box::new(CrossrefParser::new()),
+            "{\"DOI\": \"10.1000/x\", \"title\": [\"Ti\0tle\"], \
+             \"issued\": {\"date-parts\": [[2020]]}}",
+            |c| c.title == "Title" && c.year == Some(2020),
+        ));
+
+        for (parser, input, check) in cases {
+            let input = format!("\u{feff}{}", input);
+            assert!(parser.can_parse(&input), "{}", parser.format_name());
+            let citations = parser.parse(&input).unwrap();
+            assert!(check(&citations[0]), "{}", parser.format_name());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "ris")]
+    fn test_shared_parser_across_threads() {
+        let parser = RisParser::new().with_source("Shared");
+        let inputs: Vec<String> = (0..4)
+            .map(|i| format!("TY  - JOUR\nTI  - Title {}\nER  -\n", i))
+            .collect();
+
+        let titles: Vec<String> = std::thread::scope(|scope| {
+            let handles: Vec<_> = inputs
+                .iter()
+                .map(|input| scope.spawn(|| parser.parse(input).unwrap()))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap().remove(0).title)
+                .collect()
+        });
+
+        assert_eq!(titles, vec!["Title 0", "Title 1", "Title 2", "Title 3"]);
+    }
+
+    #[test]
+    fn test_same_work() {
+        let smith = Author {
+            family_name: "Smith".to_string(),
+            given_name: "J".to_string(),
+            ..Default::default()
+        };
+        let base = Citation {
+            title: "Effects of <i>in vivo</i> exposure".to_string(),
+            year: Some(2020),
+            authors: vec![smith.clone()],
+            ..Default::default()
+        };
+
+        // Title, year and first author
+        let other = Citation {
+            title: "Effects of in vivo exposure.".to_string(),
+            ..base.clone()
+        };
+        assert!(base.same_work(&other));
+        let other_year = Citation {
+            year: Some(2021),
+            ..other.clone()
+        };
+        assert!(!base.same_work(&other_year));
+
+        // PMIDs take precedence over titles
+        let with_pmid = |pmid: &str| Citation {
+            pmid: Some(pmid.to_string()),
+            ..base.clone()
+        };
+        assert!(!with_pmid("1").same_work(&with_pmid("2")));
+        assert!(with_pmid("1").same_work(&Citation {
+            title: "Different".to_string(),
+            ..with_pmid("1")
+        }));
+
+        // DOIs take precedence over PMIDs
+        let with_doi = |doi: &str| Citation {
+            doi: Some(doi.to_string()),
+            ..with_pmid("1")
+        };
+        assert!(!with_doi("10.1000/a").same_work(&with_doi("10.1000/b")));
+        assert!(with_doi("10.1000/A").same_work(&with_doi("https://doi.org/10.1000/a")));
+    }
+
+    #[test]
+    fn test_detect_and_parse_reader() {
+        let input = "PMID- 12345678\nTI  - Test Title\n\n";
+        let (citations, format) = detect_and_parse_reader(input.as_bytes(), "PubMed").unwrap();
+        assert_eq!(format, "PubMed");
+        assert_eq!(citations[0].title, "Test Title");
+
+        #[cfg(not(feature = "gzip"))]
+        {
+            let gzip: &[u8] = &[0x1f, 0x8b, 0x08, 0x00];
+            let result = detect_and_parse_reader(gzip, "Any Source");
+            assert!(
+                matches!(result, Err(CitationError::InvalidFormat(msg)) if msg.contains("gzip"))
+            );
+        }
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_parse_gz_file() {
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(b"PMID- 12345678\nTI  - Test Title\n\n")
+            .unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let (citations, format) = detect_and_parse_reader(compressed.as_slice(), "PubMed").unwrap();
+        assert_eq!(format, "PubMed");
+        assert_eq!(citations[0].title, "Test Title");
+
+        let path = std::env::temp_dir().join(format!("biblib-{}.nbib.gz", nanoid::nanoid!()));
+        std::fs::write(&path, &compressed).unwrap();
+        let result = parse_gz_file(&path, "PubMed");
+        std::fs::remove_file(&path).unwrap();
+        let (citations, format) = result.unwrap();
+        assert_eq!(format, "PubMed");
+        assert_eq!(citations[0].source.as_deref(), Some("PubMed"));
+
+        assert!(parse_gz_file("tests/fixtures/endnote.ris", "EndNote").is_err());
+    }
+
+    #[test]
+    fn test_detect_and_parse_file() {
+        let (citations, format) =
+            detect_and_parse_file("tests/fixtures/endnote.ris", "EndNote").unwrap();
+        assert_eq!(format, "RIS");
+        assert_eq!(citations.len(), 2);
+
+        let result = detect_and_parse_file("tests/fixtures/missing.ris", "EndNote");
+        assert!(matches!(result, Err(CitationError::Io(_))));
+    }
+
+    #[test]
+    fn test_from_json_migrating() {
+        let citation = Citation {
+            title: "Current".to_string(),
+            series: Some("Series".to_string()),
+            ..Default::default()
+        };
+        let migrated = Citation::from_json_migrating(citation.to_json_value()).unwrap();
+        assert_eq!(migrated.title, "Current");
+        assert_eq!(migrated.series.as_deref(), Some("Series"));
+
+        // A record from before `isbn`, `grants` and the other later fields
+        let old = serde_json::json!({
+            "id": "old",
+            "title": "Old",
+            "authors": [{"family_name": "Smith", "given_name": "J"}],
+            "mesh_terms": null,
+        });
+        let migrated = Citation::from_json_migrating(old).unwrap();
+        assert_eq!(migrated.authors[0].family_name, "Smith");
+        assert!(migrated.mesh_terms.is_empty() && migrated.grants.is_empty());
+
+        let newer = serde_json::json!({"title": "Newer", "schema_version": 99});
+        assert!(matches!(
+            Citation::from_json_migrating(newer),
+            Err(CitationError::InvalidFieldValue { field, .. }) if field == "schema_version"
+        ));
+        assert!(Citation::from_json_migrating(serde_json::json!([])).is_err());
+    }
+
+    #[test]
+    fn test_rename_fields() {
+        let renames = [
+            (2, "abstract", "abstract_text"),
+            (2, "doi_list", "all_dois"),
+        ];
+        let mut map = serde_json::json!({
+            "abstract": "Old name",
+            "doi_list": ["10.1/a"],
+            "all_dois": ["10.1/b"],
+        });
+        let map = map.as_object_mut().unwrap();
+        rename_fields(map, 1, &renames);
+        assert_eq!(map["abstract_text"], "Old name");
+        // The new name wins when both are present
+        assert_eq!(map["all_dois"], serde_json::json!(["10.1/b"]));
+        assert!(!map.contains_key("abstract") && !map.contains_key("doi_list"));
+
+        let mut current = serde_json::json!({"abstract": "Unrelated"});
+        let current = current.as_object_mut().unwrap();
+        rename_fields(current, 2, &renames);
+        assert_eq!(current["abstract"], "Unrelated");
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn test_json_schema_covers_all_fields() {
+        // The serialized keys of each type must be exactly the properties its
+        // schema documents, so adding or renaming a field fails until the
+        // schema is updated too
+        fn keys(value: &serde_json::Value) -> Vec<String> {
+            let mut keys: Vec<_> = value.as_object().unwrap().keys().cloned().collect();
+            keys.sort();
+            keys
+        }
+
+        let schema: serde_json::Value = serde_json::from_str(Citation::json_schema()).unwrap();
+        let defs = &schema["$defs"];
+        for (schema, serialized) in [
+            (&schema, Citation::default().to_json_value()),
+            (
+                &defs["Author"],
+                serde_json::to_value(Author::default()).unwrap(),
+            ),
+            (
+                &defs["Grant"],
+                serde_json::to_value(Grant::default()).unwrap(),
+            ),
+            (
+                &defs["RelatedArticle"],
+                serde_json::to_value(RelatedArticle::default()).unwrap(),
+            ),
+        ] {
+            assert_eq!(keys(&schema["properties"]), keys(&serialized));
+        }
+    }
+
+    /// Checks `value` against the parts of JSON Schema the citation schema uses
+    #[cfg(feature = "schema")]
+    fn check_schema(
+        root: &serde_json::Value,
+        schema: &serde_json::Value,
+        value: &serde_json::Value,
+        path: &str,
+    ) {
+        use serde_json::Value;
+
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let name = reference.strip_prefix("#/$defs/").unwrap();
+            let definition = root["$defs"]
+                .get(name)
+                .unwrap_or_else(|| panic!("{path}: no definition for {reference}"));
+            return check_schema(root, definition, value, path);
+        }
+        if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+            assert!(
+                options.contains(value),
+                "{path}: {value} not in {options:?}"
+            );
+        }
+        if let Some(types) = schema.get("type") {
+            let types: Vec<&str> = match types {
+                Value::String(name) => vec![name.as_str()],
+                names => names
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|name| name.as_str().unwrap())
+                    .collect(),
+            };
+            let actual = match value {
+                Value::Null => "null",
+                Value::Bool(_) => "boolean",
+                Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+                Value::Number(_) => "number",
+                Value::String(_) => "string",
+                Value::Array(_) => "array",
+                Value::Object(_) => "object",
+            };
+            assert!(types.contains(&actual), "{path}: {actual} is not {types:?}");
+        }
+        if let (Some(minimum), Some(n)) = (schema.get("minimum"), value.as_i64()) {
+            assert!(
+                n >= minimum.as_i64().unwrap(),
+                "{path}: {n} below {minimum}"
+            );
+        }
+        if let (Some(pattern), Some(s)) = (schema.get("pattern"), value.as_str()) {
+            let pattern = regex::Regex::new(pattern.as_str().unwrap()).unwrap();
+            assert!(pattern.is_match(s), "{path}: {s} does not match {pattern}");
+        }
+        if let (Some(items), Some(values)) = (schema.get("items"), value.as_array()) {
+            for (i, item) in values.iter().enumerate() {
+                check_schema(root, items, item, &format!("{path}[{i}]"));
+            }
+        }
+        let Some(object) = value.as_object() else {
+            return;
+        };
+        for required in schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let required = required.as_str().unwrap();
+            assert!(object.contains_key(required), "{path}: missing {required}");
+        }
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (key, field) in object {
+                let property = properties
+                    .get(key)
+                    .unwrap_or_else(|| panic!("{path}: {key} is not in the schema"));
+                check_schema(root, property, field, &format!("{path}.{key}"));
+            }
+        }
+        if let Some(additional) = schema.get("additionalProperties") {
+            for (key, field) in object {
+                check_schema(root, additional, field, &format!("{path}.{key}"));
+            }
+        }
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn test_json_schema_validates_serialized_citations() {
+        let schema: serde_json::Value = serde_json::from_str(Citation::json_schema()).unwrap();
+        let some = |value: &str| Some(value.to_string());
+        let author = |role| Author {
+            family_name: "Smith".to_string(),
+            given_name: "John".to_string(),
+            affiliation: some("University of Tests"),
+            orcid: some("0000-0002-1825-0097"),
+            role,
+        };
+        // Every field is set, so the schema of each is checked with a value
+        let full = Citation {
+            id: "1".to_string(),
+            citation_type: vec!["JOUR".to_string()],
+            title: "Title".to_string(),
+            authors: [
+                None,
+                Some(AuthorRole::Author),
+                Some(AuthorRole::Corresponding),
+                Some(AuthorRole::Editor),
+                Some(AuthorRole::Translator),
+                Some(AuthorRole::Investigator),
+            ]
+            .into_iter()
+            .map(author)
+            .collect(),
+            journal: some("Journal of Tests"),
+            journal_abbr: some("J Tests"),
+            year: Some(2020),
+            status: some("epublish"),
+            volume: some("12"),
+            issue: some("3"),
+            pages: some("10-20"),
+            issn: vec!["1234-5678".to_string()],
+            isbn: vec!["978-0-306-40615-7".to_string()],
+            doi: some("10.1000/test"),
+            all_dois: vec!["10.1000/test".to_string()],
+            pmid: some("12345678"),
+            pmc_id: some("PMC1234567"),
+            arxiv_id: some("2101.00001"),
+            abstract_text: some("Abstract"),
+            copyright: some("Copyright 2020"),
+            keywords: vec!["testing".to_string()],
+            urls: vec!["https://example.com".to_string()],
+            language: some("eng"),
+            mesh_terms: vec!["Humans".to_string()],
+            publisher: some("Publisher"),
+            place: some("London"),
+            conference_name: some("Conference"),
+            series: some("Series"),
+            accession_number: some("WOS:000123"),
+            cited_by_count: Some(5),
+            reference_count: Some(30),
+            grants: vec![Grant {
+                id: some("R01 HL123456"),
+                agency: some("NHLBI NIH HHS"),
+                country: some("United States"),
+            }],
+            related: vec![RelatedArticle {
+                ref_type: "ErratumIn".to_string(),
+                pmid: "23456789".to_string(),
+            }],
+            extra_fields: [("N1".to_string(), vec!["Note".to_string()])]
+                .into_iter()
+                .collect(),
+            source: some("PubMed"),
+        };
+
+        for citation in [full, Citation::default()] {
+            let value = citation.to_json_value();
+            check_schema(&schema, &schema, &value, "citation");
+
+            // Every field needed to read a citation back must be required
+            let required = schema["required"].as_array().unwrap();
+            for key in value.as_object().unwrap().keys() {
+                let mut without = value.clone();
+                without.as_object_mut().unwrap().remove(key);
+                if serde_json::from_value::<Citation>(without).is_err() {
+                    assert!(
+                        required.contains(&key.as_str().into()),
+                        "{key} is not required"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_detect_ris_with_malformed_separators() {
+        let content = include_str!("../tests/fixtures/malformed_separator.ris");
+        let (citations, format) = detect_and_parse(content, "test").unwrap();
+        assert_eq!(format, "RIS");
+        assert_eq!(citations.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_dir() {
+        let dir = std::env::temp_dir().join(format!("biblib-parse-dir-{}", nanoid::nanoid!()));
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(
+            dir.join("scopus.ris"),
+            "TY  - JOUR\nTI  - From RIS\nER  - \n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("nested/wos.csv"), "Title,Year\nFrom CSV,2020\n").unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a citation file").unwrap();
+        std::fs::write(
+            dir.join(".hidden.ris"),
+            "TY  - JOUR\nTI  - Hidden\nER  - \n",
+        )
+        .unwrap();
+
+        let import = parse_dir(&dir).unwrap();
+        let mut titles: Vec<_> = import
+            .citations
+            .iter()
+            .map(|c| (c.title.as_str(), c.source.as_deref()))
+            .collect();
+        titles.sort();
+        assert_eq!(
+            titles,
+            [("From CSV", Some("wos")), ("From RIS", Some("scopus"))]
+        );
+        assert_eq!(import.errors.len(), 1);
+        assert!(import.errors[0].0.ends_with("notes.txt"));
+
+        let import = parse_dir_with(&dir, |_| "Search 1".to_string()).unwrap();
+        assert!(import
+            .citations
+            .iter()
+            .all(|c| c.source.as_deref() == Some("Search 1")));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(matches!(parse_dir(&dir), Err(CitationError::Io(_))));
+    }
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(detect_format("PMID- 1\nTI  - Title\n"), Some("PubMed"));
+        assert_eq!(
+            detect_format("<?xml version=\"1.0\"?><TEI><listBibl><biblStruct/></listBibl></TEI>"),
+            Some("TEI")
+        );
+        assert_eq!(
+            detect_format("<?xml version=\"1.0\"?><xml><records/></xml>"),
+            Some("EndNote XML")
+        );
+        assert_eq!(detect_format("%A Smith, J\n%T Title\n"), Some("refer"));
+        assert_eq!(detect_format("Title,Year\nExample,2023\n"), None);
+    }
+
+    #[test]
+    fn test_pmid_round_trip_through_ris() {
+        let input = "PMID- 31452104\nTI  - Aspirin for primary prevention.\nJT  - The Lancet\nDP  - 2019 Sep\n";
+        let citation = PubMedParser::new().parse(input).unwrap().remove(0);
+        let reparsed = RisParser::new()
+            .parse(&citation.to_ris_string())
+            .unwrap()
+            .remove(0);
+        assert_eq!(reparsed.pmid.as_deref(), Some("31452104"));
+        assert!(citation.same_work(&reparsed));
+    }
+
+    #[test]
+    fn test_language_round_trip() {
+        let inputs = [
+            "TY  - JOUR\nTI  - Language Test\nLA  - English\nER  - \n",
+            "PMID- 1\nTI  - Language Test\nLA  - English\n",
+            "<?xml version=\"1.0\"?><xml><records><record><titles><title>Language Test</title></titles><language>English</language></record></records></xml>",
+            "%T Language Test\n%G English\n",
+            "<TEI><listBibl><biblStruct><analytic><title level=\"a\">Language Test</title></analytic><monogr><textLang mainLang=\"English\"/></monogr></biblStruct></listBibl></TEI>",
+        ];
+        let mut citations: Vec<Citation> = inputs
+            .iter()
+            .map(|input| detect_and_parse(input, "test").unwrap().0.remove(0))
+            .collect();
+        citations.push(
+            CsvParser::new()
+                .parse("Title,Language\nLanguage Test,English\n")
+                .unwrap()
+                .remove(0),
+        );
+
+        for citation in &citations {
+            assert_eq!(citation.language.as_deref(), Some("English"));
+            let ris = RisParser::new().parse(&citation.to_ris_string()).unwrap();
+            assert_eq!(ris[0].language.as_deref(), Some("English"));
+            assert!(citation
+                .to_bibtex_string()
+                .contains("  language = {English},\n"));
+        }
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let citation = |title: &str, doi: Option<&str>| Citation {
+            title: title.to_string(),
+            year: Some(2019),
+            doi: doi.map(String::from),
+            authors: vec![Author {
+                family_name: "Smith".to_string(),
+                given_name: "J".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let a = citation("Sleep and memory", Some("10.1000/a"));
+        let b = citation("Sleep and Memory.", None);
+        assert!(a.same_work(&b));
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        assert_ne!(
+            a.fingerprint(),
+            citation("Sleep and mood", None).fingerprint()
+        );
+
+        // Fixed value, so fingerprints stored by earlier runs stay comparable
+        assert_eq!(a.fingerprint(), 0xd4dc_bf87_c5cf_af4c);
+
+        // Editors listed first are not the first author
+        let mut edited = citation("Sleep and memory", None);
+        edited.authors.insert(
+            0,
+            Author {
+                family_name: "Doe".to_string(),
+                role: Some(AuthorRole::Editor),
+                ..Default::default()
+            },
+        );
+        assert!(edited.same_work(&b));
+        assert_eq!(edited.fingerprint(), a.fingerprint());
+
+        let untitled = |doi: &str| Citation {
+            doi: Some(doi.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            untitled("10.1000/X").fingerprint(),
+            untitled("https://doi.org/10.1000/x").fingerprint()
+        );
     }
 }
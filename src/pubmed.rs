@@ -21,23 +21,65 @@
 //! assert_eq!(citations[0].source.as_deref(), Some("PubMed"));
 //! ```
 
-use crate::utils::{format_doi, format_page_numbers, parse_author_name};
-use crate::{Author, Citation, CitationError, CitationParser, Result};
+use crate::transform::extract_copyright;
+use crate::utils::{
+    clean_input, extract_pmids, extract_year, format_page_numbers, markup_to_markdown,
+    normalize_orcid, normalize_publication_status, parse_author_name, push_capped, strip_markup,
+    ParserOptions, WarningHandler,
+};
+use crate::{
+    Author, AuthorRole, Citation, CitationError, CitationParser, Grant, RelatedArticle, Result,
+};
 use nanoid::nanoid;
+use std::borrow::Cow;
+use std::iter::Peekable;
+
+/// Comments and corrections tags with the `RefType` of PubMed XML they stand for
+const COMMENTS_CORRECTIONS: [(&str, &str); 20] = [
+    ("CON", "CommentOn"),
+    ("CIN", "CommentIn"),
+    ("EIN", "ErratumIn"),
+    ("EFR", "ErratumFor"),
+    ("CRI", "CorrectedandRepublishedIn"),
+    ("CRF", "CorrectedandRepublishedFrom"),
+    ("ECI", "ExpressionOfConcernIn"),
+    ("ECF", "ExpressionOfConcernFor"),
+    ("RIN", "RetractionIn"),
+    ("ROF", "RetractionOf"),
+    ("PRIN", "PartialRetractionIn"),
+    ("PROF", "PartialRetractionOf"),
+    ("RRI", "RetractedandRepublishedIn"),
+    ("RRF", "RetractedandRepublishedFrom"),
+    ("RPI", "RepublishedIn"),
+    ("RPF", "RepublishedFrom"),
+    ("UIN", "UpdateIn"),
+    ("UOF", "UpdateOf"),
+    ("SPIN", "SummaryForPatientsIn"),
+    ("ORI", "OriginalReportIn"),
+];
 
 /// Parser for PubMed format citations.
 ///
 /// PubMed format is commonly used by PubMed and the National Library of Medicine
 /// for bibliographic citations.
-#[derive(Debug, Clone)]
+#[derive(Debug, Default, Clone)]
 pub struct PubMedParser {
-    source: Option<String>,
+    options: ParserOptions,
+    markup_mode: MarkupMode,
+    split_copyright: bool,
+    mesh_qualifiers: bool,
 }
 
-impl Default for PubMedParser {
-    fn default() -> Self {
-        Self { source: None }
-    }
+/// How inline markup such as `<i>` or `<sub>` in titles and abstracts is handled.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MarkupMode {
+    /// Remove the tags and keep their content, e.g. `H<sub>2</sub>O` becomes `H2O`
+    #[default]
+    Strip,
+    /// Convert the tags to markdown-style delimiters, e.g. `H~2~O`
+    Markdown,
+    /// Keep the tags as they appear in the input
+    Keep,
 }
 
 #[derive(Debug, PartialEq)]
@@ -60,12 +102,272 @@ impl PubMedParser {
         Self::default()
     }
 
+    /// Sets the source of the parsed citations.
+    ///
+    /// Each citation's record is also named under
+    /// [`SOURCE_RECORD_KEY`](crate::SOURCE_RECORD_KEY).
     #[must_use]
     pub fn with_source(mut self, source: &str) -> Self {
-        self.source = Some(source.to_string());
+        self.options.source = Some(source.to_string());
+        self
+    }
+
+    /// Sets how inline markup in titles and abstracts is handled.
+    ///
+    /// Defaults to [`MarkupMode::Strip`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::pubmed::{MarkupMode, PubMedParser};
+    /// let parser = PubMedParser::new().with_markup_mode(MarkupMode::Markdown);
+    /// ```
+    #[must_use]
+    pub fn with_markup_mode(mut self, mode: MarkupMode) -> Self {
+        self.markup_mode = mode;
+        self
+    }
+
+    /// Stops parsing once `limit` citations have been read.
+    ///
+    /// Useful for previewing large inputs. A limit of `0` returns no citations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::PubMedParser;
+    /// let parser = PubMedParser::new().with_limit(10);
+    /// ```
+    #[must_use]
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.options.limit = Some(limit);
+        self
+    }
+
+    /// Sets the longest field, in bytes, kept from the input.
+    ///
+    /// Longer fields are truncated at a character boundary, as they are read,
+    /// and a warning is reported through [`PubMedParser::with_warning_handler`].
+    /// Defaults to [`DEFAULT_MAX_FIELD_LENGTH`](crate::DEFAULT_MAX_FIELD_LENGTH).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::PubMedParser;
+    /// let parser = PubMedParser::new().with_max_field_length(64 * 1024);
+    /// ```
+    #[must_use]
+    pub fn with_max_field_length(mut self, max: usize) -> Self {
+        self.options.max_field_length = Some(max);
+        self
+    }
+
+    /// Sets the function receiving warnings, which are ignored otherwise.
+    ///
+    /// Warnings are reported when input is altered, for example when a field
+    /// exceeds the [maximum length](PubMedParser::with_max_field_length).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::PubMedParser;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let warnings = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = Arc::clone(&warnings);
+    /// let parser = PubMedParser::new()
+    ///     .with_warning_handler(move |message| sink.lock().unwrap().push(message.to_string()));
+    /// ```
+    #[must_use]
+    pub fn with_warning_handler(mut self, handler: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.options.warning_handler = WarningHandler::new(handler);
+        self
+    }
+
+    /// Sets whether whitespace is normalized in every field.
+    ///
+    /// When enabled, runs of whitespace, including non-breaking spaces and
+    /// line breaks, become a single space and zero-width characters are
+    /// removed, as by
+    /// [`normalize_whitespace`](crate::transform::normalize_whitespace). Off by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::PubMedParser;
+    /// let parser = PubMedParser::new().with_whitespace_normalization(true);
+    /// ```
+    #[must_use]
+    pub fn with_whitespace_normalization(mut self, enabled: bool) -> Self {
+        self.options.normalize_whitespace = enabled;
         self
     }
 
+    /// Sets whether trailing copyright statements are moved out of abstracts.
+    ///
+    /// When enabled, a statement such as `"Copyright © 2020 Elsevier Ltd. All
+    /// rights reserved."` at the end of `AB` is moved to `copyright`, see
+    /// [`extract_copyright`]. Off by default, as a sentence mentioning
+    /// copyright could be legitimate abstract text. A `CI` line is always
+    /// read as the copyright.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{CitationParser, PubMedParser};
+    ///
+    /// let input = "PMID- 1\nTI  - Title\nAB  - Results. (c) 2020 Wiley Periodicals, Inc.\n";
+    /// let citations = PubMedParser::new()
+    ///     .with_split_copyright(true)
+    ///     .parse(input)
+    ///     .unwrap();
+    /// assert_eq!(citations[0].abstract_text.as_deref(), Some("Results."));
+    /// assert_eq!(citations[0].copyright.as_deref(), Some("(c) 2020 Wiley Periodicals, Inc."));
+    /// ```
+    #[must_use]
+    pub fn with_split_copyright(mut self, split: bool) -> Self {
+        self.split_copyright = split;
+        self
+    }
+
+    /// Sets whether MeSH qualifiers (subheadings) are kept in `mesh_terms`.
+    ///
+    /// By default an `MH` line such as `"*Sleep/physiology"` adds only its
+    /// descriptor, `"Sleep"`. When enabled, the heading is kept with its
+    /// qualifiers, `"Sleep/physiology"`. The `*` marking a major topic is
+    /// dropped either way. Author keywords from `OT` lines always go to
+    /// `keywords`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{CitationParser, PubMedParser};
+    ///
+    /// let input = "PMID- 1\nTI  - Title\nMH  - *Sleep/physiology/*psychology\n";
+    /// let citations = PubMedParser::new().parse(input).unwrap();
+    /// assert_eq!(citations[0].mesh_terms, vec!["Sleep"]);
+    ///
+    /// let citations = PubMedParser::new()
+    ///     .with_mesh_qualifiers(true)
+    ///     .parse(input)
+    ///     .unwrap();
+    /// assert_eq!(citations[0].mesh_terms, vec!["Sleep/physiology/psychology"]);
+    /// ```
+    #[must_use]
+    pub fn with_mesh_qualifiers(mut self, keep: bool) -> Self {
+        self.mesh_qualifiers = keep;
+        self
+    }
+
+    /// Turns an `MH` heading into a MeSH term, dropping major topic markers
+    /// and, unless qualifiers are kept, the subheadings
+    fn mesh_term(&self, heading: &str) -> String {
+        let mut parts = heading
+            .split('/')
+            .map(|part| part.trim().trim_start_matches('*').trim())
+            .filter(|part| !part.is_empty());
+        if self.mesh_qualifiers {
+            parts.collect::<Vec<_>>().join("/")
+        } else {
+            parts.next().unwrap_or_default().to_string()
+        }
+    }
+
+    /// Applies the markup mode to a piece of text
+    fn apply_markup(&self, text: &str) -> String {
+        match self.markup_mode {
+            MarkupMode::Strip => strip_markup(text),
+            MarkupMode::Markdown => markup_to_markdown(text),
+            MarkupMode::Keep => text.to_string(),
+        }
+    }
+
+    /// Completes a citation once all of its lines have been read
+    ///
+    /// # Arguments
+    ///
+    /// * `citation` - The citation being built
+    /// * `au_authors` - Authors from `AU` tags, used when there are no `FAU` tags
+    /// * `investigators` - Investigators from `FIR` tags, listed after the authors
+    fn finish_citation(
+        &self,
+        mut citation: Citation,
+        au_authors: Vec<Author>,
+        investigators: Vec<Author>,
+    ) -> Citation {
+        if citation.authors.is_empty() && !au_authors.is_empty() {
+            citation.authors = au_authors;
+        }
+        citation.authors.extend(investigators);
+        // Articles published online first may have no print date yet
+        if citation.year.is_none() {
+            citation.year = citation
+                .extra("DEP")
+                .into_iter()
+                .flatten()
+                .find_map(|date| date.get(..4).and_then(extract_year));
+        }
+        // Comments and corrections without a PMID stay in `extra_fields`
+        for (tag, ref_type) in COMMENTS_CORRECTIONS {
+            let Some(values) = citation.extra_fields.remove(tag) else {
+                continue;
+            };
+            let mut unlinked = Vec::new();
+            for value in values {
+                match extract_pmids(&value).into_iter().next() {
+                    Some(pmid) => citation.related.push(RelatedArticle {
+                        ref_type: ref_type.to_string(),
+                        pmid,
+                    }),
+                    None => unlinked.push(value),
+                }
+            }
+            if !unlinked.is_empty() {
+                citation.extra_fields.insert(tag.to_string(), unlinked);
+            }
+        }
+        if citation.status.is_none()
+            && citation
+                .extra("SO")
+                .into_iter()
+                .flatten()
+                .any(|source| source.contains("[Epub ahead of print]"))
+        {
+            citation.status = Some("aheadofprint".to_string());
+        }
+        // Electronic-only articles have no PG, their e-locator is the pii LID.
+        // Other piis, such as publisher ids like "S0140-6736(19)32456-1", are
+        // not page numbers.
+        if citation.pages.is_none() {
+            citation.pages = citation
+                .extra("LID")
+                .into_iter()
+                .flatten()
+                .filter_map(|lid| lid.strip_suffix("[pii]"))
+                .map(str::trim)
+                .find(|pii| {
+                    let digits = pii.strip_prefix(['e', 'E']).unwrap_or(pii);
+                    !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+                })
+                .map(String::from);
+        }
+        citation.title = self.apply_markup(&citation.title);
+        citation.abstract_text = citation.abstract_text.map(|text| self.apply_markup(&text));
+        if self.split_copyright {
+            extract_copyright(&mut citation);
+        }
+        citation
+    }
+
+    /// Creates an empty citation with a fresh id and the parser's source
+    fn new_citation(&self) -> Citation {
+        Citation {
+            id: nanoid!(),
+            source: self.options.source.clone(),
+            ..Default::default()
+        }
+    }
+
     /// Parses an author string in the format "LastName, FirstName".
     ///
     /// ## Arguments
@@ -77,51 +379,101 @@ impl PubMedParser {
             family_name: family,
             given_name: given,
             affiliation: None,
+            orcid: None,
+            role: None,
         }
     }
 
-    /// Handles continued lines in PubMed format.
+    /// Parses a `GR` line such as `"R01 HL123456/HL/NHLBI NIH HHS/United States"`.
+    ///
+    /// The fields are read from the end, since the grant number may itself
+    /// contain slashes: the country, the agency, the agency's acronym (which is
+    /// dropped), and the grant number. Shorter lines lack the leading fields,
+    /// e.g. `"Wellcome Trust/United Kingdom"`.
+    ///
+    /// ## Arguments
+    ///
+    /// * `content` - The content of the `GR` line
+    fn parse_grant(content: &str) -> Grant {
+        let value = |part: &str| Some(part.trim().to_string()).filter(|part| !part.is_empty());
+        let parts: Vec<&str> = content.split('/').collect();
+        match parts.as_slice() {
+            [only] if only.contains(|c: char| c.is_ascii_digit()) => Grant {
+                id: value(only),
+                ..Default::default()
+            },
+            [agency] => Grant {
+                agency: value(agency),
+                ..Default::default()
+            },
+            [agency, country] => Grant {
+                id: None,
+                agency: value(agency),
+                country: value(country),
+            },
+            [id, agency, country] => Grant {
+                id: value(id),
+                agency: value(agency),
+                country: value(country),
+            },
+            [id @ .., _acronym, agency, country] => Grant {
+                id: value(&id.join("/")),
+                agency: value(agency),
+                country: value(country),
+            },
+            [] => Grant::default(),
+        }
+    }
+
+    /// Joins the continuation lines following a field onto its value.
+    ///
+    /// MEDLINE wraps long values onto lines indented with six spaces, or a tab
+    /// in some exports; each is appended with a single space. The value stops
+    /// growing at the maximum field length, with a warning.
     ///
     /// # Arguments
     ///
-    /// * `citation` - The current citation being built
-    /// * `field` - The current field being processed
-    /// * `content` - The content to append
-    fn handle_continuation(citation: &mut Citation, field: &str, content: &str) {
-        match field {
-            "FAU" => citation.authors.push(Self::parse_author(content)),
-            "AB" => {
-                citation
-                    .abstract_text
-                    .get_or_insert_with(String::new)
-                    .push_str(content);
-            }
-            "AD" => {
-                if let Some(last_author) = citation.authors.last_mut() {
-                    if let Some(ref mut aff) = last_author.affiliation {
-                        aff.push(' ');
-                        aff.push_str(content.trim());
-                    } else {
-                        last_author.affiliation = Some(content.trim().to_string());
-                    }
-                }
-            }
-            _ => {
-                if let Some(values) = citation.extra_fields.get_mut(field) {
-                    if let Some(last_value) = values.last_mut() {
-                        last_value.push(' ');
-                        last_value.push_str(content);
-                    }
-                }
-            }
+    /// * `tag` - The field's tag, for the warning
+    /// * `content` - The value on the field's own line
+    /// * `lines` - The remaining numbered lines, advanced past the continuations
+    fn join_continuations<'a>(
+        &self,
+        tag: &str,
+        content: &'a str,
+        lines: &mut Peekable<impl Iterator<Item = (usize, &'a str)>>,
+    ) -> Cow<'a, str> {
+        let max = self.options.max_field_length();
+        let mut value = Cow::Borrowed(content);
+        let mut truncated = false;
+        while let Some(Ok(PubMedLine::Continuation(more))) = lines
+            .peek()
+            .map(|&(line_num, line)| Self::validate_line(line, line_num + 1))
+        {
+            let value = value.to_mut();
+            let separator = if !value.is_empty() && !more.is_empty() {
+                " "
+            } else {
+                ""
+            };
+            truncated |= push_capped(value, &format!("{separator}{more}"), max);
+            lines.next();
+        }
+        if truncated {
+            self.options
+                .warning_handler
+                .warn(&format!("truncated {tag} field to {max} bytes"));
         }
+        value
     }
 
-    fn validate_line(line: &str, line_num: usize) -> Result<PubMedLine> {
+    fn validate_line(line: &str, line_num: usize) -> Result<PubMedLine<'_>> {
         let line = line.trim_end();
 
-        // Check for continuation line (starts with 6 spaces)
-        if let Some(content) = line.strip_prefix("      ") {
+        // Check for continuation line (starts with 6 spaces or a tab)
+        if let Some(content) = line
+            .strip_prefix("      ")
+            .or_else(|| line.strip_prefix('\t'))
+        {
             return Ok(PubMedLine::Continuation(content.trim_start()));
         }
 
@@ -163,39 +515,47 @@ impl CitationParser for PubMedParser {
     ///
     /// Returns `CitationError` if the input is malformed
     fn parse(&self, input: &str) -> Result<Vec<Citation>> {
+        let input: &str = &clean_input(input);
         if input.trim().is_empty() {
             return Err(CitationError::InvalidFormat("Empty input".into()));
         }
 
+        let limit = self.options.limit();
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
         let mut citations = Vec::new();
-        let mut current_citation = Citation::default();
-        current_citation.id = nanoid!();
-        current_citation.source = self.source.clone(); // Add source if provided
-        let mut current_field = String::new();
+        let mut current_citation = self.new_citation();
         let mut temp_au_authors: Vec<Author> = Vec::new();
+        let mut investigators: Vec<Author> = Vec::new();
 
-        for (line_num, line) in input.lines().enumerate() {
+        let mut lines = input.lines().enumerate().peekable();
+        while let Some((line_num, line)) = lines.next() {
             let line = line.trim_end();
             if line.is_empty() {
                 if !current_citation.title.is_empty() {
-                    // If we have AU authors but no FAU authors, use the AU authors
-                    if current_citation.authors.is_empty() && !temp_au_authors.is_empty() {
-                        current_citation.authors = temp_au_authors;
-                    }
-                    citations.push(current_citation);
-                    current_citation = Citation::default();
-                    current_citation.id = nanoid!();
+                    citations.push(self.finish_citation(
+                        current_citation,
+                        temp_au_authors,
+                        investigators,
+                    ));
+                    current_citation = self.new_citation();
                     temp_au_authors = Vec::new();
+                    investigators = Vec::new();
+                    if citations.len() >= limit {
+                        break;
+                    }
                 }
                 continue;
             }
 
             match Self::validate_line(line, line_num + 1)? {
-                PubMedLine::Continuation(content) => {
-                    Self::handle_continuation(&mut current_citation, &current_field, content);
-                }
+                // Continuations are joined onto their field below, so this one has none
+                PubMedLine::Continuation(_) => {}
                 PubMedLine::Field { tag, content } => {
-                    current_field = tag.to_string();
+                    let content = self.join_continuations(tag, content, &mut lines);
+                    let content: &str = &content;
                     match tag {
                         "PMID" => current_citation.pmid = Some(content.to_string()),
                         "PMC" => current_citation.pmc_id = Some(content.to_string()),
@@ -203,34 +563,55 @@ impl CitationParser for PubMedParser {
                         "JT" => current_citation.journal = Some(content.to_string()),
                         "TA" => current_citation.journal_abbr = Some(content.to_string()),
                         "DP" => {
-                            if let Ok(year) = content
-                                .split_whitespace()
-                                .next()
-                                .unwrap_or("0")
-                                .parse::<i32>()
-                            {
+                            if let Some(year) = extract_year(content) {
                                 current_citation.year = Some(year);
                             }
                         }
+                        "PST" => current_citation.status = normalize_publication_status(content),
                         "VI" => current_citation.volume = Some(content.to_string()),
                         "IP" => current_citation.issue = Some(content.to_string()),
                         "PG" => current_citation.pages = Some(format_page_numbers(content)),
-                        "LID" => {
-                            if content.ends_with("[doi]") || content.contains("doi.org") {
-                                current_citation.doi = format_doi(content);
-                            }
+                        "LID" | "AID"
+                            if content.ends_with("[doi]") || content.contains("doi.org") =>
+                        {
+                            current_citation.add_doi(content);
                         }
                         "PT" => current_citation.citation_type.push(content.to_string()),
                         "FAU" => current_citation.authors.push(Self::parse_author(content)),
                         "AU" => temp_au_authors.push(Self::parse_author(content)),
+                        "FIR" => investigators.push(Author {
+                            role: Some(AuthorRole::Investigator),
+                            ..Self::parse_author(content)
+                        }),
+                        "IRAD" => {
+                            if let Some(investigator) = investigators.last_mut() {
+                                investigator.affiliation = Some(content.to_string());
+                            }
+                        }
                         "AB" => current_citation.abstract_text = Some(content.to_string()),
                         "OT" => current_citation.keywords.push(content.to_string()),
-                        "MH" => current_citation.mesh_terms.push(content.to_string()),
+                        "MH" => {
+                            let term = self.mesh_term(content);
+                            if !term.is_empty() {
+                                current_citation.mesh_terms.push(term);
+                            }
+                        }
                         "AD" => {
                             if let Some(last_author) = current_citation.authors.last_mut() {
                                 last_author.affiliation = Some(content.to_string());
                             }
                         }
+                        "AUID" if normalize_orcid(content).is_some() => {
+                            let orcid = normalize_orcid(content);
+                            if let Some(last_author) = current_citation.authors.last_mut() {
+                                last_author.orcid.clone_from(&orcid);
+                            }
+                            if let Some(last_author) = temp_au_authors.last_mut() {
+                                last_author.orcid = orcid;
+                            }
+                        }
+                        "CI" => current_citation.copyright = Some(content.to_string()),
+                        "GR" => current_citation.grants.push(Self::parse_grant(content)),
                         "LA" => current_citation.language = Some(content.to_string()),
                         "IS" => current_citation.issn.push(content.to_string()),
                         _ => {
@@ -245,11 +626,8 @@ impl CitationParser for PubMedParser {
             }
         }
 
-        if !current_citation.title.is_empty() {
-            if current_citation.authors.is_empty() && !temp_au_authors.is_empty() {
-                current_citation.authors = temp_au_authors;
-            }
-            citations.push(current_citation);
+        if !current_citation.title.is_empty() && citations.len() < limit {
+            citations.push(self.finish_citation(current_citation, temp_au_authors, investigators));
         }
 
         if citations.is_empty() {
@@ -258,8 +636,19 @@ impl CitationParser for PubMedParser {
             ));
         }
 
+        self.options.finish(&mut citations);
         Ok(citations)
     }
+
+    fn format_name(&self) -> &'static str {
+        "PubMed"
+    }
+
+    fn can_parse(&self, input: &str) -> bool {
+        let input: &str = &clean_input(input);
+        let trimmed = input.trim();
+        trimmed.starts_with("PMID-") || trimmed.contains("\nPMID-")
+    }
 }
 
 #[cfg(test)]
@@ -403,7 +792,321 @@ AU  - Zhang H
             PubMedParser::validate_line("      Continuation text", 1).unwrap(),
             PubMedLine::Continuation("Continuation text")
         ));
+        assert!(matches!(
+            PubMedParser::validate_line("\tContinuation text", 1).unwrap(),
+            PubMedLine::Continuation("Continuation text")
+        ));
 
         assert!(PubMedParser::validate_line("Invalid- line", 1).is_err());
     }
+
+    #[test]
+    fn test_source_on_every_record() {
+        let input = r#"PMID- 1
+TI  - First
+
+PMID- 2
+TI  - Second
+
+"#;
+        let parser = PubMedParser::new().with_source("PubMed");
+        let result = parser.parse(input).unwrap();
+        assert_eq!(result.len(), 2);
+        assert!(result.iter().all(|c| c.source.as_deref() == Some("PubMed")));
+    }
+
+    #[test]
+    fn test_inline_markup() {
+        let input = r#"PMID- 12345678
+TI  - Uptake of <i>in vivo</i> H<sub>2</sub>O
+AB  - Measured <sup>11</sup>C levels.
+
+"#;
+        let result = PubMedParser::new().parse(input).unwrap();
+        assert_eq!(result[0].title, "Uptake of in vivo H2O");
+        assert_eq!(
+            result[0].abstract_text.as_deref(),
+            Some("Measured 11C levels.")
+        );
+
+        let parser = PubMedParser::new().with_markup_mode(MarkupMode::Markdown);
+        let result = parser.parse(input).unwrap();
+        assert_eq!(result[0].title, "Uptake of *in vivo* H~2~O");
+
+        let parser = PubMedParser::new().with_markup_mode(MarkupMode::Keep);
+        let result = parser.parse(input).unwrap();
+        assert_eq!(result[0].title, "Uptake of <i>in vivo</i> H<sub>2</sub>O");
+    }
+
+    #[test]
+    fn test_with_limit() {
+        let input = "PMID- 1\nTI  - First\n\nPMID- 2\nTI  - Second\n\nPMID- 3\nTI  - Third\n";
+        let result = PubMedParser::new().with_limit(2).parse(input).unwrap();
+        let titles: Vec<_> = result.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, vec!["First", "Second"]);
+    }
+
+    #[test]
+    fn test_parse_author_orcid() {
+        let input = r#"PMID- 12345678
+TI  - Test Article Title
+FAU - Smith, John
+AU  - Smith J
+AUID- ORCID: 0000-0002-1825-0097
+FAU - Doe, Jane
+AU  - Doe J
+AUID- ORCID: 0000-0002-1825-0098
+FAU - Roe, Richard
+AU  - Roe R
+
+"#;
+        let parser = PubMedParser::new();
+        let result = parser.parse(input).unwrap();
+        let authors = &result[0].authors;
+        assert_eq!(authors[0].orcid.as_deref(), Some("0000-0002-1825-0097"));
+        // Invalid checksums are not attached but kept as raw data
+        assert_eq!(authors[1].orcid, None);
+        assert_eq!(
+            result[0].extra_first("AUID"),
+            Some("ORCID: 0000-0002-1825-0098")
+        );
+        assert_eq!(authors[2].orcid, None);
+    }
+
+    #[test]
+    fn test_electronic_only_pagination() {
+        let input = include_str!("../tests/fixtures/elocator.nbib");
+        let citations = PubMedParser::new().parse(input).unwrap();
+
+        assert_eq!(citations[0].pages.as_deref(), Some("e0215432"));
+        assert_eq!(
+            citations[0].doi.as_deref(),
+            Some("10.1371/journal.pone.0215432")
+        );
+        // The PG range wins over a pii
+        assert_eq!(citations[1].pages.as_deref(), Some("101-109"));
+
+        let input = "PMID- 1\nTI  - E-locator page\nPG  - e0123456\n";
+        let citations = PubMedParser::new().parse(input).unwrap();
+        assert_eq!(citations[0].pages.as_deref(), Some("e0123456"));
+
+        // A publisher pii is not an e-locator
+        let input = "PMID- 1\nTI  - Title\nLID - S0140-6736(19)32456-1 [pii]\n";
+        let citations = PubMedParser::new().parse(input).unwrap();
+        assert_eq!(citations[0].pages, None);
+
+        let input = "PMID- 1\nTI  - Title\nLID - 101832 [pii]\n";
+        let citations = PubMedParser::new().parse(input).unwrap();
+        assert_eq!(citations[0].pages.as_deref(), Some("101832"));
+    }
+
+    #[test]
+    fn test_copyright() {
+        let input = "PMID- 1\nTI  - Title\nAB  - Results were good. Copyright © 2020 Elsevier Ltd. All rights reserved.\nCI  - Copyright © 2020 Elsevier Ltd.\n      All rights reserved.\n";
+
+        let citations = PubMedParser::new().parse(input).unwrap();
+        assert!(citations[0]
+            .abstract_text
+            .as_deref()
+            .unwrap()
+            .ends_with("All rights reserved."));
+        assert_eq!(
+            citations[0].copyright.as_deref(),
+            Some("Copyright © 2020 Elsevier Ltd. All rights reserved.")
+        );
+        assert!(citations[0].extra("CI").is_none());
+
+        let citations = PubMedParser::new()
+            .with_split_copyright(true)
+            .parse(input)
+            .unwrap();
+        assert_eq!(
+            citations[0].abstract_text.as_deref(),
+            Some("Results were good.")
+        );
+    }
+
+    #[test]
+    fn test_keywords_and_mesh_terms() {
+        let input = include_str!("../tests/fixtures/mesh_keywords.nbib");
+        let citations = PubMedParser::new().parse(input).unwrap();
+
+        assert_eq!(
+            citations[0].keywords,
+            vec!["adolescents", "screen time", "sleep hygiene"]
+        );
+        assert_eq!(
+            citations[0].mesh_terms,
+            vec![
+                "Adolescent",
+                "Humans",
+                "Sleep",
+                "Sleep Deprivation",
+                "Screen Time"
+            ]
+        );
+        assert_eq!(citations[0].extra_first("OTO"), Some("NOTNLM"));
+        assert!(citations[1].keywords.is_empty());
+        assert_eq!(citations[1].mesh_terms, vec!["Exercise", "Cognition"]);
+
+        let citations = PubMedParser::new()
+            .with_mesh_qualifiers(true)
+            .parse(input)
+            .unwrap();
+        assert_eq!(
+            citations[0].mesh_terms,
+            vec![
+                "Adolescent",
+                "Humans",
+                "Sleep/physiology",
+                "Sleep Deprivation/complications/psychology",
+                "Screen Time",
+            ]
+        );
+        assert_eq!(
+            citations[0].keywords,
+            vec!["adolescents", "screen time", "sleep hygiene"]
+        );
+    }
+
+    #[test]
+    fn test_grants() {
+        let input = include_str!("../tests/fixtures/grants.nbib");
+        let citations = PubMedParser::new().parse(input).unwrap();
+
+        let grant = |id: Option<&str>, agency: &str, country: &str| Grant {
+            id: id.map(String::from),
+            agency: Some(agency.to_string()),
+            country: Some(country.to_string()),
+        };
+        assert_eq!(
+            citations[0].grants,
+            vec![
+                grant(Some("R01 HL123456"), "NHLBI NIH HHS", "United States"),
+                grant(Some("098051"), "Wellcome Trust", "United Kingdom"),
+                grant(
+                    Some("MC_UU_12015/1"),
+                    "Medical Research Council",
+                    "United Kingdom"
+                ),
+                grant(None, "Cancer Research UK", "United Kingdom"),
+            ]
+        );
+        assert!(citations[0].extra("GR").is_none());
+        assert!(citations[1].grants.is_empty());
+    }
+
+    #[test]
+    fn test_investigators() {
+        let input = "PMID- 12345678
+TI  - Consortium Trial
+FAU - Smith, John
+AU  - Smith J
+AD  - University of Testing
+FIR - Doe, Jane
+IR  - Doe J
+IRAD- Hospital of Testing
+";
+        let citations = PubMedParser::new().parse(input).unwrap();
+        let authors = &citations[0].authors;
+        assert_eq!(authors.len(), 2);
+        assert_eq!(authors[0].role, None);
+        assert_eq!(
+            authors[0].affiliation.as_deref(),
+            Some("University of Testing")
+        );
+        assert_eq!(authors[1].family_name, "Doe");
+        assert_eq!(authors[1].role, Some(AuthorRole::Investigator));
+        assert_eq!(
+            authors[1].affiliation.as_deref(),
+            Some("Hospital of Testing")
+        );
+    }
+
+    #[test]
+    fn test_ahead_of_print() {
+        let input = "PMID- 12345678
+TI  - Published Online First
+DEP - 20231228
+PST - aheadofprint
+SO  - J Tests. 2023 Dec 28. doi: 10.1000/test. [Epub ahead of print]
+
+PMID- 23456789
+TI  - Status Only In Source
+DP  - 2024 Jan 2
+SO  - J Tests. 2024 Jan 2. [Epub ahead of print]
+
+PMID- 34567890
+TI  - In Print
+DP  - 2024 Mar
+DEP - 20231228
+PST - ppublish
+";
+        let citations = PubMedParser::new().parse(input).unwrap();
+        assert_eq!(citations[0].year, Some(2023));
+        assert_eq!(citations[0].status.as_deref(), Some("aheadofprint"));
+        assert_eq!(citations[1].year, Some(2024));
+        assert_eq!(citations[1].status.as_deref(), Some("aheadofprint"));
+        assert_eq!(citations[2].year, Some(2024));
+        assert_eq!(citations[2].status.as_deref(), Some("ppublish"));
+    }
+
+    #[test]
+    fn test_wrapped_lines() {
+        let input = include_str!("../tests/fixtures/wrapped_abstract.nbib");
+        let citation = PubMedParser::new().parse_one(input).unwrap();
+
+        assert_eq!(
+            citation.title,
+            "Effect of a structured exercise programme on fatigue in adults receiving \
+             adjuvant chemotherapy for breast cancer: a randomised controlled trial."
+        );
+        assert_eq!(
+            citation.abstract_text.as_deref(),
+            Some(
+                "BACKGROUND: Fatigue is the most common side effect of adjuvant chemotherapy \
+                 for breast cancer. We assessed whether a supervised exercise programme reduces \
+                 fatigue during treatment. METHODS: We randomly assigned 240 women to exercise \
+                 or usual care. RESULTS: Fatigue scores were lower with exercise (mean \
+                 difference -1.2, 95% CI -1.9 to -0.5). CONCLUSIONS: Supervised exercise \
+                 reduces fatigue during adjuvant chemotherapy."
+            )
+        );
+        assert_eq!(
+            citation.authors[0].affiliation.as_deref(),
+            Some("Department of Oncology, Rigshospitalet, University of Copenhagen, Copenhagen, Denmark.")
+        );
+        assert_eq!(
+            citation.journal.as_deref(),
+            Some("Annals of oncology : official journal of the European Society for Medical Oncology")
+        );
+        assert_eq!(citation.language.as_deref(), Some("eng"));
+    }
+
+    #[test]
+    fn test_comments_corrections() {
+        let input = include_str!("../tests/fixtures/retraction.nbib");
+        let citations = PubMedParser::new().parse(input).unwrap();
+
+        let related = |ref_type: &str, pmid: &str| RelatedArticle {
+            ref_type: ref_type.to_string(),
+            pmid: pmid.to_string(),
+        };
+        assert_eq!(
+            citations[0].related,
+            vec![
+                related("CommentIn", "32000003"),
+                related("RetractionIn", "32000002"),
+            ]
+        );
+        assert_eq!(
+            citations[0].extra("CIN"),
+            Some(&["Letter to the editor, J Tests. 2020.".to_string()][..])
+        );
+        assert!(citations[0].extra("RIN").is_none());
+        assert_eq!(
+            citations[1].related,
+            vec![related("RetractionOf", "32000001")]
+        );
+    }
 }
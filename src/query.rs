@@ -0,0 +1,296 @@
+//! Composable predicates for filtering citations.
+//!
+//! A [`Query`] is built from conditions that must all hold, and queries can be
+//! combined with [`Query::and`], [`Query::or`] and [`Query::negate`].
+//!
+//! # Example
+//!
+//! ```
+//! use biblib::query::Query;
+//! use biblib::{Author, Citation};
+//!
+//! let citation = Citation {
+//!     title: "Sleep and memory".to_string(),
+//!     year: Some(2015),
+//!     doi: Some("10.1000/sleep".to_string()),
+//!     authors: vec![Author {
+//!         family_name: "Smith".to_string(),
+//!         given_name: "John".to_string(),
+//!         ..Default::default()
+//!     }],
+//!     ..Default::default()
+//! };
+//!
+//! let query = Query::new()
+//!     .year_between(2010, 2020)
+//!     .has_doi()
+//!     .author_contains("smith");
+//! assert!(query.matches(&citation));
+//!
+//! let either = Query::new()
+//!     .title_contains("cancer")
+//!     .or(Query::new().title_contains("memory"));
+//! assert!(either.matches(&citation));
+//! ```
+
+use std::fmt;
+
+use crate::Citation;
+
+type Predicate = Box<dyn Fn(&Citation) -> bool + Send + Sync>;
+
+/// A reusable predicate over citations.
+///
+/// Each builder method adds a condition, and a citation matches when it meets
+/// all of them. An empty query matches every citation. Text conditions ignore
+/// case.
+#[derive(Default)]
+pub struct Query {
+    predicates: Vec<Predicate>,
+}
+
+impl fmt::Debug for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Query")
+            .field("conditions", &self.predicates.len())
+            .finish()
+    }
+}
+
+impl Query {
+    /// Creates a query matching every citation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::query::Query;
+    /// use biblib::Citation;
+    ///
+    /// assert!(Query::new().matches(&Citation::default()));
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a custom condition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::query::Query;
+    ///
+    /// let query = Query::new().custom(|c| c.keywords.len() > 2);
+    /// ```
+    #[must_use]
+    pub fn custom(mut self, predicate: impl Fn(&Citation) -> bool + Send + Sync + 'static) -> Self {
+        self.predicates.push(Box::new(predicate));
+        self
+    }
+
+    /// Requires a publication year between `from` and `to`, inclusive.
+    ///
+    /// Citations without a year do not match.
+    #[must_use]
+    pub fn year_between(self, from: i32, to: i32) -> Self {
+        self.custom(move |c| c.year.is_some_and(|year| (from..=to).contains(&year)))
+    }
+
+    /// Requires a DOI.
+    #[must_use]
+    pub fn has_doi(self) -> Self {
+        self.custom(|c| c.doi.as_deref().is_some_and(|doi| !doi.trim().is_empty()))
+    }
+
+    /// Requires an abstract.
+    #[must_use]
+    pub fn has_abstract(self) -> Self {
+        self.custom(|c| {
+            c.abstract_text
+                .as_deref()
+                .is_some_and(|text| !text.trim().is_empty())
+        })
+    }
+
+    /// Requires an author whose name contains `text`, e.g. `"smith"` or
+    /// `"john smith"`.
+    #[must_use]
+    pub fn author_contains(self, text: &str) -> Self {
+        let text = text.to_lowercase();
+        self.custom(move |c| {
+            c.authors.iter().any(|author| {
+                format!("{} {}", author.given_name, author.family_name)
+                    .to_lowercase()
+                    .contains(&text)
+                    || format!("{}, {}", author.family_name, author.given_name)
+                        .to_lowercase()
+                        .contains(&text)
+            })
+        })
+    }
+
+    /// Requires a title containing `text`.
+    #[must_use]
+    pub fn title_contains(self, text: &str) -> Self {
+        let text = text.to_lowercase();
+        self.custom(move |c| c.title.to_lowercase().contains(&text))
+    }
+
+    /// Requires a journal name or abbreviation containing `text`.
+    #[must_use]
+    pub fn journal_contains(self, text: &str) -> Self {
+        let text = text.to_lowercase();
+        self.custom(move |c| {
+            [&c.journal, &c.journal_abbr]
+                .into_iter()
+                .flatten()
+                .any(|journal| journal.to_lowercase().contains(&text))
+        })
+    }
+
+    /// Requires a keyword or MeSH term equal to `keyword`.
+    #[must_use]
+    pub fn has_keyword(self, keyword: &str) -> Self {
+        let keyword = keyword.to_lowercase();
+        self.custom(move |c| {
+            c.keywords
+                .iter()
+                .chain(&c.mesh_terms)
+                .any(|k| k.trim().to_lowercase() == keyword)
+        })
+    }
+
+    /// Requires the citation to come from `source`.
+    #[must_use]
+    pub fn source_is(self, source: &str) -> Self {
+        let source = source.to_lowercase();
+        self.custom(move |c| {
+            c.source
+                .as_deref()
+                .is_some_and(|s| s.to_lowercase() == source)
+        })
+    }
+
+    /// Requires both this query and `other` to match.
+    #[must_use]
+    pub fn and(self, other: Query) -> Self {
+        self.custom(move |c| other.matches(c))
+    }
+
+    /// Requires this query or `other` to match.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::query::Query;
+    ///
+    /// let recent_or_open = Query::new()
+    ///     .year_between(2020, 2029)
+    ///     .or(Query::new().source_is("PubMed Central"));
+    /// ```
+    #[must_use]
+    pub fn or(self, other: Query) -> Self {
+        Query::new().custom(move |c| self.matches(c) || other.matches(c))
+    }
+
+    /// Matches the citations this query does not match.
+    #[must_use]
+    pub fn negate(self) -> Self {
+        Query::new().custom(move |c| !self.matches(c))
+    }
+
+    /// Checks whether a citation meets every condition of the query.
+    #[must_use]
+    pub fn matches(&self, citation: &Citation) -> bool {
+        self.predicates.iter().all(|predicate| predicate(citation))
+    }
+
+    /// Returns the citations matching the query, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::query::Query;
+    /// use biblib::Citation;
+    ///
+    /// let citations = vec![
+    ///     Citation { year: Some(2001), ..Default::default() },
+    ///     Citation { year: Some(2015), ..Default::default() },
+    /// ];
+    /// let query = Query::new().year_between(2010, 2020);
+    /// assert_eq!(query.filter(&citations).count(), 1);
+    /// ```
+    pub fn filter<'a>(
+        &'a self,
+        citations: &'a [Citation],
+    ) -> impl Iterator<Item = &'a Citation> + 'a {
+        citations
+            .iter()
+            .filter(move |citation| self.matches(citation))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Author;
+
+    fn citation(year: Option<i32>, doi: Option<&str>, family_name: &str) -> Citation {
+        Citation {
+            title: "Effects of light on sleep".to_string(),
+            year,
+            doi: doi.map(String::from),
+            authors: vec![Author {
+                family_name: family_name.to_string(),
+                given_name: "Ann".to_string(),
+                ..Default::default()
+            }],
+            journal: Some("Sleep Medicine".to_string()),
+            keywords: vec!["Circadian Rhythm".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_conditions() {
+        let citation = citation(Some(2015), Some("10.1000/x"), "Smith");
+
+        assert!(Query::new().year_between(2015, 2015).matches(&citation));
+        assert!(!Query::new().year_between(2016, 2020).matches(&citation));
+        assert!(!Query::new()
+            .year_between(2010, 2020)
+            .matches(&self::citation(None, None, "Smith")));
+        assert!(Query::new().has_doi().matches(&citation));
+        assert!(!Query::new().has_abstract().matches(&citation));
+        assert!(Query::new().author_contains("ann smith").matches(&citation));
+        assert!(Query::new().author_contains("SMITH, A").matches(&citation));
+        assert!(!Query::new().author_contains("jones").matches(&citation));
+        assert!(Query::new().title_contains("LIGHT").matches(&citation));
+        assert!(Query::new().journal_contains("sleep").matches(&citation));
+        assert!(Query::new()
+            .has_keyword("circadian rhythm")
+            .matches(&citation));
+        assert!(!Query::new().source_is("PubMed").matches(&citation));
+    }
+
+    #[test]
+    fn test_combinators() {
+        let citations = vec![
+            citation(Some(2015), Some("10.1000/x"), "Smith"),
+            citation(Some(2015), None, "Jones"),
+            citation(Some(1999), None, "Smith"),
+        ];
+
+        let smith_or_jones = Query::new()
+            .author_contains("smith")
+            .or(Query::new().author_contains("jones"));
+        assert_eq!(smith_or_jones.filter(&citations).count(), 3);
+
+        let recent_without_doi = Query::new()
+            .year_between(2010, 2020)
+            .and(Query::new().has_doi().negate());
+        let matched: Vec<_> = recent_without_doi.filter(&citations).collect();
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].authors[0].family_name, "Jones");
+    }
+}
@@ -0,0 +1,379 @@
+//! refer format parser implementation with source tracking support.
+//!
+//! Parses the percent-tagged format of the UNIX `refer` tool, also exported by
+//! EndNote and other reference managers. Each line starts with a `%` tag, and
+//! records are separated by blank lines.
+//!
+//! # Example
+//!
+//! ```
+//! use biblib::{CitationParser, ReferParser};
+//!
+//! let input = "%T Example Title\n%A Smith, John\n%D 2020\n";
+//!
+//! let parser = ReferParser::new().with_source("refer");
+//!
+//! let citations = parser.parse(input).unwrap();
+//! assert_eq!(citations[0].title, "Example Title");
+//! assert_eq!(citations[0].year, Some(2020));
+//! assert_eq!(citations[0].source.as_deref(), Some("refer"));
+//! ```
+
+use nanoid::nanoid;
+
+use crate::utils::{
+    clean_input, extract_year, format_doi, format_page_numbers, parse_author_name, push_capped,
+    split_issns, ParserOptions, WarningHandler,
+};
+use crate::{Author, Citation, CitationError, CitationParser, Result};
+
+/// Parser for refer format citations.
+///
+/// # Field handling
+///
+/// | Tag  | Field                  |
+/// |------|------------------------|
+/// | `%0` | `citation_type`        |
+/// | `%T` | `title`                |
+/// | `%A` | `authors`              |
+/// | `%J` | `journal`              |
+/// | `%B` | `journal` (if no `%J`) |
+/// | `%D` | `year`                 |
+/// | `%V` | `volume`               |
+/// | `%N` | `issue`                |
+/// | `%P` | `pages`                |
+/// | `%K` | `keywords`             |
+/// | `%X` | `abstract_text`        |
+/// | `%I` | `publisher`            |
+/// | `%C` | `place`                |
+/// | `%S` | `series`               |
+/// | `%R` | `doi` (if a DOI)       |
+/// | `%U` | `urls`                 |
+/// | `%@` | `issn`                 |
+/// | `%G` | `language`             |
+///
+/// All other tags are stored in `extra_fields` under their tag, e.g. `%E`.
+/// Lines that do not start with a tag continue the previous field.
+#[derive(Debug, Default, Clone)]
+pub struct ReferParser {
+    options: ParserOptions,
+}
+
+impl ReferParser {
+    /// Creates a new refer parser instance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::ReferParser;
+    /// let parser = ReferParser::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the source of the parsed citations.
+    ///
+    /// Each citation's record is also named under
+    /// [`SOURCE_RECORD_KEY`](crate::SOURCE_RECORD_KEY).
+    #[must_use]
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.options.source = Some(source.to_string());
+        self
+    }
+
+    /// Stops parsing once `limit` citations have been read.
+    ///
+    /// Useful for previewing large inputs. A limit of `0` returns no citations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::ReferParser;
+    /// let parser = ReferParser::new().with_limit(10);
+    /// ```
+    #[must_use]
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.options.limit = Some(limit);
+        self
+    }
+
+    /// Sets the longest field, in bytes, kept from the input.
+    ///
+    /// Longer fields are truncated at a character boundary, as they are read,
+    /// and a warning is reported through [`ReferParser::with_warning_handler`].
+    /// Defaults to [`DEFAULT_MAX_FIELD_LENGTH`](crate::DEFAULT_MAX_FIELD_LENGTH).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::ReferParser;
+    /// let parser = ReferParser::new().with_max_field_length(64 * 1024);
+    /// ```
+    #[must_use]
+    pub fn with_max_field_length(mut self, max: usize) -> Self {
+        self.options.max_field_length = Some(max);
+        self
+    }
+
+    /// Sets the function receiving warnings, which are ignored otherwise.
+    ///
+    /// Warnings are reported when input is altered, for example when a field
+    /// exceeds the [maximum length](ReferParser::with_max_field_length).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::ReferParser;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let warnings = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = Arc::clone(&warnings);
+    /// let parser = ReferParser::new()
+    ///     .with_warning_handler(move |message| sink.lock().unwrap().push(message.to_string()));
+    /// ```
+    #[must_use]
+    pub fn with_warning_handler(mut self, handler: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.options.warning_handler = WarningHandler::new(handler);
+        self
+    }
+
+    /// Sets whether whitespace is normalized in every field.
+    ///
+    /// When enabled, runs of whitespace, including non-breaking spaces and
+    /// line breaks, become a single space and zero-width characters are
+    /// removed, as by
+    /// [`normalize_whitespace`](crate::transform::normalize_whitespace). Off by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::ReferParser;
+    /// let parser = ReferParser::new().with_whitespace_normalization(true);
+    /// ```
+    #[must_use]
+    pub fn with_whitespace_normalization(mut self, enabled: bool) -> Self {
+        self.options.normalize_whitespace = enabled;
+        self
+    }
+
+    /// Splits a line into its tag and content, if it starts with a tag
+    fn split_tag(line: &str) -> Option<(&str, &str)> {
+        let mut chars = line.char_indices();
+        match (chars.next(), chars.next()) {
+            (Some((0, '%')), Some((_, c))) if !c.is_whitespace() => {
+                let end = 1 + c.len_utf8();
+                Some((&line[..end], line[end..].trim()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Builds a citation from the tagged lines of a single record.
+    ///
+    /// Returns `None` if the record has no title.
+    fn build_citation(&self, fields: &[(&str, String)]) -> Option<Citation> {
+        let mut citation = Citation {
+            id: nanoid!(),
+            source: self.options.source.clone(),
+            ..Default::default()
+        };
+        let mut book_title = None;
+
+        for (tag, content) in fields {
+            let content = content.as_str();
+            match *tag {
+                "%0" => citation.citation_type.push(content.to_string()),
+                "%T" if citation.title.is_empty() => citation.title = content.to_string(),
+                "%A" => {
+                    let (family, given) = parse_author_name(content);
+                    citation.authors.push(Author {
+                        family_name: family,
+                        given_name: given,
+                        ..Default::default()
+                    });
+                }
+                "%J" if citation.journal.is_none() => citation.journal = Some(content.to_string()),
+                "%B" if book_title.is_none() => book_title = Some(content.to_string()),
+                "%D" if citation.year.is_none() => citation.year = extract_year(content),
+                "%V" if citation.volume.is_none() => citation.volume = Some(content.to_string()),
+                "%N" if citation.issue.is_none() => citation.issue = Some(content.to_string()),
+                "%P" if citation.pages.is_none() => {
+                    citation.pages = Some(format_page_numbers(content))
+                }
+                "%K" => citation.keywords.push(content.to_string()),
+                "%X" if citation.abstract_text.is_none() => {
+                    citation.abstract_text = Some(content.to_string())
+                }
+                "%I" if citation.publisher.is_none() => {
+                    citation.publisher = Some(content.to_string())
+                }
+                "%C" if citation.place.is_none() => citation.place = Some(content.to_string()),
+                "%S" if citation.series.is_none() => citation.series = Some(content.to_string()),
+                "%R" if format_doi(content).is_some() => citation.add_doi(content),
+                "%U" => {
+                    if citation.doi.is_none() && content.contains("doi.org") {
+                        citation.add_doi(content);
+                    }
+                    citation.urls.push(content.to_string());
+                }
+                "%@" => citation.issn.extend(split_issns(content)),
+                "%G" if citation.language.is_none() => {
+                    citation.language = Some(content.to_string())
+                }
+                _ => {
+                    citation
+                        .extra_fields
+                        .entry(tag.to_string())
+                        .or_default()
+                        .push(content.to_string());
+                }
+            }
+        }
+
+        if citation.journal.is_none() {
+            citation.journal = book_title;
+        }
+
+        if citation.title.is_empty() {
+            None
+        } else {
+            Some(citation)
+        }
+    }
+}
+
+impl CitationParser for ReferParser {
+    fn parse(&self, input: &str) -> Result<Vec<Citation>> {
+        let input: &str = &clean_input(input);
+        if input.trim().is_empty() {
+            return Err(CitationError::InvalidFormat("Empty input".into()));
+        }
+
+        let limit = self.options.limit();
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let max_field_length = self.options.max_field_length();
+        let mut citations = Vec::new();
+        let mut record: Vec<(&str, String)> = Vec::new();
+
+        for line in input.lines() {
+            let line = line.trim_end();
+
+            if line.trim().is_empty() {
+                if !record.is_empty() {
+                    citations.extend(self.build_citation(&record));
+                    record.clear();
+                    if citations.len() >= limit {
+                        break;
+                    }
+                }
+                continue;
+            }
+
+            match Self::split_tag(line) {
+                Some((tag, content)) => record.push((tag, content.to_string())),
+                None => {
+                    // Continuation of the previous field
+                    if let Some((tag, content)) = record.last_mut() {
+                        let more = format!(" {}", line.trim());
+                        if push_capped(content, &more, max_field_length) {
+                            self.options.warning_handler.warn(&format!(
+                                "truncated {tag} field to {max_field_length} bytes"
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        if citations.len() < limit && !record.is_empty() {
+            citations.extend(self.build_citation(&record));
+        }
+
+        if citations.is_empty() {
+            return Err(CitationError::InvalidFormat(
+                "No valid citations found".into(),
+            ));
+        }
+
+        self.options.finish(&mut citations);
+        Ok(citations)
+    }
+
+    fn format_name(&self) -> &'static str {
+        "refer"
+    }
+
+    fn can_parse(&self, input: &str) -> bool {
+        let input: &str = &clean_input(input);
+        let trimmed = input.trim();
+        trimmed.starts_with('%') && trimmed.lines().any(|line| line.starts_with("%T "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_refer_records() {
+        let input = r#"%0 Journal Article
+%A Smith, John
+%A Doe, Jane
+%T Effects of exercise on
+sleep quality
+%J Journal of Testing
+%D 2019 Mar
+%V 12
+%N 3
+%P 100-110
+%K exercise
+%K sleep
+%X A short abstract.
+%R 10.1000/test.2019
+%E Editor, Ed
+
+%T Second Record
+%B Book of Tests
+%I Test Press
+%C London
+%D 2001
+"#;
+
+        let parser = ReferParser::new();
+        let citations = parser.parse(input).unwrap();
+        assert_eq!(citations.len(), 2);
+
+        let citation = &citations[0];
+        assert_eq!(citation.citation_type, vec!["Journal Article"]);
+        assert_eq!(citation.title, "Effects of exercise on sleep quality");
+        assert_eq!(citation.authors.len(), 2);
+        assert_eq!(citation.authors[1].family_name, "Doe");
+        assert_eq!(citation.journal.as_deref(), Some("Journal of Testing"));
+        assert_eq!(citation.year, Some(2019));
+        assert_eq!(citation.volume.as_deref(), Some("12"));
+        assert_eq!(citation.issue.as_deref(), Some("3"));
+        assert_eq!(citation.pages.as_deref(), Some("100-110"));
+        assert_eq!(citation.keywords, vec!["exercise", "sleep"]);
+        assert_eq!(citation.abstract_text.as_deref(), Some("A short abstract."));
+        assert_eq!(citation.doi.as_deref(), Some("10.1000/test.2019"));
+        assert_eq!(citation.extra_first("%E"), Some("Editor, Ed"));
+
+        let book = &citations[1];
+        assert_eq!(book.journal.as_deref(), Some("Book of Tests"));
+        assert_eq!(book.publisher.as_deref(), Some("Test Press"));
+        assert_eq!(book.place.as_deref(), Some("London"));
+    }
+
+    #[test]
+    fn test_skip_untitled_records() {
+        let parser = ReferParser::new();
+        let citations = parser.parse("%A Smith, J\n\n%T Titled\n").unwrap();
+        assert_eq!(citations.len(), 1);
+        assert!(parser.parse("%A Smith, J\n").is_err());
+        assert!(parser.parse("").is_err());
+    }
+}
@@ -20,17 +20,151 @@
 //! assert_eq!(citations[0].source.as_deref(), Some("Google Scholar"));
 //! ```
 
-use crate::utils::{format_doi, format_page_numbers, parse_author_name};
-use crate::{Author, Citation, CitationError, CitationParser, Result};
+use crate::utils::{
+    clean_input, extract_year, format_doi, format_page_numbers, is_isbn_shaped, parse_author_name,
+    push_capped, split_dois, split_volume_issue, ParserOptions, WarningHandler,
+};
+use crate::{Author, AuthorRole, Citation, CitationError, CitationParser, Result};
 use nanoid::nanoid;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use std::io::{BufRead, Write};
+use std::{iter, mem};
+
+/// A tag line with a non-canonical separator, such as `TI - Title` or `TI\tTitle`
+static LENIENT_TAG_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^([A-Z][A-Z0-9])\s*-?\s*(.*)$").unwrap());
+
+/// The citation count Scopus writes into `N1`, e.g. `Cited By: 12; Export Date: ...`
+static CITED_BY_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\bcited by\s*:?\s*(\d+)").unwrap());
 
 /// Parser for RIS format citations.
 ///
 /// RIS is a standardized format for bibliographic citations that uses two-letter
 /// tags at the start of each line to denote different citation fields.
+///
+/// # Field handling
+///
+/// | Tags                        | Field              | Repeated tags |
+/// |-----------------------------|--------------------|---------------|
+/// | `TY`                        | `citation_type`    | all kept      |
+/// | `TI`, `T1`                  | `title`            | first kept    |
+/// | `AU`, `A1`-`A4`, `ED`       | `authors`          | all kept      |
+/// | `JF`, `T2`                  | `journal`          | first kept    |
+/// | `JA`, `J2`, `JO`            | `journal_abbr`     | first kept    |
+/// | `PY`, `Y1`, `DA`            | `year`             | first kept    |
+/// | `VL`                        | `volume`           | first kept    |
+/// | `IS`                        | `issue`            | first kept    |
+/// | `SP`, `EP`                  | `pages`            | first kept    |
+/// | `DO`                        | `doi`              | first kept    |
+/// | `ID`                        | `pmid`, or `id`    | first kept    |
+/// | `AN`                        | `accession_number` | first kept    |
+/// | `AB`, `N2`                  | `abstract_text`    | first kept    |
+/// | `KW` (see below)            | `keywords`         | all kept      |
+/// | `SN` (ISSNs)                | `issn`             | all kept      |
+/// | `SN` (ISBNs)                | `isbn`             | all kept      |
+/// | `L1`-`L4`, `UR`, `LK`       | `urls`             | all kept      |
+/// | `LA`                        | `language`         | first kept    |
+/// | `PB`                        | `publisher`        | first kept    |
+/// | `CY`, `C1` (conferences)    | `place`            | first kept    |
+/// | `T2`, `BT` (conferences)    | `conference_name`  | first kept    |
+/// | `T3`                        | `series`           | first kept    |
+/// | `C2` (containing "PMC")     | `pmc_id`           | first kept    |
+/// | `N1` (`Cited By: n`)        | `cited_by_count`   | first kept    |
+/// | `DB` (without a source set) | `source`           | first kept    |
+///
+/// A `VL` holding both volume and issue, such as `12(3)`, is split into
+/// `volume` and `issue`, unless the record also has an `IS`, which is then
+/// kept as the issue. Other `VL` values are kept as they are.
+///
+/// Every DOI of every `DO` line, including lines listing several DOIs
+/// separated by spaces or semicolons, is kept in `all_dois`. The first DOI
+/// that is not from a data repository becomes `doi`.
+///
+/// For "first kept" fields, the first usable value wins, trying tags in the order
+/// listed. Any other values for those tags are stored in `extra_fields` under
+/// their tag, with a warning through [`RisParser::with_warning_handler`], as are
+/// all unrecognized tags. `DB` (database) and `DP` (database
+/// provider) are always kept in `extra_fields`. `N2` is only used as the abstract when
+/// a record has no `AB`, and is always kept in `extra_fields` as well.
+///
+/// `ID` is the exporter's reference ID. It is kept as `pmid` unless
+/// [`RisParser::with_ris_id`] is enabled, when it becomes the citation's `id`
+/// and is kept under [`RIS_ID_KEY`].
+///
+/// Conferences are records of type `CONF` or `CPAPER`. Their `T2` is used both
+/// as the journal and as the conference name.
+///
+/// A single `KW` line holding several keywords is split, see
+/// [`RisParser::with_keyword_delimiter`].
+///
+/// See [`RisDialect`] for the additional mappings used for EndNote exports.
+///
+/// Tag lines whose separator differs from the canonical `TAG  - value`, such as
+/// `TAG - value`, `TAG\tvalue` or `TAG value`, are accepted unless strict tags
+/// are enabled with [`RisParser::with_strict_tags`].
 #[derive(Debug, Default, Clone)]
 pub struct RisParser {
-    source: Option<String>,
+    options: ParserOptions,
+    dialect: RisDialect,
+    keyword_split: KeywordSplit,
+    strict_tags: bool,
+    field_order: bool,
+    ris_id: bool,
+    #[cfg(feature = "parallel")]
+    parallel: bool,
+}
+
+/// Key of the `extra_fields` entry listing a record's tags in input order,
+/// written by [`RisParser::with_field_order`] and read by [`write_ris`]
+pub const FIELD_ORDER_KEY: &str = "field_order";
+
+/// Key of the `extra_fields` entry holding a record's `ID`, written by
+/// [`RisParser::with_ris_id`] and read by [`write_ris`]
+pub const RIS_ID_KEY: &str = "ris_id";
+
+/// Tags read into the same field as the tag [`write_ris`] writes for it
+const TAG_ALIASES: [(&str, &[&str]); 9] = [
+    ("TI", &["T1"]),
+    ("AU", &["A1"]),
+    ("A2", &["ED", "A3"]),
+    ("JF", &["T2"]),
+    ("JA", &["J2", "JO"]),
+    ("T2", &["BT"]),
+    ("PY", &["Y1"]),
+    ("AB", &["N2"]),
+    ("UR", &["L1", "L2", "L3", "L4", "LK"]),
+];
+
+/// How `KW` values are split into separate keywords
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum KeywordSplit {
+    /// Split on `;` or `,` only when a record has a single `KW` line
+    #[default]
+    Auto,
+    /// Never split
+    Never,
+    /// Always split on the given delimiter
+    On(char),
+}
+
+/// Variant of the RIS format produced by a particular tool.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RisDialect {
+    /// RIS as described by the format specification
+    #[default]
+    Standard,
+    /// RIS as exported by EndNote:
+    ///
+    /// - `M1` is used for the issue when there is no `IS`
+    /// - `M3` is used for the DOI when it holds one and there is no `DO`,
+    ///   otherwise it is added to `citation_type`
+    /// - lines without a tag continue the previous field, so URLs wrapped over
+    ///   several lines are joined back together
+    EndNote,
 }
 
 impl RisParser {
@@ -44,21 +178,293 @@ impl RisParser {
     /// ```
     #[must_use]
     pub fn new() -> Self {
-        Self { source: None }
+        Self::default()
     }
 
+    /// Creates a parser for RIS files exported by EndNote.
+    ///
+    /// Shorthand for `RisParser::new().with_dialect(RisDialect::EndNote)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::RisParser;
+    /// let parser = RisParser::endnote_dialect();
+    /// ```
+    #[must_use]
+    pub fn endnote_dialect() -> Self {
+        Self::new().with_dialect(RisDialect::EndNote)
+    }
+
+    /// Sets the source of the parsed citations.
+    ///
+    /// Without a source, each citation's source is taken from its `DB` tag, if
+    /// it has one. Each citation's record is also named under
+    /// [`SOURCE_RECORD_KEY`](crate::SOURCE_RECORD_KEY).
     pub fn with_source(mut self, source: &str) -> Self {
-        self.source = Some(source.to_string());
+        self.options.source = Some(source.to_string());
         self
     }
 
+    /// Stops parsing once `limit` citations have been read.
+    ///
+    /// Useful for previewing large inputs. A limit of `0` returns no citations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::RisParser;
+    /// let parser = RisParser::new().with_limit(10);
+    /// ```
+    #[must_use]
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.options.limit = Some(limit);
+        self
+    }
+
+    /// Sets the longest field, in bytes, kept from the input.
+    ///
+    /// Longer fields are truncated at a character boundary, as they are read,
+    /// and a warning is reported through [`RisParser::with_warning_handler`].
+    /// Defaults to [`DEFAULT_MAX_FIELD_LENGTH`](crate::DEFAULT_MAX_FIELD_LENGTH).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::RisParser;
+    /// let parser = RisParser::new().with_max_field_length(64 * 1024);
+    /// ```
+    #[must_use]
+    pub fn with_max_field_length(mut self, max: usize) -> Self {
+        self.options.max_field_length = Some(max);
+        self
+    }
+
+    /// Sets the function receiving warnings, which are ignored otherwise.
+    ///
+    /// Warnings are reported when input is altered, for example when a field
+    /// exceeds the [maximum length](RisParser::with_max_field_length), or when a
+    /// record repeats a single-valued tag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::RisParser;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let warnings = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = Arc::clone(&warnings);
+    /// let parser = RisParser::new()
+    ///     .with_warning_handler(move |message| sink.lock().unwrap().push(message.to_string()));
+    /// ```
+    #[must_use]
+    pub fn with_warning_handler(mut self, handler: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.options.warning_handler = WarningHandler::new(handler);
+        self
+    }
+
+    /// Sets whether whitespace is normalized in every field.
+    ///
+    /// When enabled, runs of whitespace, including non-breaking spaces and
+    /// line breaks, become a single space and zero-width characters are
+    /// removed, as by
+    /// [`normalize_whitespace`](crate::transform::normalize_whitespace). Off by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::RisParser;
+    /// let parser = RisParser::new().with_whitespace_normalization(true);
+    /// ```
+    #[must_use]
+    pub fn with_whitespace_normalization(mut self, enabled: bool) -> Self {
+        self.options.normalize_whitespace = enabled;
+        self
+    }
+
+    /// Sets the RIS dialect of the input.
+    ///
+    /// Defaults to [`RisDialect::Standard`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::ris::{RisDialect, RisParser};
+    /// let parser = RisParser::new().with_dialect(RisDialect::EndNote);
+    /// ```
+    #[must_use]
+    pub fn with_dialect(mut self, dialect: RisDialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Sets the delimiter used to split `KW` values into keywords.
+    ///
+    /// With `Some(delimiter)`, every `KW` line is split on it. With `None`, each
+    /// `KW` line is kept as one keyword. By default, a record's `KW` line is
+    /// split on `;`, or else `,`, only when it is the record's only `KW` line,
+    /// so files using one `KW` per keyword are left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{CitationParser, RisParser};
+    ///
+    /// let input = "TY  - JOUR\nTI  - Title\nKW  - one|two\nER  -";
+    /// let parser = RisParser::new().with_keyword_delimiter(Some('|'));
+    /// let citations = parser.parse(input).unwrap();
+    /// assert_eq!(citations[0].keywords, vec!["one", "two"]);
+    /// ```
+    #[must_use]
+    pub fn with_keyword_delimiter(mut self, delimiter: Option<char>) -> Self {
+        self.keyword_split = match delimiter {
+            Some(delimiter) => KeywordSplit::On(delimiter),
+            None => KeywordSplit::Never,
+        };
+        self
+    }
+
+    /// Sets whether only canonical `TAG  - value` lines are read as fields.
+    ///
+    /// By default, lines with an uppercase tag followed by another separator,
+    /// such as a single space, a tab or ` - `, are also accepted. In strict mode
+    /// such lines are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{CitationParser, RisParser};
+    ///
+    /// let input = "TY - JOUR\nTI\tTitle\nER -";
+    /// let citations = RisParser::new().parse(input).unwrap();
+    /// assert_eq!(citations[0].title, "Title");
+    ///
+    /// assert!(RisParser::new().with_strict_tags(true).parse(input).is_err());
+    /// ```
+    #[must_use]
+    pub fn with_strict_tags(mut self, strict: bool) -> Self {
+        self.strict_tags = strict;
+        self
+    }
+
+    /// Sets whether each citation records the order of its record's tags.
+    ///
+    /// When enabled, the tags are stored in input order, repeats included, in
+    /// `extra_fields` under [`FIELD_ORDER_KEY`]. [`write_ris`] then writes the
+    /// record's lines in that order, along with its unrecognized tags, so a
+    /// file read and written back only changes where values were normalized.
+    /// Disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{CitationParser, RisParser};
+    ///
+    /// let input = "TY  - JOUR\nPY  - 2020\nAU  - Smith, John\nTI  - Title\nN1  - A note\nER  - \n\n";
+    /// let parser = RisParser::new().with_field_order(true);
+    /// let citations = parser.parse(input).unwrap();
+    /// assert_eq!(citations[0].to_ris_string(), input);
+    /// ```
+    #[must_use]
+    pub fn with_field_order(mut self, enabled: bool) -> Self {
+        self.field_order = enabled;
+        self
+    }
+
+    /// Sets whether a record's `ID` becomes the citation's `id`.
+    ///
+    /// When enabled, the exporter's reference ID is kept as [`Citation::id`]
+    /// instead of `pmid`, so records can be reconciled with the exporting
+    /// reference manager. The `ID` is also kept under [`RIS_ID_KEY`], which
+    /// [`write_ris`] writes back as `ID`, so it survives merges that keep
+    /// another citation's `id`. Records without an `ID` get a generated id as
+    /// usual, which is not written. Disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{CitationParser, RisParser};
+    ///
+    /// let input = "TY  - JOUR\nID  - smith2020\nTI  - Title\nER  - \n";
+    /// let citations = RisParser::new().with_ris_id(true).parse(input).unwrap();
+    /// assert_eq!(citations[0].id, "smith2020");
+    /// assert_eq!(citations[0].pmid, None);
+    /// ```
+    #[must_use]
+    pub fn with_ris_id(mut self, enabled: bool) -> Self {
+        self.ris_id = enabled;
+        self
+    }
+
+    /// Sets whether records are converted to citations in parallel.
+    ///
+    /// The input is still split into records on one thread, then the records
+    /// are converted on the rayon thread pool, which pays off for large
+    /// exports. Citations are returned in input order either way, so the
+    /// output only differs in the generated ids. Disabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{CitationParser, RisParser};
+    ///
+    /// let input = "TY  - JOUR\nTI  - First\nER  -\nTY  - JOUR\nTI  - Second\nER  -\n";
+    /// let citations = RisParser::new().with_parallel(true).parse(input).unwrap();
+    /// assert_eq!(citations[0].title, "First");
+    /// assert_eq!(citations[1].title, "Second");
+    /// ```
+    #[cfg(feature = "parallel")]
+    #[must_use]
+    pub fn with_parallel(mut self, enabled: bool) -> Self {
+        self.parallel = enabled;
+        self
+    }
+
+    /// Splits a `KW` value into keywords according to the keyword settings
+    fn split_keywords(&self, content: &str, single_line: bool) -> Vec<String> {
+        let delimiter = match self.keyword_split {
+            KeywordSplit::On(delimiter) => Some(delimiter),
+            KeywordSplit::Never => None,
+            KeywordSplit::Auto if single_line => {
+                [';', ','].into_iter().find(|&d| content.contains(d))
+            }
+            KeywordSplit::Auto => None,
+        };
+        match delimiter {
+            Some(delimiter) => content
+                .split(delimiter)
+                .map(str::trim)
+                .filter(|keyword| !keyword.is_empty())
+                .map(String::from)
+                .collect(),
+            None => vec![content.to_string()],
+        }
+    }
+
+    /// Creates an empty citation with a fresh id and the parser's source
+    fn new_citation(&self) -> Citation {
+        Citation {
+            id: nanoid!(),
+            source: self.options.source.clone(),
+            ..Default::default()
+        }
+    }
+
     /// Parses an author string in various formats
-    fn parse_author(author_str: &str) -> Author {
+    ///
+    /// `A2`, `A3` and `ED` name editors and `A4` translators.
+    fn parse_author(tag: &str, author_str: &str) -> Author {
         let (family, given) = parse_author_name(author_str);
         Author {
             family_name: family,
             given_name: given,
             affiliation: None,
+            orcid: None,
+            role: match tag {
+                "A2" | "A3" | "ED" => Some(AuthorRole::Editor),
+                "A4" => Some(AuthorRole::Translator),
+                _ => None,
+            },
         }
     }
 
@@ -79,12 +485,13 @@ impl RisParser {
     /// # Arguments
     ///
     /// * `line` - The line to validate
+    /// * `strict` - Whether to reject separators other than the canonical `  - `
     ///
     /// # Returns
     ///
     /// * `Ok((tag, content))` if the line is valid
     /// * `Err(CitationError)` if the line is invalid
-    fn validate_line(line: &str) -> Result<(&str, &str)> {
+    fn validate_line(line: &str, strict: bool) -> Result<(&str, &str)> {
         if line.trim().is_empty() {
             return Err(CitationError::InvalidFormat("Empty line".into()));
         }
@@ -96,39 +503,303 @@ impl RisParser {
             )));
         }
 
-        let tag = &line[..2];
-        if !tag.chars().all(|c| c.is_ascii_alphanumeric()) {
+        let tag = line.get(..2).unwrap_or_default();
+        if tag.len() != 2 || !tag.chars().all(|c| c.is_ascii_alphanumeric()) {
             return Err(CitationError::InvalidFormat(format!(
                 "Invalid RIS tag format: '{}'",
                 tag
             )));
         }
 
-        let content = if line.len() > 6 && &line[2..6] == "  - " {
-            line[6..].trim()
+        if Self::is_tag_line(line) {
+            return Ok((tag, line[5..].trim()));
+        }
+        if strict {
+            return Err(CitationError::InvalidFormat(format!(
+                "Invalid RIS tag separator: '{}'",
+                line
+            )));
+        }
+
+        match LENIENT_TAG_REGEX.captures(line) {
+            Some(captures) => {
+                let (tag, content) = (captures.get(1).unwrap(), captures.get(2).unwrap());
+                Ok((&line[tag.range()], line[content.range()].trim()))
+            }
+            None => Ok((tag, line[2..].trim())),
+        }
+    }
+
+    /// Checks for records whose `TY` and `ER` tags use a non-canonical separator
+    fn has_lenient_tags(content: &str) -> bool {
+        let is_tag = |line: &str, tag: &str| {
+            line.trim_end()
+                .strip_prefix(tag)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t', '-']))
+        };
+        content.lines().any(|line| is_tag(line, "TY"))
+            && content.lines().any(|line| is_tag(line, "ER"))
+    }
+
+    /// Checks if a line starts with a tag followed by the `  -` separator
+    fn is_tag_line(line: &str) -> bool {
+        let bytes = line.as_bytes();
+        bytes.len() >= 5
+            && bytes[..2].iter().all(u8::is_ascii_alphanumeric)
+            && &bytes[2..5] == b"  -"
+    }
+
+    /// Takes the first value for a single-valued field.
+    ///
+    /// Tags are tried in order of priority, and only values accepted by `accept`
+    /// are considered. The chosen line is marked as used so it is not also
+    /// stored in `extra_fields`, and `tags` are added to `taken` so any other
+    /// line with them can be reported.
+    fn take_first<'a, 't>(
+        fields: &[(&str, &'a str)],
+        used: &mut [bool],
+        taken: &mut Vec<&'t str>,
+        tags: &[&'t str],
+        accept: impl Fn(&str) -> bool,
+    ) -> Option<&'a str> {
+        for tag in tags {
+            let position = fields
+                .iter()
+                .zip(used.iter())
+                .position(|((t, content), used)| !used && t == tag && accept(content));
+            if let Some(i) = position {
+                used[i] = true;
+                taken.extend_from_slice(tags);
+                return Some(fields[i].1);
+            }
+        }
+        None
+    }
+
+    /// Builds a citation from the tagged lines of a single record.
+    ///
+    /// Returns `None` if the record has no title.
+    fn build_citation(&self, fields: &[(&str, &str)]) -> Option<Citation> {
+        let mut citation = self.new_citation();
+        let mut used = vec![false; fields.len()];
+        let mut taken = Vec::new();
+        let mut first =
+            |tags: &[&'static str]| Self::take_first(fields, &mut used, &mut taken, tags, |_| true);
+
+        citation.title = first(&["TI", "T1"])?.to_string();
+        citation.journal = first(&["JF", "T2"]).map(String::from);
+        citation.journal_abbr = first(&["JA", "J2", "JO"]).map(String::from);
+        citation.volume = first(&["VL"]).map(String::from);
+        citation.issue = first(&["IS"]).map(String::from);
+        // Some exporters write the issue into the volume, as in `VL  - 12(3)`
+        if let Some((volume, issue)) = citation.volume.as_deref().and_then(split_volume_issue) {
+            citation.issue = citation.issue.or_else(|| Some(issue.to_string()));
+            citation.volume = Some(volume.to_string());
+        }
+        match first(&["ID"]) {
+            Some(id) if self.ris_id => {
+                citation.id = id.to_string();
+                citation
+                    .extra_fields
+                    .insert(RIS_ID_KEY.to_string(), vec![id.to_string()]);
+            }
+            id => citation.pmid = id.map(String::from),
+        }
+        citation.accession_number = first(&["AN"]).map(String::from);
+        citation.abstract_text = first(&["AB"]).map(String::from);
+        citation.language = first(&["LA"]).map(String::from);
+        citation.publisher = first(&["PB"]).map(String::from);
+        citation.series = first(&["T3"]).map(String::from);
+        citation.pages = match (first(&["SP"]), first(&["EP"])) {
+            (Some(start), Some(end)) => Some(format_page_numbers(&format!("{}-{}", start, end))),
+            (Some(page), None) | (None, Some(page)) => Some(format_page_numbers(page)),
+            (None, None) => None,
+        };
+        // DA is only a fallback for the year and is kept in extra_fields
+        citation.year = Self::take_first(fields, &mut used, &mut taken, &["PY", "Y1"], |c| {
+            extract_year(c).is_some()
+        })
+        .and_then(extract_year)
+        .or_else(|| {
+            fields
+                .iter()
+                .filter(|(tag, _)| *tag == "DA")
+                .find_map(|(_, content)| extract_year(content))
+        });
+        if let Some(dois) = Self::take_first(fields, &mut used, &mut taken, &["DO"], |c| {
+            format_doi(c).is_some()
+        }) {
+            for doi in split_dois(dois) {
+                citation.add_doi(doi);
+            }
+        }
+        citation.pmc_id = Self::take_first(fields, &mut used, &mut taken, &["C2"], |c| {
+            c.contains("PMC")
+        })
+        .map(String::from);
+
+        let is_conference = fields
+            .iter()
+            .any(|(tag, content)| *tag == "TY" && matches!(*content, "CONF" | "CPAPER"));
+        let place_tags: &[&str] = if is_conference {
+            &["CY", "C1"]
         } else {
-            line[2..].trim()
+            &["CY"]
         };
-        
-        Ok((tag, content))
+        citation.place =
+            Self::take_first(fields, &mut used, &mut taken, place_tags, |_| true).map(String::from);
+        if is_conference {
+            citation.conference_name = fields
+                .iter()
+                .find(|(tag, _)| *tag == "T2")
+                .or_else(|| fields.iter().find(|(tag, _)| *tag == "BT"))
+                .map(|(_, content)| content.to_string());
+        }
+
+        if self.dialect == RisDialect::EndNote {
+            if citation.issue.is_none() {
+                citation.issue = Self::take_first(fields, &mut used, &mut taken, &["M1"], |_| true)
+                    .map(String::from);
+            }
+            if citation.doi.is_none() {
+                if let Some(doi) = Self::take_first(fields, &mut used, &mut taken, &["M3"], |c| {
+                    format_doi(c).is_some()
+                }) {
+                    citation.add_doi(doi);
+                }
+            }
+        }
+
+        // N2 is used as the abstract only when there is no AB, but is kept in
+        // extra_fields so it is never lost
+        if citation.abstract_text.is_none() {
+            citation.abstract_text = fields
+                .iter()
+                .find(|(tag, _)| *tag == "N2")
+                .map(|(_, content)| content.to_string());
+        }
+
+        // Aggregators name the database in DB, used as the source unless one is set
+        if citation.source.is_none() {
+            citation.source = fields
+                .iter()
+                .find(|(tag, content)| *tag == "DB" && !content.is_empty())
+                .map(|(_, content)| content.to_string());
+        }
+
+        // Scopus notes the citation count in N1, which is also kept in extra_fields
+        citation.cited_by_count = fields
+            .iter()
+            .filter(|(tag, _)| *tag == "N1")
+            .find_map(|(_, content)| CITED_BY_REGEX.captures(content)?[1].parse().ok());
+
+        let single_keyword_line = fields.iter().filter(|(tag, _)| *tag == "KW").count() == 1;
+        for (&(tag, content), used) in fields.iter().zip(used) {
+            if used {
+                continue;
+            }
+            match tag {
+                "TY" => citation.citation_type.push(content.to_string()),
+                "AU" | "A1" | "A2" | "A3" | "A4" | "ED" => {
+                    citation.authors.push(Self::parse_author(tag, content))
+                }
+                "M3" if self.dialect == RisDialect::EndNote && format_doi(content).is_none() => {
+                    citation.citation_type.push(content.to_string())
+                }
+                "DO" if format_doi(content).is_some() => {
+                    for doi in split_dois(content) {
+                        citation.add_doi(doi);
+                    }
+                }
+                "KW" => citation
+                    .keywords
+                    .extend(self.split_keywords(content, single_keyword_line)),
+                "SN" if is_isbn_shaped(content) => citation.isbn.push(content.to_string()),
+                "SN" => citation.issn.push(content.to_string()),
+                "L1" | "L2" | "L3" | "L4" | "UR" | "LK" => {
+                    if citation.doi.is_none() && content.contains("doi.org") {
+                        citation.add_doi(content);
+                    }
+                    citation.urls.push(content.to_string());
+                }
+                _ => {
+                    if taken.contains(&tag) {
+                        self.options
+                            .warning_handler
+                            .warn(&format!("kept repeated {tag} field in extra_fields"));
+                    }
+                    citation
+                        .extra_fields
+                        .entry(tag.to_string())
+                        .or_default()
+                        .push(content.to_string());
+                }
+            }
+        }
+
+        if self.field_order {
+            citation.extra_fields.insert(
+                FIELD_ORDER_KEY.to_string(),
+                fields.iter().map(|(tag, _)| tag.to_string()).collect(),
+            );
+        }
+        Some(citation)
+    }
+
+    /// Builds a citation from buffered record lines, see [`Self::build_citation`]
+    fn build_record(&self, record: &[(&str, Cow<str>)]) -> Option<Citation> {
+        let fields: Vec<(&str, &str)> = record
+            .iter()
+            .map(|(tag, content)| (*tag, content.as_ref()))
+            .collect();
+        self.build_citation(&fields)
+    }
+
+    /// Builds the citations of `records`, in input order
+    fn build_records(&self, records: &[Vec<(&str, Cow<str>)>]) -> Vec<Citation> {
+        #[cfg(feature = "parallel")]
+        if self.parallel {
+            use rayon::prelude::*;
+
+            // Collecting a parallel iterator keeps the order of its items
+            return records
+                .par_iter()
+                .filter_map(|record| self.build_record(record))
+                .collect();
+        }
+        records
+            .iter()
+            .filter_map(|record| self.build_record(record))
+            .collect()
+    }
+
+    /// Whether a record has a title, without which it yields no citation
+    fn has_title(record: &[(&str, Cow<str>)]) -> bool {
+        record.iter().any(|(t, _)| *t == "TI" || *t == "T1")
     }
 }
 
 impl CitationParser for RisParser {
     fn parse(&self, input: &str) -> Result<Vec<Citation>> {
+        let input: &str = &clean_input(input);
         if input.trim().is_empty() {
             return Err(CitationError::InvalidFormat("Empty input".into()));
         }
 
-        let mut citations = Vec::new();
-        let mut current_citation = Citation::default();
-        current_citation.id = nanoid!();
-        current_citation.source = self.source.clone(); // Add source if provided
-        let mut start_page = String::new();
+        let limit = self.options.limit();
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let max_field_length = self.options.max_field_length();
+        // Records are collected first and converted together, which lets them
+        // be converted in parallel
+        let mut records = Vec::new();
+        let mut record: Vec<(&str, Cow<str>)> = Vec::new();
 
         for line in input.lines() {
             let line = line.trim();
-            
+
             // Skip empty lines without error
             if line.is_empty() {
                 continue;
@@ -139,99 +810,60 @@ impl CitationParser for RisParser {
                 continue;
             }
 
-            match Self::validate_line(line) {
-                Ok((tag, content)) => {
-                    match tag {
-                        "TY" => {
-                            if !current_citation.title.is_empty() {
-                                citations.push(current_citation);
-                                current_citation = Citation::default();
-                                current_citation.id = nanoid!();
-                            }
-                            current_citation.citation_type.push(content.to_string());
-                        }
-                        "TI" => current_citation.title = content.to_string(),
-                        "T1" => {
-                            if current_citation.title.is_empty() {
-                                current_citation.title = content.to_string()
-                            }
-                        }
-                        "AU" | "A1" | "A2" | "A3" | "A4" => {
-                            current_citation.authors.push(Self::parse_author(content))
-                        }
-                        "JF" | "T2" => current_citation.journal = Some(content.to_string()),
-                        "JA" | "J2" => current_citation.journal_abbr = Some(content.to_string()),
-                        "JO" => {
-                            if current_citation.journal_abbr.is_none() {
-                                current_citation.journal_abbr = Some(content.to_string())
-                            }
-                        }
-                        "PY" | "Y1" => {
-                            if let Ok(year) = content.split('/').next().unwrap_or("0").parse::<i32>() {
-                                current_citation.year = Some(year);
-                            }
-                        }
-                        "VL" => current_citation.volume = Some(content.to_string()),
-                        "IS" => current_citation.issue = Some(content.to_string()),
-                        "SP" => {
-                            start_page = content.to_string();
-                            // Set pages immediately for single page citations
-                            current_citation.pages = Some(format_page_numbers(&content));
-                        }
-                        "EP" => {
-                            let page_str = if !start_page.is_empty() {
-                                format!("{}-{}", start_page, content)
-                            } else {
-                                content.to_string()
-                            };
-                            current_citation.pages = Some(format_page_numbers(&page_str));
-                        }
-                        "DO" => current_citation.doi = format_doi(content.trim()),
-                        "ID" => current_citation.pmid = Some(content.to_string()),
-                        "AB" => current_citation.abstract_text = Some(content.to_string()),
-                        "N2" => {
-                            if current_citation.abstract_text.is_none() {
-                                current_citation.abstract_text = Some(content.to_string())
-                            }
-                        }
-                        "KW" => current_citation.keywords.push(content.to_string()),
-                        "SN" => current_citation.issn.push(content.to_string()),
-                        "L1" | "L2" | "L3" | "L4" | "UR" | "LK" => {
-                            if current_citation.doi.is_none() && content.contains("doi.org") {
-                                current_citation.doi = format_doi(content);
-                            }
-                            current_citation.urls.push(content.to_string());
-                        }
-                        "LA" => current_citation.language = Some(content.to_string()),
-                        "PB" => current_citation.publisher = Some(content.to_string()),
-                        "ER" => {
-                            if !current_citation.title.is_empty() {
-                                citations.push(current_citation);
-                                current_citation = Citation::default();
-                                current_citation.id = nanoid!();
-                            }
-                        }
-                        "C2" => {
-                            if content.contains("PMC") {
-                                current_citation.pmc_id = Some(content.to_string());
-                            }
-                        }
-                        _ => {
-                            current_citation
-                                .extra_fields
-                                .entry(tag.to_string())
-                                .or_default()
-                                .push(content.to_string());
+            // EndNote wraps long values, such as URLs, onto untagged lines
+            if self.dialect == RisDialect::EndNote && !Self::is_tag_line(line) {
+                if let Some((tag, content)) = record.last_mut() {
+                    let separator = if matches!(*tag, "UR" | "L1" | "L2" | "L3" | "L4" | "LK") {
+                        ""
+                    } else {
+                        " "
+                    };
+                    if push_capped(
+                        content.to_mut(),
+                        &format!("{separator}{line}"),
+                        max_field_length,
+                    ) {
+                        self.options.warning_handler.warn(&format!(
+                            "truncated {tag} field to {max_field_length} bytes"
+                        ));
+                    }
+                }
+                continue;
+            }
+
+            // Skip invalid lines without failing
+            let Ok((tag, content)) = Self::validate_line(line, self.strict_tags) else {
+                continue;
+            };
+
+            match tag {
+                "ER" => {
+                    if Self::has_title(&record) {
+                        records.push(mem::take(&mut record));
+                    }
+                    record.clear();
+                    if records.len() >= limit {
+                        break;
+                    }
+                }
+                "TY" => {
+                    // A new TY only starts a new record once the current one has a title
+                    if Self::has_title(&record) {
+                        records.push(mem::take(&mut record));
+                        if records.len() >= limit {
+                            break;
                         }
                     }
-                },
-                Err(_) => continue, // Skip invalid lines without failing
+                    record.push((tag, Cow::Borrowed(content)));
+                }
+                _ if !content.is_empty() => record.push((tag, Cow::Borrowed(content))),
+                _ => {}
             }
         }
-
-        if !current_citation.title.is_empty() {
-            citations.push(current_citation);
+        if records.len() < limit && Self::has_title(&record) {
+            records.push(record);
         }
+        let mut citations = self.build_records(&records);
 
         if citations.is_empty() {
             return Err(CitationError::InvalidFormat(
@@ -239,13 +871,419 @@ impl CitationParser for RisParser {
             ));
         }
 
+        self.options.finish(&mut citations);
         Ok(citations)
     }
+
+    /// Parses citations from a buffered reader one record at a time.
+    ///
+    /// Input is read line by line and each record is yielded as soon as its
+    /// `ER` line has been read, so citations arriving through a pipe, e.g. a
+    /// locked [`std::io::Stdin`], are processed as they arrive rather than
+    /// once the input ends. A record that cannot be parsed is yielded as an
+    /// error, and the records after it are still read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::{CitationParser, RisParser};
+    ///
+    /// let input = "TY  - JOUR\nTI  - First\nER  -\nTY  - JOUR\nTI  - Second\nER  -\n";
+    /// let parser = RisParser::new();
+    /// let mut citations = parser.parse_stream(input.as_bytes());
+    /// assert_eq!(citations.next().unwrap().unwrap().title, "First");
+    /// assert_eq!(citations.next().unwrap().unwrap().title, "Second");
+    /// assert!(citations.next().is_none());
+    /// ```
+    fn parse_stream<R: BufRead>(&self, reader: R) -> impl Iterator<Item = Result<Citation>> {
+        let limit = self.options.limit();
+        let mut lines = reader.lines();
+        let mut record = String::new();
+        let mut parsed = VecDeque::new();
+        let mut yielded = 0;
+        let mut seen_record = false;
+        let mut done = false;
+
+        iter::from_fn(move || loop {
+            if yielded >= limit {
+                return None;
+            }
+            if let Some(citation) = parsed.pop_front() {
+                yielded += 1;
+                return Some(Ok(citation));
+            }
+            if done {
+                return None;
+            }
+
+            match lines.next() {
+                Some(Ok(line)) => {
+                    let ends_record = matches!(
+                        Self::validate_line(line.trim(), self.strict_tags),
+                        Ok(("ER", _))
+                    );
+                    record.push_str(&line);
+                    record.push('\n');
+                    if !ends_record {
+                        continue;
+                    }
+                }
+                Some(Err(e)) => {
+                    done = true;
+                    return Some(Err(e.into()));
+                }
+                None => done = true,
+            }
+
+            // Blank lines between records are skipped unless the whole input is
+            // blank; a record that fails to parse is yielded as an error and
+            // reading goes on with the next one
+            let chunk = mem::take(&mut record);
+            if chunk.trim().is_empty() && (!done || seen_record) {
+                continue;
+            }
+            seen_record = true;
+            match self.parse(&chunk) {
+                Ok(citations) => parsed.extend(citations),
+                Err(e) => return Some(Err(e)),
+            }
+        })
+    }
+
+    fn format_name(&self) -> &'static str {
+        "RIS"
+    }
+
+    fn can_parse(&self, input: &str) -> bool {
+        let input: &str = &clean_input(input);
+        let trimmed = input.trim();
+        trimmed.starts_with("TY  -")
+            || trimmed.contains("\nTY  -")
+            || Self::has_lenient_tags(trimmed)
+    }
+}
+
+/// Writes citations in RIS format.
+///
+/// Each citation becomes one record ending with `ER`. Authors are written as
+/// `AU  - Family, Given` and page ranges are split into `SP` and `EP`, so the
+/// output reads back into the same fields with [`RisParser`]. The record type
+/// is the first `citation_type` that is a RIS type code, e.g. `JOUR`, or is
+/// otherwise derived from type names such as `"Journal Article"`, falling back
+/// to `GEN`. `ID` is the value kept under [`RIS_ID_KEY`] by
+/// [`RisParser::with_ris_id`], or otherwise the `pmid`, so either reads back
+/// as it was read. `extra_fields` are not written.
+///
+/// Citations read with [`RisParser::with_field_order`] are written with their
+/// lines in the order of the original record, under the tag the record used
+/// for each field, e.g. `T1` rather than `TI`. Their unrecognized tags, kept in
+/// `extra_fields`, are written too. Fields the record did not have come last.
+///
+/// # Arguments
+///
+/// * `citations` - The citations to write
+/// * `writer` - Destination for the RIS output
+///
+/// # Errors
+///
+/// Returns `CitationError::Io` if writing fails.
+///
+/// # Examples
+///
+/// ```
+/// use biblib::ris::write_ris;
+/// use biblib::Citation;
+///
+/// let citation = Citation {
+///     title: "Example Title".to_string(),
+///     year: Some(2023),
+///     ..Default::default()
+/// };
+///
+/// let mut output = Vec::new();
+/// write_ris([&citation], &mut output).unwrap();
+/// assert_eq!(
+///     String::from_utf8(output).unwrap(),
+///     "TY  - GEN\nTI  - Example Title\nPY  - 2023\nER  - \n\n"
+/// );
+/// ```
+pub fn write_ris<'a, W: Write>(
+    citations: impl IntoIterator<Item = &'a Citation>,
+    mut writer: W,
+) -> Result<()> {
+    for citation in citations {
+        write_record(citation, &mut writer)?;
+    }
+    Ok(())
+}
+
+/// Writes a single citation as a RIS record
+fn write_record<W: Write>(citation: &Citation, writer: &mut W) -> Result<()> {
+    let mut lines: Vec<(&str, String)> = Vec::new();
+    let mut line = |tag: &'static str, value: &str| {
+        let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !value.is_empty() {
+            lines.push((tag, value));
+        }
+    };
+
+    let record_type = ris_type(&citation.citation_type);
+    line("TI", &citation.title);
+    for author in &citation.authors {
+        let tag = match author.role {
+            Some(AuthorRole::Editor) => "A2",
+            Some(AuthorRole::Translator) => "A4",
+            _ => "AU",
+        };
+        match (author.family_name.trim(), author.given_name.trim()) {
+            (family, "") => line(tag, family),
+            (family, given) => line(tag, &format!("{}, {}", family, given)),
+        }
+    }
+    let is_conference = matches!(record_type, "CONF" | "CPAPER");
+    match (&citation.journal, &citation.conference_name) {
+        (_, Some(conference)) if is_conference => line("T2", conference),
+        (Some(journal), _) => line("JF", journal),
+        _ => {}
+    }
+    for (tag, value) in [
+        ("JA", &citation.journal_abbr),
+        ("T3", &citation.series),
+        ("VL", &citation.volume),
+        ("IS", &citation.issue),
+    ] {
+        if let Some(value) = value {
+            line(tag, value);
+        }
+    }
+    if let Some(year) = citation.year {
+        line("PY", &year.to_string());
+    }
+    if let Some(pages) = &citation.pages {
+        match pages.split_once('-') {
+            Some((start, end)) => {
+                line("SP", start);
+                line("EP", end);
+            }
+            None => line("SP", pages),
+        }
+    }
+    for serial_number in citation.issn.iter().chain(&citation.isbn) {
+        line("SN", serial_number);
+    }
+    for doi in citation.dois() {
+        line("DO", doi);
+    }
+    // Not the citation's `id`, which a merge may have taken from a record
+    // without an `ID`
+    let id = citation.extra_first(RIS_ID_KEY);
+    if let Some(id) = id.or(citation.pmid.as_deref()) {
+        line("ID", id);
+    }
+    for (tag, value) in [
+        ("C2", &citation.pmc_id),
+        ("AN", &citation.accession_number),
+        ("AB", &citation.abstract_text),
+        ("LA", &citation.language),
+        ("PB", &citation.publisher),
+        ("CY", &citation.place),
+        ("DB", &citation.source),
+    ] {
+        if let Some(value) = value {
+            line(tag, value);
+        }
+    }
+    for keyword in &citation.keywords {
+        line("KW", keyword);
+    }
+    for url in &citation.urls {
+        line("UR", url);
+    }
+    if let Some(order) = citation.extra(FIELD_ORDER_KEY) {
+        lines = in_field_order(lines, citation, order);
+    }
+
+    writeln!(writer, "TY  - {}", record_type)?;
+    for (tag, value) in lines {
+        writeln!(writer, "{}  - {}", tag, value)?;
+    }
+    writeln!(writer, "ER  - ")?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// Puts the lines of a record in the recorded tag order, see [`write_ris`]
+fn in_field_order<'a>(
+    lines: Vec<(&'a str, String)>,
+    citation: &'a Citation,
+    order: &'a [String],
+) -> Vec<(&'a str, String)> {
+    let mut used = vec![false; order.len()];
+    let mut claim = |tags: &[&str]| {
+        let position = (0..order.len()).find(|&k| !used[k] && tags.contains(&order[k].as_str()));
+        if let Some(k) = position {
+            used[k] = true;
+        }
+        position
+    };
+
+    let mut placed: Vec<(usize, &str, String)> = Vec::new();
+    for (tag, value) in lines {
+        let aliases = TAG_ALIASES
+            .iter()
+            .find(|(written, _)| *written == tag)
+            .map_or(&[][..], |(_, aliases)| *aliases);
+        // Under an alias only when the record did not use the written tag
+        let position = if order.iter().any(|recorded| recorded == tag) {
+            claim(&[tag])
+        } else {
+            claim(aliases)
+        };
+        match position {
+            Some(k) => placed.push((k, &order[k], value)),
+            None => placed.push((usize::MAX, tag, value)),
+        }
+    }
+
+    // Unrecognized tags, kept in extra_fields, go back where they were
+    let mut seen: Vec<&str> = Vec::new();
+    for tag in order {
+        if seen.contains(&tag.as_str()) {
+            continue;
+        }
+        seen.push(tag);
+        for value in citation.extra_fields.get(tag).into_iter().flatten() {
+            let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+            if value.is_empty() || placed.iter().any(|(_, t, v)| t == tag && *v == value) {
+                continue;
+            }
+            if let Some(k) = claim(&[tag]) {
+                placed.push((k, tag, value));
+            }
+        }
+    }
+
+    placed.sort_by_key(|(position, _, _)| *position);
+    placed
+        .into_iter()
+        .map(|(_, tag, value)| (tag, value))
+        .collect()
+}
+
+/// Picks the RIS type code for a citation's types
+fn ris_type(types: &[String]) -> &str {
+    if let Some(code) = types.iter().find(|t| {
+        (2..=6).contains(&t.len())
+            && t.chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+    }) {
+        return code;
+    }
+    for name in types.iter().map(|t| t.to_lowercase()) {
+        let code = if name.contains("chapter") {
+            "CHAP"
+        } else if name.contains("book") {
+            "BOOK"
+        } else if name.contains("conference") || name.contains("proceedings") {
+            "CONF"
+        } else if name.contains("thesis") || name.contains("dissertation") {
+            "THES"
+        } else if name.contains("journal") || name.contains("article") {
+            "JOUR"
+        } else {
+            continue;
+        };
+        return code;
+    }
+    "GEN"
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_parse_order() {
+        let input: String = (0..500)
+            .map(|i| {
+                format!(
+                    "TY  - JOUR\nID  - ref{i}\nTI  - Study {i}\nAU  - Author{}, A.\n\
+                     PY  - {}\nKW  - one; two\nER  -\n\n",
+                    i % 7,
+                    1990 + i % 30
+                )
+            })
+            .collect();
+        let parser = |parallel| RisParser::new().with_ris_id(true).with_parallel(parallel);
+        // Citations have no `PartialEq`, so compare their JSON
+        let json = |citations: Vec<Citation>| -> Vec<serde_json::Value> {
+            citations.iter().map(Citation::to_json_value).collect()
+        };
+
+        let sequential = json(parser(false).parse(&input).unwrap());
+        let parallel = json(parser(true).parse(&input).unwrap());
+        assert_eq!(sequential.len(), 500);
+        assert_eq!(sequential, parallel);
+
+        let sequential = json(parser(false).with_limit(120).parse(&input).unwrap());
+        let parallel = json(parser(true).with_limit(120).parse(&input).unwrap());
+        assert_eq!(parallel.len(), 120);
+        assert_eq!(sequential, parallel);
+    }
+
+    #[test]
+    fn test_parse_stream_incremental() {
+        use std::io::{BufReader, Read};
+
+        /// Fails once the records before it have been read
+        struct BrokenPipe;
+
+        impl Read for BrokenPipe {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::ErrorKind::BrokenPipe.into())
+            }
+        }
+
+        let input = "TY  - JOUR\nTI  - First\nER  -\n\nTY  - JOUR\nTI  - Second\n";
+        let reader = BufReader::new(input.as_bytes().chain(BrokenPipe));
+        let parser = RisParser::new();
+        let mut citations = parser.parse_stream(reader);
+        assert_eq!(citations.next().unwrap().unwrap().title, "First");
+        assert!(matches!(citations.next(), Some(Err(CitationError::Io(_)))));
+        assert!(citations.next().is_none());
+
+        let input =
+            "TY  - JOUR\nTI  - First\nER  -\nTY  - JOUR\nTI  - Second\nTY  - JOUR\nTI  - Third\n";
+        let titles: Vec<String> = RisParser::new()
+            .with_limit(2)
+            .parse_stream(input.as_bytes())
+            .map(|c| c.unwrap().title)
+            .collect();
+        assert_eq!(titles, vec!["First", "Second"]);
+
+        let mut results = parser.parse_stream("not RIS\n".as_bytes());
+        assert!(matches!(
+            results.next(),
+            Some(Err(CitationError::InvalidFormat(_)))
+        ));
+        assert!(results.next().is_none());
+
+        let input = "TY  - JOUR\nTI  - First\nER  -\n\nnot RIS\nER  -\n\nTY  - JOUR\nTI  - Third\nER  -\n\n";
+        let mut results = parser.parse_stream(input.as_bytes());
+        assert_eq!(results.next().unwrap().unwrap().title, "First");
+        assert!(matches!(
+            results.next(),
+            Some(Err(CitationError::InvalidFormat(_)))
+        ));
+        assert_eq!(results.next().unwrap().unwrap().title, "Third");
+        assert!(results.next().is_none());
+
+        let mut results = parser.parse_stream("\n\n".as_bytes());
+        assert!(results.next().unwrap().is_err());
+        assert!(results.next().is_none());
+    }
 
     #[test]
     fn test_parse_simple_ris() {
@@ -307,7 +1345,7 @@ ER  -"#;
 
     #[test]
     fn test_parse_gs() {
-       let input = r#"TY  - JOUR
+        let input = r#"TY  - JOUR
 T1  - Albendazole therapy in children with focal seizures and single small enhancing computerized tomographic lesions: a randomized, placebo-controlled, double blind trial
 A1  - Baranwal, Arun K
 A1  - Singhi, Pratibha D
@@ -338,10 +1376,626 @@ PB  - BMJ Publishing Group
 ER  - 
 
 "#;
-        
+
         let parser = RisParser::new();
-        let citations = parser.parse(&input).unwrap();
+        let citations = parser.parse(input).unwrap();
         assert_eq!(citations.len(), 2, "Expected 2 citations in test.ris");
         assert_eq!(citations[0].year, Some(1998));
     }
+
+    #[test]
+    fn test_source_on_every_record() {
+        let input = r#"TY  - JOUR
+TI  - First
+ER  -
+
+TY  - JOUR
+TI  - Second
+ER  -
+
+TY  - JOUR
+TI  - Third
+"#;
+        let parser = RisParser::new().with_source("Scopus");
+        let result = parser.parse(input).unwrap();
+        assert_eq!(result.len(), 3);
+        assert!(result.iter().all(|c| c.source.as_deref() == Some("Scopus")));
+    }
+
+    #[test]
+    fn test_abstract_sources() {
+        let parser = RisParser::new();
+
+        let ab_only = "TY  - JOUR\nTI  - Title\nAB  - From AB\nER  -";
+        let result = parser.parse(ab_only).unwrap();
+        assert_eq!(result[0].abstract_text.as_deref(), Some("From AB"));
+        assert_eq!(result[0].extra("N2"), None);
+
+        let n2_only = "TY  - JOUR\nTI  - Title\nN2  - From N2\nER  -";
+        let result = parser.parse(n2_only).unwrap();
+        assert_eq!(result[0].abstract_text.as_deref(), Some("From N2"));
+
+        // AB wins regardless of order, N2 is kept
+        let both = "TY  - JOUR\nTI  - Title\nN2  - From N2\nAB  - From AB\nER  -";
+        let result = parser.parse(both).unwrap();
+        assert_eq!(result[0].abstract_text.as_deref(), Some("From AB"));
+        assert_eq!(result[0].extra_first("N2"), Some("From N2"));
+    }
+
+    #[test]
+    fn test_notes() {
+        let input = "TY  - JOUR\nTI  - Title\nN1  - First note\nN1  - Second note\nER  -";
+        let result = RisParser::new().parse(input).unwrap();
+        assert_eq!(
+            result[0].extra("N1"),
+            Some(&["First note".to_string(), "Second note".to_string()][..])
+        );
+    }
+
+    #[test]
+    fn test_repeated_single_valued_tags() {
+        let input = r#"TY  - JOUR
+TI  - First Title
+TI  - Second Title
+T1  - Alternative Title
+PY  - n.d.
+PY  - 2020
+PY  - 2021
+DO  - 10.1000/first
+DO  - 10.1000/second
+AU  - Smith, John
+AU  - Doe, Jane
+ER  -"#;
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&warnings);
+        let result = RisParser::new()
+            .with_warning_handler(move |message| sink.lock().unwrap().push(message.to_string()))
+            .parse(input)
+            .unwrap();
+        let citation = &result[0];
+
+        // Single-valued fields keep the first usable value
+        assert_eq!(citation.title, "First Title");
+        assert_eq!(citation.year, Some(2020));
+        assert_eq!(citation.doi.as_deref(), Some("10.1000/first"));
+
+        // Remaining values are kept in extra_fields
+        assert_eq!(citation.extra_first("TI"), Some("Second Title"));
+        assert_eq!(citation.extra_first("T1"), Some("Alternative Title"));
+        assert_eq!(
+            citation.extra("PY"),
+            Some(&["n.d.".to_string(), "2021".to_string()][..])
+        );
+        assert_eq!(
+            *warnings.lock().unwrap(),
+            vec![
+                "kept repeated TI field in extra_fields",
+                "kept repeated T1 field in extra_fields",
+                "kept repeated PY field in extra_fields",
+                "kept repeated PY field in extra_fields",
+            ]
+        );
+
+        // Multi-valued fields keep every value
+        assert_eq!(citation.authors.len(), 2);
+        assert_eq!(citation.all_dois, vec!["10.1000/first", "10.1000/second"]);
+    }
+
+    #[test]
+    fn test_pages_do_not_leak_between_records() {
+        let input = r#"TY  - JOUR
+TI  - First
+SP  - 100
+ER  -
+
+TY  - JOUR
+TI  - Second
+EP  - 20
+ER  -"#;
+        let result = RisParser::new().parse(input).unwrap();
+        assert_eq!(result[0].pages.as_deref(), Some("100"));
+        assert_eq!(result[1].pages.as_deref(), Some("20"));
+    }
+
+    #[test]
+    fn test_with_limit() {
+        let input = "TY  - JOUR\nTI  - First\nER  -\n\nTY  - JOUR\nTI  - Second\nER  -\n\nTY  - JOUR\nTI  - Third\n";
+        let result = RisParser::new().with_limit(2).parse(input).unwrap();
+        let titles: Vec<_> = result.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, vec!["First", "Second"]);
+
+        assert_eq!(
+            RisParser::new().with_limit(5).parse(input).unwrap().len(),
+            3
+        );
+        assert!(RisParser::new()
+            .with_limit(0)
+            .parse(input)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_endnote_dialect_fixture() {
+        let input = include_str!("../tests/fixtures/endnote.ris");
+        let parser = RisParser::endnote_dialect();
+        let citations = parser.parse(input).unwrap();
+        assert_eq!(citations.len(), 2);
+
+        let article = &citations[0];
+        assert_eq!(article.citation_type, vec!["JOUR"]);
+        assert_eq!(article.authors[0].family_name, "Nguyen");
+        assert_eq!(article.issue.as_deref(), Some("3"));
+        assert_eq!(article.doi.as_deref(), Some("10.1093/ageing/afz012"));
+        assert_eq!(article.pages.as_deref(), Some("412-419"));
+        assert_eq!(
+            article.urls,
+            vec!["https://academic.oup.com/ageing/article-abstract/48/3/412/5369852?redirectedFrom=fulltext"]
+        );
+        assert!(!article.extra_fields.contains_key("fu"));
+
+        let paper = &citations[1];
+        assert_eq!(paper.citation_type, vec!["CONF", "Conference Paper"]);
+        assert_eq!(paper.doi, None);
+        assert_eq!(
+            paper.abstract_text.as_deref(),
+            Some("Discharge planning led by ward nurses was associated with shorter stays.")
+        );
+    }
+
+    #[test]
+    fn test_standard_dialect_ignores_endnote_tags() {
+        let input = "TY  - JOUR\nTI  - Title\nM1  - 3\nM3  - 10.1000/test\nER  - \n";
+        let parser = RisParser::new();
+        let citations = parser.parse(input).unwrap();
+        assert_eq!(citations[0].issue, None);
+        assert_eq!(citations[0].doi, None);
+        assert_eq!(citations[0].extra_first("M1"), Some("3"));
+    }
+
+    #[test]
+    fn test_keyword_layouts() {
+        let single_line =
+            "TY  - JOUR\nTI  - Title\nKW  - asthma; children ;inhaled steroids\nER  -";
+        let repeated =
+            "TY  - JOUR\nTI  - Title\nKW  - asthma, childhood\nKW  - inhaled steroids\nER  -";
+
+        let parser = RisParser::new();
+        let citations = parser.parse(single_line).unwrap();
+        assert_eq!(
+            citations[0].keywords,
+            vec!["asthma", "children", "inhaled steroids"]
+        );
+        let citations = parser.parse(repeated).unwrap();
+        assert_eq!(
+            citations[0].keywords,
+            vec!["asthma, childhood", "inhaled steroids"]
+        );
+
+        let parser = RisParser::new().with_keyword_delimiter(None);
+        let citations = parser.parse(single_line).unwrap();
+        assert_eq!(
+            citations[0].keywords,
+            vec!["asthma; children ;inhaled steroids"]
+        );
+
+        let parser = RisParser::new().with_keyword_delimiter(Some(','));
+        let citations = parser.parse(repeated).unwrap();
+        assert_eq!(
+            citations[0].keywords,
+            vec!["asthma", "childhood", "inhaled steroids"]
+        );
+    }
+
+    #[test]
+    fn test_conference_fields() {
+        let input = r#"TY  - CONF
+TI  - Fast Deduplication of Citations
+T2  - Proceedings of the Conference on Digital Libraries
+C1  - Oslo, Norway
+PY  - 2021
+ER  -
+
+TY  - JOUR
+TI  - Journal Article
+T2  - Journal of Testing
+CY  - London
+C1  - Not a place
+ER  -"#;
+
+        let parser = RisParser::new();
+        let citations = parser.parse(input).unwrap();
+        assert_eq!(citations[0].place.as_deref(), Some("Oslo, Norway"));
+        assert_eq!(
+            citations[0].conference_name.as_deref(),
+            Some("Proceedings of the Conference on Digital Libraries")
+        );
+        assert_eq!(citations[1].place.as_deref(), Some("London"));
+        assert_eq!(citations[1].conference_name, None);
+        assert_eq!(citations[1].extra_first("C1"), Some("Not a place"));
+    }
+
+    #[test]
+    fn test_year_from_messy_dates() {
+        let input = "TY  - JOUR\nTI  - First\nPY  - Spring 2020\nER  -\n\nTY  - JOUR\nTI  - Second\nDA  - 2019/03/15\nER  -";
+        let parser = RisParser::new();
+        let citations = parser.parse(input).unwrap();
+        assert_eq!(citations[0].year, Some(2020));
+        assert_eq!(citations[1].year, Some(2019));
+        assert_eq!(citations[1].extra_first("DA"), Some("2019/03/15"));
+    }
+
+    #[test]
+    fn test_parse_multiple_dois() {
+        let input = "TY  - JOUR\nTI  - Article with data\nDO  - 10.5061/dryad.xyz\nDO  - 10.1000/ARTICLE\nER  - \n";
+        let citations = RisParser::new().parse(input).unwrap();
+        let citation = &citations[0];
+        assert_eq!(citation.doi.as_deref(), Some("10.1000/article"));
+        assert_eq!(
+            citation.all_dois,
+            vec!["10.5061/dryad.xyz", "10.1000/article"]
+        );
+        assert!(citation.extra("DO").is_none());
+    }
+
+    #[test]
+    fn test_parse_space_separated_dois() {
+        let input = "TY  - JOUR\nTI  - Two DOIs\nDO  - 10.5061/dryad.xyz 10.1000/ARTICLE\nER  - \n";
+        let citations = RisParser::new().parse(input).unwrap();
+        let citation = &citations[0];
+        assert_eq!(citation.doi.as_deref(), Some("10.1000/article"));
+        assert_eq!(
+            citation.all_dois,
+            vec!["10.5061/dryad.xyz", "10.1000/article"]
+        );
+
+        let input = "TY  - JOUR\nTI  - Two DOIs\nDO  - 10.1000/one; 10.1000/two\nER  - \n";
+        let citations = RisParser::new().parse(input).unwrap();
+        assert_eq!(citations[0].doi.as_deref(), Some("10.1000/one"));
+        assert_eq!(citations[0].all_dois, vec!["10.1000/one", "10.1000/two"]);
+    }
+
+    #[test]
+    fn test_malformed_tag_separators() {
+        let input = include_str!("../tests/fixtures/malformed_separator.ris");
+
+        let citations = RisParser::new().parse(input).unwrap();
+        assert_eq!(citations.len(), 2);
+        let citation = &citations[0];
+        assert_eq!(citation.citation_type, vec!["JOUR"]);
+        assert_eq!(citation.title, "Sleep duration and cardiovascular risk");
+        assert_eq!(citation.authors.len(), 2);
+        assert_eq!(citation.authors[1].family_name, "Doe");
+        assert_eq!(citation.year, Some(2019));
+        assert_eq!(
+            citation.journal_abbr.as_deref(),
+            Some("Journal of Sleep Research")
+        );
+        assert_eq!(citation.volume.as_deref(), Some("28"));
+        assert_eq!(citation.pages.as_deref(), Some("100-110"));
+        assert_eq!(citation.doi.as_deref(), Some("10.1000/jsr.2019.1"));
+        assert_eq!(citations[1].title, "Second record");
+
+        // Strict mode only reads canonical tag lines
+        assert!(RisParser::new()
+            .with_strict_tags(true)
+            .parse(input)
+            .is_err());
+    }
+
+    #[test]
+    fn test_scopus_cited_by_note() {
+        let input = "TY  - JOUR\nTI  - Scopus Record\nN1  - Cited By: 12; Export Date: 1 March 2024\nER  - \nTY  - JOUR\nTI  - No Count\nN1  - Just a note\nER  - \n";
+        let citations = RisParser::new().parse(input).unwrap();
+        assert_eq!(citations[0].cited_by_count, Some(12));
+        assert!(citations[0].extra_first("N1").is_some());
+        assert_eq!(citations[1].cited_by_count, None);
+    }
+
+    #[test]
+    fn test_source_from_database_tag() {
+        let input = "TY  - JOUR\nTI  - Aggregated Record\nDB  - MEDLINE\nDP  - Ovid Technologies\nER  - \nTY  - JOUR\nTI  - Plain Record\nER  - \n";
+
+        let citations = RisParser::new().parse(input).unwrap();
+        assert_eq!(citations[0].source.as_deref(), Some("MEDLINE"));
+        assert_eq!(citations[0].extra_first("DB"), Some("MEDLINE"));
+        assert_eq!(citations[0].extra_first("DP"), Some("Ovid Technologies"));
+        assert_eq!(citations[1].source, None);
+
+        // An explicit source takes precedence
+        let citations = RisParser::new().with_source("Embase").parse(input).unwrap();
+        assert_eq!(citations[0].source.as_deref(), Some("Embase"));
+    }
+
+    #[test]
+    fn test_accession_number() {
+        let input = "TY  - JOUR\nTI  - Record with accession\nAN  - 631234567\nAN  - 999\nER  - \n";
+        let citations = RisParser::new().parse(input).unwrap();
+        assert_eq!(citations[0].accession_number.as_deref(), Some("631234567"));
+        assert_eq!(citations[0].extra_first("AN"), Some("999"));
+    }
+
+    #[test]
+    fn test_doi_prefix_and_url_forms() {
+        for value in [
+            "10.1000/ABC.123",
+            "doi:10.1000/abc.123",
+            "DOI: 10.1000/abc.123",
+            "https://doi.org/10.1000/abc.123",
+            "http://dx.doi.org/10.1000/abc.123",
+        ] {
+            let input = format!("TY  - JOUR\nTI  - DOI Forms\nDO  - {}\nER  - \n", value);
+            let citations = RisParser::new().parse(&input).unwrap();
+            assert_eq!(
+                citations[0].doi.as_deref(),
+                Some("10.1000/abc.123"),
+                "DO value {:?}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_max_field_length() {
+        let input = "TY  - JOUR\nTI  - Café society\nAU  - Smith, John\nKW  - nutrition\nER  - \n";
+        let citations = RisParser::new()
+            .with_max_field_length(4)
+            .parse(input)
+            .unwrap();
+        // "Café" ends inside the two-byte "é", so the cut falls before it
+        assert_eq!(citations[0].title, "Caf");
+        assert_eq!(citations[0].authors[0].family_name, "Smit");
+        assert_eq!(citations[0].keywords, vec!["nutr"]);
+
+        let citations = RisParser::new().parse(input).unwrap();
+        assert_eq!(citations[0].title, "Café society");
+    }
+
+    #[test]
+    fn test_max_field_length_across_lines() {
+        let input = format!(
+            "TY  - JOUR\nTI  - Title\nN2  - Start\n{}ER  - \n",
+            "more text\n".repeat(100)
+        );
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&warnings);
+        let citations = RisParser::new()
+            .with_dialect(RisDialect::EndNote)
+            .with_max_field_length(20)
+            .with_warning_handler(move |message| sink.lock().unwrap().push(message.to_string()))
+            .parse(&input)
+            .unwrap();
+        assert_eq!(
+            citations[0].abstract_text.as_deref(),
+            Some("Start more text more")
+        );
+        assert_eq!(
+            *warnings.lock().unwrap(),
+            vec!["truncated N2 field to 20 bytes"]
+        );
+    }
+
+    #[test]
+    fn test_write_ris_round_trip() {
+        let input = "TY  - JOUR\nTI  - Round Trip\nAU  - Smith, John\nAU  - Doe, Jane\nJF  - Journal of Tests\nT3  - Test Series\nPY  - 2021\nVL  - 4\nSP  - 10\nEP  - 20\nSN  - 1234-5678\nDO  - 10.1000/one\nDO  - 10.1000/two\nAN  - 12345\nKW  - testing\nUR  - https://example.com\nER  - \n";
+        let citations = RisParser::new().parse(input).unwrap();
+
+        let mut output = Vec::new();
+        write_ris(&citations, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output, citations[0].to_ris_string());
+
+        let reparsed = RisParser::new().parse(&output).unwrap();
+        let (original, copy) = (&citations[0], &reparsed[0]);
+        assert_eq!(copy.title, original.title);
+        assert_eq!(copy.authors, original.authors);
+        assert_eq!(copy.journal, original.journal);
+        assert_eq!(copy.series.as_deref(), Some("Test Series"));
+        assert_eq!(copy.year, original.year);
+        assert_eq!(copy.pages.as_deref(), Some("10-20"));
+        assert_eq!(copy.issn, original.issn);
+        assert_eq!(copy.all_dois, original.all_dois);
+        assert_eq!(copy.accession_number, original.accession_number);
+        assert_eq!(copy.keywords, original.keywords);
+        assert_eq!(copy.urls, original.urls);
+    }
+
+    #[test]
+    fn test_records_without_blank_lines() {
+        let input = include_str!("../tests/fixtures/no_blank_lines.ris");
+        let citations = RisParser::new().parse(input).unwrap();
+        let titles: Vec<_> = citations.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            [
+                "First Record",
+                "Second Record",
+                "Third Record Without End",
+                "Fourth Record"
+            ]
+        );
+        assert_eq!(citations[1].citation_type, vec!["BOOK"]);
+        assert_eq!(citations[2].year, Some(2018));
+    }
+
+    #[test]
+    fn test_serial_numbers() {
+        let input = "TY  - BOOK\nTI  - A Book\nSN  - 978-0-306-40615-7\nSN  - 0-306-40615-2\nSN  - 1234-5678\nSN  - 978-0-306-40615-8\nER  - \n";
+        let citations = RisParser::new().parse(input).unwrap();
+        assert_eq!(
+            citations[0].isbn,
+            vec!["978-0-306-40615-7", "0-306-40615-2", "978-0-306-40615-8"]
+        );
+        // Values with a wrong ISBN check digit are still ISBNs, but not valid ones
+        assert_eq!(citations[0].issn, vec!["1234-5678"]);
+        assert_eq!(citations[0].valid_isbns(), ["9780306406157", "0306406152"]);
+    }
+
+    #[test]
+    fn test_combined_volume_and_issue() {
+        let input = "TY  - JOUR\nTI  - Separate\nVL  - 12\nIS  - 3\nER  - \n\n\
+                     TY  - JOUR\nTI  - Combined\nVL  - 12(3)\nER  - \n\n\
+                     TY  - JOUR\nTI  - Both\nVL  - 12 (Suppl 1)\nIS  - S1\nER  - \n\n\
+                     TY  - JOUR\nTI  - Unparsed\nVL  - 12(3\nER  - \n";
+        let citations = RisParser::new().parse(input).unwrap();
+        let fields: Vec<_> = citations
+            .iter()
+            .map(|c| (c.volume.as_deref(), c.issue.as_deref()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                (Some("12"), Some("3")),
+                (Some("12"), Some("3")),
+                (Some("12"), Some("S1")),
+                (Some("12(3"), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cr_only_line_endings() {
+        let input = include_str!("../tests/fixtures/cr_only.ris");
+        assert!(!input.contains('\n'));
+        let parser = RisParser::new();
+        assert!(parser.can_parse(input));
+
+        let citations = parser.parse(input).unwrap();
+        assert_eq!(citations.len(), 2);
+        let first = &citations[0];
+        assert_eq!(first.title, "Classic Mac Export");
+        assert_eq!(first.authors.len(), 2);
+        assert_eq!(first.journal.as_deref(), Some("Journal of Old Systems"));
+        assert_eq!(first.year, Some(1998));
+        assert_eq!(first.pages.as_deref(), Some("1-9"));
+        assert_eq!(first.keywords, vec!["legacy"]);
+        assert!(first.extra_fields.is_empty());
+        assert_eq!(citations[1].title, "Second Record");
+        assert_eq!(citations[1].publisher.as_deref(), Some("Old Press"));
+    }
+
+    #[test]
+    fn test_author_roles() {
+        let input = "TY  - BOOK\nTI  - Edited Book\nAU  - Smith, John\nA2  - Doe, Jane\nED  - Roe, Rick\nA4  - Poe, Pat\nER  - \n";
+        let citations = RisParser::new().parse(input).unwrap();
+        let roles: Vec<_> = citations[0].authors.iter().map(|a| a.role).collect();
+        assert_eq!(
+            roles,
+            vec![
+                None,
+                Some(AuthorRole::Editor),
+                Some(AuthorRole::Editor),
+                Some(AuthorRole::Translator),
+            ]
+        );
+        assert_eq!(citations[0].editors().count(), 2);
+
+        let output = citations[0].to_ris_string();
+        assert!(output
+            .contains("AU  - Smith, John\nA2  - Doe, Jane\nA2  - Roe, Rick\nA4  - Poe, Pat\n"));
+        let reparsed = RisParser::new().parse(&output).unwrap();
+        assert_eq!(reparsed[0].authors, citations[0].authors);
+    }
+
+    #[test]
+    fn test_field_order_round_trip() {
+        let input = "TY  - JOUR\nDB  - MEDLINE\nT1  - Ordered Record\nA1  - Smith, John\nY1  - 2020\nA1  - Doe, Jane\nJO  - J Tests\nSP  - 101\nEP  - 109\nKW  - one\nN1  - A note\nKW  - two\nM3  - Article\nN2  - An abstract.\nER  - \n\nTY  - BOOK\nTI  - Plain Record\nER  - \n\n";
+        let parser = RisParser::new().with_field_order(true);
+        let citations = parser.parse(input).unwrap();
+        assert_eq!(
+            citations[1].extra(FIELD_ORDER_KEY),
+            Some(&["TY".to_string(), "TI".to_string()][..])
+        );
+
+        let mut output = Vec::new();
+        write_ris(&citations, &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), input);
+
+        // Fields added after parsing are written after the recorded ones
+        let mut citation = citations[0].clone();
+        citation.volume = Some("4".to_string());
+        assert!(citation
+            .to_ris_string()
+            .ends_with("N2  - An abstract.\nVL  - 4\nER  - \n\n"));
+
+        // Without the option, the usual order is used
+        let plain = RisParser::new().parse(input).unwrap();
+        assert!(plain[0].extra(FIELD_ORDER_KEY).is_none());
+        assert!(plain[0]
+            .to_ris_string()
+            .starts_with("TY  - JOUR\nTI  - Ordered Record\nAU  - Smith, John\n"));
+    }
+
+    #[test]
+    fn test_ris_id_round_trip() {
+        let input = "TY  - JOUR\nTI  - First Record\nID  - 4521\nER  - \n\nTY  - JOUR\nTI  - Second Record\nER  - \n\n";
+        let parser = RisParser::new().with_ris_id(true);
+        let citations = parser.parse(input).unwrap();
+        assert_eq!(citations[0].id, "4521");
+        assert_eq!(citations[0].pmid, None);
+        assert!(!citations[1].id.is_empty());
+
+        // Generated ids are not written
+        let mut output = Vec::new();
+        write_ris(&citations, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output, input);
+        let reparsed = parser.parse(&output).unwrap();
+        assert_eq!(reparsed[0].id, "4521");
+
+        // Without the option the ID is kept as the PMID, and written back
+        let citations = RisParser::new().parse(input).unwrap();
+        assert_ne!(citations[0].id, "4521");
+        assert_eq!(citations[0].pmid.as_deref(), Some("4521"));
+        assert_eq!(
+            citations[0].to_ris_string(),
+            "TY  - JOUR\nTI  - First Record\nID  - 4521\nER  - \n\n"
+        );
+
+        let ordered = RisParser::new()
+            .with_field_order(true)
+            .with_ris_id(true)
+            .parse(input)
+            .unwrap();
+        let mut output = Vec::new();
+        write_ris(&ordered, &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), input);
+
+        // A merge keeps the unique's generated id, but the duplicate's ID is
+        // still written
+        let input =
+            "TY  - JOUR\nTI  - Title\nER  - \n\nTY  - JOUR\nTI  - Title\nID  - smith2020\nER  - \n";
+        let citations = parser.parse(input).unwrap();
+        let mut unique = citations[0].clone();
+        unique.merge_from(&citations[1], crate::MergePolicy::FillEmpty);
+        assert_ne!(unique.id, "smith2020");
+        assert!(unique.to_ris_string().contains("ID  - smith2020\n"));
+    }
+
+    #[test]
+    fn test_non_ascii_serial_number() {
+        let input = "TY  - BOOK\nTI  - Title\nSN  - ISBN \u{212A}\nER  - \n";
+        let citations = RisParser::new().parse(input).unwrap();
+        assert!(citations[0].isbn.is_empty());
+        assert_eq!(citations[0].issn, vec!["ISBN \u{212A}"]);
+    }
+
+    #[test]
+    fn test_whitespace_normalization() {
+        let input = "TY  - JOUR\nTI  - Effects of\u{a0}aspirin  on\u{200b} platelets\nAU  - Smith,\u{a0}John\nJF  - Journal\u{a0}\u{a0}of Tests\nER  - \n";
+
+        let citations = RisParser::new().parse(input).unwrap();
+        assert_eq!(
+            citations[0].title,
+            "Effects of\u{a0}aspirin  on\u{200b} platelets"
+        );
+
+        let citations = RisParser::new()
+            .with_whitespace_normalization(true)
+            .parse(input)
+            .unwrap();
+        assert_eq!(citations[0].title, "Effects of aspirin on platelets");
+        assert_eq!(citations[0].authors[0].given_name, "John");
+        assert_eq!(citations[0].journal.as_deref(), Some("Journal of Tests"));
+    }
 }
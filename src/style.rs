@@ -0,0 +1,544 @@
+//! Formatted reference strings for display.
+//!
+//! A lightweight formatter for [`Citation::format`], aimed at journal
+//! articles. It is not a CSL engine: only the authors, title, journal, year,
+//! volume, issue, pages and DOI are used, and missing fields are left out
+//! together with their punctuation.
+//!
+//! # Example
+//!
+//! ```
+//! use biblib::{Author, Citation, CiteStyle};
+//!
+//! let citation = Citation {
+//!     title: "Sleep and memory".to_string(),
+//!     authors: vec![
+//!         Author {
+//!             family_name: "Smith".to_string(),
+//!             given_name: "John".to_string(),
+//!             ..Default::default()
+//!         },
+//!         Author {
+//!             family_name: "Doe".to_string(),
+//!             given_name: "Jane Anne".to_string(),
+//!             ..Default::default()
+//!         },
+//!     ],
+//!     journal: Some("Journal of Sleep Research".to_string()),
+//!     journal_abbr: Some("J Sleep Res".to_string()),
+//!     year: Some(2020),
+//!     volume: Some("12".to_string()),
+//!     issue: Some("3".to_string()),
+//!     pages: Some("100-110".to_string()),
+//!     doi: Some("10.1000/sleep".to_string()),
+//!     ..Default::default()
+//! };
+//!
+//! assert_eq!(
+//!     citation.format(CiteStyle::Vancouver),
+//!     "Smith J, Doe JA. Sleep and memory. J Sleep Res. 2020;12(3):100-110. doi:10.1000/sleep"
+//! );
+//! assert_eq!(
+//!     citation.format(CiteStyle::Apa),
+//!     "Smith, J., & Doe, J. A. (2020). Sleep and memory. Journal of Sleep Research, 12(3), 100–110. https://doi.org/10.1000/sleep"
+//! );
+//! ```
+//!
+//! [`citations_to_html`] renders a whole reference list for web pages.
+
+use crate::{Author, Citation};
+
+/// Citation style used by [`Citation::format`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CiteStyle {
+    /// Vancouver (ICMJE/NLM) style:
+    /// `Smith J, Doe JA. Title. J Abbr. 2020;12(3):100-10. doi:10.1000/x`
+    ///
+    /// Up to six authors are listed, followed by `et al.` when there are more.
+    /// The journal abbreviation is preferred over the full name.
+    #[default]
+    Vancouver,
+    /// APA (7th edition) style:
+    /// `Smith, J., & Doe, J. A. (2020). Title. Journal, 12(3), 100–110. https://doi.org/10.1000/x`
+    ///
+    /// Up to twenty authors are listed; with more, the first nineteen are
+    /// followed by an ellipsis and the last author. The full journal name is
+    /// preferred over the abbreviation, and citations without a year are dated
+    /// `(n.d.)`.
+    Apa,
+}
+
+/// Formats a citation in the given style
+pub(crate) fn format_citation(citation: &Citation, style: CiteStyle) -> String {
+    style_parts(citation, style)
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Formats citations as an HTML ordered list of references.
+///
+/// Each citation becomes an `<li>` formatted as by [`Citation::format`], with
+/// all text escaped and the DOI linked to `https://doi.org/`. See
+/// [`citations_to_html_with_coins`] to also embed COinS metadata.
+///
+/// # Arguments
+///
+/// * `citations` - The citations to list, in order
+/// * `style` - The citation style to use
+///
+/// # Examples
+///
+/// ```
+/// use biblib::style::citations_to_html;
+/// use biblib::{Citation, CiteStyle};
+///
+/// let citation = Citation {
+///     title: "Sleep & memory".to_string(),
+///     year: Some(2020),
+///     doi: Some("10.1000/sleep".to_string()),
+///     ..Default::default()
+/// };
+///
+/// assert_eq!(
+///     citations_to_html(&[citation], CiteStyle::Vancouver),
+///     "<ol>\n<li>Sleep &amp; memory. 2020. \
+///      <a href=\"https://doi.org/10.1000/sleep\">doi:10.1000/sleep</a></li>\n</ol>\n"
+/// );
+/// ```
+#[must_use]
+pub fn citations_to_html(citations: &[Citation], style: CiteStyle) -> String {
+    html_list(citations, style, false)
+}
+
+/// Formats citations as an HTML ordered list of references with COinS.
+///
+/// Like [`citations_to_html`], but each `<li>` also holds an empty
+/// `<span class="Z3988">` carrying the citation as an OpenURL ContextObject,
+/// which reference managers such as Zotero detect on the page.
+///
+/// # Arguments
+///
+/// * `citations` - The citations to list, in order
+/// * `style` - The citation style to use
+///
+/// # Examples
+///
+/// ```
+/// use biblib::style::citations_to_html_with_coins;
+/// use biblib::{Citation, CiteStyle};
+///
+/// let citation = Citation {
+///     title: "Sleep and memory".to_string(),
+///     ..Default::default()
+/// };
+///
+/// let html = citations_to_html_with_coins(&[citation], CiteStyle::Apa);
+/// assert!(html.contains("<span class=\"Z3988\" title=\"ctx_ver=Z39.88-2004&amp;"));
+/// assert!(html.contains("rft.atitle=Sleep%20and%20memory"));
+/// ```
+#[must_use]
+pub fn citations_to_html_with_coins(citations: &[Citation], style: CiteStyle) -> String {
+    html_list(citations, style, true)
+}
+
+fn html_list(citations: &[Citation], style: CiteStyle, coins: bool) -> String {
+    let mut html = String::from("<ol>\n");
+    for citation in citations {
+        let mut parts = style_parts(citation, style);
+        // The DOI is always the last part
+        let doi_text = parts.pop().unwrap_or_default();
+        let mut item = parts
+            .iter()
+            .filter(|part| !part.is_empty())
+            .map(|part| escape_html(part))
+            .collect::<Vec<_>>()
+            .join(" ");
+        if let Some(doi) = trimmed(&citation.doi) {
+            let link = format!(
+                "<a href=\"https://doi.org/{}\">{}</a>",
+                escape_html(&url_encode(doi, "/")),
+                escape_html(&doi_text)
+            );
+            item = join_nonempty(&[&item, &link], " ");
+        }
+        if coins {
+            item.push_str(&format!(
+                "<span class=\"Z3988\" title=\"{}\"></span>",
+                escape_html(&coins_context(citation))
+            ));
+        }
+        html.push_str(&format!("<li>{}</li>\n", item));
+    }
+    html.push_str("</ol>\n");
+    html
+}
+
+/// Builds the OpenURL ContextObject of a COinS span, as a journal article
+fn coins_context(citation: &Citation) -> String {
+    let mut fields = vec![
+        ("ctx_ver", "Z39.88-2004".to_string()),
+        ("rft_val_fmt", "info:ofi/fmt:kev:mtx:journal".to_string()),
+        ("rft.genre", "article".to_string()),
+        ("rft.atitle", citation.title.trim().to_string()),
+    ];
+    let optional = [
+        ("rft.jtitle", trimmed(&citation.journal)),
+        ("rft.stitle", trimmed(&citation.journal_abbr)),
+        ("rft.volume", trimmed(&citation.volume)),
+        ("rft.issue", trimmed(&citation.issue)),
+        ("rft.pages", trimmed(&citation.pages)),
+    ];
+    fields.extend(
+        optional
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value?.to_string()))),
+    );
+    if let Some(year) = citation.year {
+        fields.push(("rft.date", year.to_string()));
+    }
+    fields.extend(citation.issn.iter().map(|issn| ("rft.issn", issn.clone())));
+    fields.extend(
+        citation
+            .authors
+            .iter()
+            .filter(|a| a.is_author())
+            .map(|author| {
+                (
+                    "rft.au",
+                    join_nonempty(&[author.family_name.trim(), author.given_name.trim()], ", "),
+                )
+            }),
+    );
+    if let Some(doi) = trimmed(&citation.doi) {
+        fields.push(("rft_id", format!("info:doi/{}", doi)));
+    }
+    if let Some(pmid) = trimmed(&citation.pmid) {
+        fields.push(("rft_id", format!("info:pmid/{}", pmid)));
+    }
+
+    fields
+        .into_iter()
+        .filter(|(_, value)| !value.is_empty())
+        .map(|(key, value)| format!("{}={}", key, url_encode(&value, "")))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Percent-encodes everything but unreserved characters and those in `keep`
+fn url_encode(text: &str, keep: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        let c = char::from(byte);
+        if c.is_ascii_alphanumeric() || "-._~".contains(c) || (byte.is_ascii() && keep.contains(c))
+        {
+            encoded.push(c);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Escapes text for use in HTML content and attribute values
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Returns the parts of a formatted reference, ending with the DOI part
+fn style_parts(citation: &Citation, style: CiteStyle) -> Vec<String> {
+    match style {
+        CiteStyle::Vancouver => vancouver(citation),
+        CiteStyle::Apa => apa(citation),
+    }
+}
+
+fn vancouver(citation: &Citation) -> Vec<String> {
+    let listed: Vec<&Author> = citation.authors.iter().filter(|a| a.is_author()).collect();
+    let names: Vec<String> = listed
+        .iter()
+        .take(6)
+        .map(|author| join_nonempty(&[author.family_name.trim(), &author.to_initials()], " "))
+        .collect();
+    let mut authors = names.join(", ");
+    if listed.len() > 6 {
+        authors.push_str(", et al");
+    }
+
+    let journal = citation
+        .journal_abbr
+        .as_deref()
+        .or(citation.journal.as_deref())
+        .map(str::trim)
+        .unwrap_or_default();
+    let mut issue = String::new();
+    if let Some(volume) = trimmed(&citation.volume) {
+        issue.push_str(volume);
+    }
+    if let Some(number) = trimmed(&citation.issue) {
+        issue.push_str(&format!("({})", number));
+    }
+    let mut date = citation.year.map(|y| y.to_string()).unwrap_or_default();
+    if !issue.is_empty() {
+        date = join_nonempty(&[&date, &issue], ";");
+    }
+    if let Some(pages) = trimmed(&citation.pages) {
+        date = join_nonempty(&[&date, pages], ":");
+    }
+
+    vec![
+        sentence(&authors),
+        sentence(citation.title.trim()),
+        sentence(journal),
+        sentence(&date),
+        trimmed(&citation.doi)
+            .map(|doi| format!("doi:{}", doi))
+            .unwrap_or_default(),
+    ]
+}
+
+fn apa(citation: &Citation) -> Vec<String> {
+    let names: Vec<String> = citation
+        .authors
+        .iter()
+        .filter(|a| a.is_author())
+        .map(apa_name)
+        .collect();
+    let authors = match names.as_slice() {
+        [] => String::new(),
+        [only] => only.clone(),
+        // Beyond twenty authors, the first nineteen and the last are listed
+        [first @ .., last] if names.len() > 20 => {
+            format!("{}, . . . {}", first[..19].join(", "), last)
+        }
+        [rest @ .., last] => format!("{}, & {}", rest.join(", "), last),
+    };
+    let year = match citation.year {
+        Some(year) => format!("({}).", year),
+        None => "(n.d.).".to_string(),
+    };
+
+    let journal = citation
+        .journal
+        .as_deref()
+        .or(citation.journal_abbr.as_deref())
+        .map(str::trim)
+        .unwrap_or_default();
+    let mut volume = trimmed(&citation.volume).unwrap_or_default().to_string();
+    if let Some(number) = trimmed(&citation.issue) {
+        volume.push_str(&format!("({})", number));
+    }
+    let pages = trimmed(&citation.pages)
+        .map(|pages| pages.replacen('-', "–", 1))
+        .unwrap_or_default();
+    let source = [journal, &volume, &pages]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    vec![
+        authors,
+        year,
+        sentence(citation.title.trim()),
+        sentence(&source),
+        trimmed(&citation.doi)
+            .map(|doi| format!("https://doi.org/{}", doi))
+            .unwrap_or_default(),
+    ]
+}
+
+/// Formats an author as `Family, J. A.`
+fn apa_name(author: &Author) -> String {
+    let initials: Vec<String> = author
+        .to_initials()
+        .chars()
+        .map(|initial| format!("{}.", initial))
+        .collect();
+    join_nonempty(&[author.family_name.trim(), &initials.join(" ")], ", ")
+}
+
+/// Ends non-empty text with a period, unless it already ends with punctuation
+fn sentence(text: &str) -> String {
+    match text.chars().last() {
+        None => String::new(),
+        Some('.' | '?' | '!') => text.to_string(),
+        Some(_) => format!("{}.", text),
+    }
+}
+
+/// Joins the non-empty parts with `separator`
+fn join_nonempty(parts: &[&str], separator: &str) -> String {
+    parts
+        .iter()
+        .filter(|part| !part.is_empty())
+        .copied()
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+/// Returns the trimmed value of an optional field, if it is not empty
+fn trimmed(value: &Option<String>) -> Option<&str> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AuthorRole;
+
+    #[test]
+    fn test_missing_fields_are_omitted() {
+        let citation = Citation {
+            title: "Untitled work?".to_string(),
+            journal: Some("Journal of Tests".to_string()),
+            volume: Some("4".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            format_citation(&citation, CiteStyle::Vancouver),
+            "Untitled work? Journal of Tests. 4."
+        );
+        assert_eq!(
+            format_citation(&citation, CiteStyle::Apa),
+            "(n.d.). Untitled work? Journal of Tests, 4."
+        );
+    }
+
+    #[test]
+    fn test_long_author_lists() {
+        let citation = Citation {
+            title: "Consortium paper".to_string(),
+            authors: (1..=7)
+                .map(|i| Author {
+                    family_name: format!("Author{}", i),
+                    given_name: "A".to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            year: Some(2021),
+            ..Default::default()
+        };
+        assert!(format_citation(&citation, CiteStyle::Vancouver)
+            .starts_with("Author1 A, Author2 A, Author3 A, Author4 A, Author5 A, Author6 A, et al. Consortium paper."));
+        assert!(format_citation(&citation, CiteStyle::Apa)
+            .starts_with("Author1, A., Author2, A., Author3, A., Author4, A., Author5, A., Author6, A., & Author7, A. (2021)."));
+
+        let authors = |count: usize| -> Vec<Author> {
+            (1..=count)
+                .map(|i| Author {
+                    family_name: format!("Author{}", i),
+                    given_name: "A".to_string(),
+                    ..Default::default()
+                })
+                .collect()
+        };
+        let citation = Citation {
+            authors: authors(20),
+            ..citation
+        };
+        assert!(format_citation(&citation, CiteStyle::Apa)
+            .contains("Author19, A., & Author20, A. (2021)."));
+        let citation = Citation {
+            authors: authors(25),
+            ..citation
+        };
+        let formatted = format_citation(&citation, CiteStyle::Apa);
+        assert!(formatted.contains("Author18, A., Author19, A., . . . Author25, A. (2021)."));
+        assert!(!formatted.contains("Author20,"));
+        assert!(!formatted.contains('&'));
+    }
+
+    #[test]
+    fn test_citations_to_html() {
+        let citation = Citation {
+            title: "<Sleep> & \"memory\"".to_string(),
+            authors: vec![Author {
+                family_name: "O'Brien".to_string(),
+                given_name: "Ann".to_string(),
+                ..Default::default()
+            }],
+            journal: Some("Sleep".to_string()),
+            year: Some(2020),
+            doi: Some("10.1000/a<b>#1".to_string()),
+            pmid: Some("123".to_string()),
+            ..Default::default()
+        };
+        let untitled = Citation {
+            title: "Untitled".to_string(),
+            ..Default::default()
+        };
+
+        let html = citations_to_html(&[citation.clone(), untitled], CiteStyle::Apa);
+        assert_eq!(
+            html,
+            "<ol>\n\
+             <li>O&#39;Brien, A. (2020). &lt;Sleep&gt; &amp; &quot;memory&quot;. Sleep. \
+             <a href=\"https://doi.org/10.1000/a%3Cb%3E%231\">https://doi.org/10.1000/a&lt;b&gt;#1</a></li>\n\
+             <li>(n.d.). Untitled.</li>\n\
+             </ol>\n"
+        );
+        assert!(!html.contains("Z3988"));
+        assert_eq!(
+            citations_to_html(&[], CiteStyle::Vancouver),
+            "<ol>\n</ol>\n"
+        );
+
+        let html = citations_to_html_with_coins(&[citation], CiteStyle::Vancouver);
+        assert!(html.contains(concat!(
+            "<span class=\"Z3988\" title=\"ctx_ver=Z39.88-2004",
+            "&amp;rft_val_fmt=info%3Aofi%2Ffmt%3Akev%3Amtx%3Ajournal",
+            "&amp;rft.genre=article",
+            "&amp;rft.atitle=%3CSleep%3E%20%26%20%22memory%22",
+            "&amp;rft.jtitle=Sleep&amp;rft.date=2020",
+            "&amp;rft.au=O%27Brien%2C%20Ann",
+            "&amp;rft_id=info%3Adoi%2F10.1000%2Fa%3Cb%3E%231",
+            "&amp;rft_id=info%3Apmid%2F123\"></span></li>",
+        )));
+    }
+
+    #[test]
+    fn test_editors_are_not_listed_as_authors() {
+        let citation = Citation {
+            title: "Edited volume".to_string(),
+            authors: vec![
+                Author {
+                    family_name: "Smith".to_string(),
+                    given_name: "John".to_string(),
+                    ..Default::default()
+                },
+                Author {
+                    family_name: "Doe".to_string(),
+                    given_name: "Jane".to_string(),
+                    role: Some(AuthorRole::Editor),
+                    ..Default::default()
+                },
+            ],
+            year: Some(2020),
+            ..Default::default()
+        };
+        assert_eq!(
+            format_citation(&citation, CiteStyle::Vancouver),
+            "Smith J. Edited volume. 2020."
+        );
+        assert_eq!(
+            format_citation(&citation, CiteStyle::Apa),
+            "Smith, J. (2020). Edited volume."
+        );
+    }
+}
@@ -0,0 +1,652 @@
+//! TEI XML parser for reference lists produced by GROBID.
+//!
+//! Parses `<biblStruct>` elements, such as those in the `<listBibl>` of a GROBID
+//! processed PDF, with built-in source tracking. Elements are matched by their
+//! local name, so both the default TEI namespace and a `tei:` prefix are accepted.
+//!
+//! # Example
+//!
+//! ```
+//! use biblib::{CitationParser, TeiParser};
+//!
+//! let input = r#"<listBibl xmlns="http://www.tei-c.org/ns/1.0">
+//! <biblStruct xml:id="b0">
+//!   <analytic>
+//!     <title level="a" type="main">Example Title</title>
+//!     <author><persName><forename type="first">John</forename><surname>Smith</surname></persName></author>
+//!     <idno type="DOI">10.1000/example</idno>
+//!   </analytic>
+//!   <monogr>
+//!     <title level="j">Example Journal</title>
+//!     <imprint><date type="published" when="2020-05-01" /></imprint>
+//!   </monogr>
+//! </biblStruct>
+//! </listBibl>"#;
+//!
+//! let parser = TeiParser::new().with_source("GROBID");
+//!
+//! let citations = parser.parse(input).unwrap();
+//! assert_eq!(citations[0].title, "Example Title");
+//! assert_eq!(citations[0].year, Some(2020));
+//! assert_eq!(citations[0].source.as_deref(), Some("GROBID"));
+//! ```
+
+use nanoid::nanoid;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::reader::Reader;
+use std::io::BufRead;
+
+use crate::utils::{
+    clean_input, extract_year, format_page_numbers, normalize_arxiv_id, push_capped, ParserOptions,
+    WarningHandler,
+};
+use crate::{Author, Citation, CitationError, CitationParser, Result};
+
+/// Parser for TEI XML `<biblStruct>` citations.
+///
+/// # Field handling
+///
+/// | Element                                   | Field             |
+/// |-------------------------------------------|-------------------|
+/// | `analytic/title`                          | `title`           |
+/// | `monogr/title` (no `analytic` title)      | `title`           |
+/// | `author/persName`                         | `authors`         |
+/// | `monogr/title[@level="j"]`                | `journal`         |
+/// | `monogr/title[@level="j"][@type="abbrev"]`| `journal_abbr`    |
+/// | `imprint/date`                            | `year`            |
+/// | `biblScope[@unit="volume"]`               | `volume`          |
+/// | `biblScope[@unit="issue"]`                | `issue`           |
+/// | `biblScope[@unit="page"]`                 | `pages`           |
+/// | `imprint/publisher`                       | `publisher`       |
+/// | `imprint/pubPlace`                        | `place`           |
+/// | `idno[@type="DOI"]`                       | `doi`             |
+/// | `idno[@type="PMID"]`                      | `pmid`            |
+/// | `idno[@type="PMCID"]`                     | `pmc_id`          |
+/// | `idno[@type="arXiv"]`                     | `arxiv_id`        |
+/// | `idno[@type="ISSN"]`, `eISSN`             | `issn`            |
+/// | `ptr[@target]`                            | `urls`            |
+/// | `textLang`, `analytic/title[@xml:lang]`   | `language`        |
+///
+/// Other identifiers are stored in `extra_fields` under their `type`, and
+/// GROBID's `<note type="raw_reference">` under `raw_reference`. Authors of the
+/// `analytic` part are preferred over those of the `monogr` part.
+#[derive(Debug, Default, Clone)]
+pub struct TeiParser {
+    options: ParserOptions,
+}
+
+/// The part of a `<biblStruct>` currently being read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Analytic,
+    Monogr,
+    Other,
+}
+
+impl TeiParser {
+    /// Creates a new TEI parser instance.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::TeiParser;
+    /// let parser = TeiParser::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the source of the parsed citations.
+    ///
+    /// Each citation's record is also named under
+    /// [`SOURCE_RECORD_KEY`](crate::SOURCE_RECORD_KEY).
+    #[must_use]
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.options.source = Some(source.to_string());
+        self
+    }
+
+    /// Stops parsing once `limit` citations have been read.
+    ///
+    /// Useful for previewing large inputs. A limit of `0` returns no citations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::TeiParser;
+    /// let parser = TeiParser::new().with_limit(10);
+    /// ```
+    #[must_use]
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.options.limit = Some(limit);
+        self
+    }
+
+    /// Sets the longest field, in bytes, kept from the input.
+    ///
+    /// Longer fields are truncated at a character boundary, as they are read,
+    /// and a warning is reported through [`TeiParser::with_warning_handler`].
+    /// Defaults to [`DEFAULT_MAX_FIELD_LENGTH`](crate::DEFAULT_MAX_FIELD_LENGTH).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::TeiParser;
+    /// let parser = TeiParser::new().with_max_field_length(64 * 1024);
+    /// ```
+    #[must_use]
+    pub fn with_max_field_length(mut self, max: usize) -> Self {
+        self.options.max_field_length = Some(max);
+        self
+    }
+
+    /// Sets the function receiving warnings, which are ignored otherwise.
+    ///
+    /// Warnings are reported when input is altered, for example when a field
+    /// exceeds the [maximum length](TeiParser::with_max_field_length).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::TeiParser;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let warnings = Arc::new(Mutex::new(Vec::new()));
+    /// let sink = Arc::clone(&warnings);
+    /// let parser = TeiParser::new()
+    ///     .with_warning_handler(move |message| sink.lock().unwrap().push(message.to_string()));
+    /// ```
+    #[must_use]
+    pub fn with_warning_handler(mut self, handler: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        self.options.warning_handler = WarningHandler::new(handler);
+        self
+    }
+
+    /// Sets whether whitespace is normalized in every field.
+    ///
+    /// When enabled, runs of whitespace, including non-breaking spaces and
+    /// line breaks, become a single space and zero-width characters are
+    /// removed, as by
+    /// [`normalize_whitespace`](crate::transform::normalize_whitespace). Off by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use biblib::TeiParser;
+    /// let parser = TeiParser::new().with_whitespace_normalization(true);
+    /// ```
+    #[must_use]
+    pub fn with_whitespace_normalization(mut self, enabled: bool) -> Self {
+        self.options.normalize_whitespace = enabled;
+        self
+    }
+
+    /// Returns the value of an attribute, matched by its local name
+    fn attribute(element: &BytesStart, name: &[u8]) -> Result<Option<String>> {
+        for attr in element.attributes() {
+            let attr = attr.map_err(CitationError::from)?;
+            if attr.key.local_name().as_ref() == name {
+                return Ok(Some(
+                    attr.unescape_value()
+                        .map_err(CitationError::from)?
+                        .into_owned(),
+                ));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Extracts the text content of an element, including nested markup such as
+    /// `<hi>`, until its closing tag is found. Whitespace is collapsed.
+    fn extract_text<B: BufRead>(
+        &self,
+        reader: &mut Reader<B>,
+        buf: &mut Vec<u8>,
+        closing_tag: &[u8],
+    ) -> Result<String> {
+        let max = self.options.max_field_length();
+        let mut text = String::new();
+        let mut truncated = false;
+
+        loop {
+            match reader.read_event_into(buf) {
+                Ok(Event::Text(e)) => {
+                    let more = e.unescape().map_err(|e| {
+                        CitationError::InvalidFormat(format!("Invalid XML text content: {}", e))
+                    })?;
+                    truncated |= push_capped(&mut text, &more, max);
+                }
+                Ok(Event::End(e)) if e.local_name().as_ref() == closing_tag => break,
+                Ok(Event::Eof) => {
+                    return Err(CitationError::InvalidFormat(format!(
+                        "Unexpected EOF while looking for closing tag '{}'",
+                        String::from_utf8_lossy(closing_tag)
+                    )))
+                }
+                Err(e) => return Err(CitationError::from(e)),
+                _ => (),
+            }
+            buf.clear();
+        }
+
+        if truncated {
+            self.options.warning_handler.warn(&format!(
+                "truncated <{}> field to {max} bytes",
+                String::from_utf8_lossy(closing_tag)
+            ));
+        }
+        Ok(text.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+
+    /// Parses a `<persName>` element into an author
+    fn parse_pers_name<B: BufRead>(
+        &self,
+        reader: &mut Reader<B>,
+        buf: &mut Vec<u8>,
+    ) -> Result<Author> {
+        let mut forenames = Vec::new();
+        let mut author = Author::default();
+
+        loop {
+            match reader.read_event_into(buf) {
+                Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                    b"forename" => forenames.push(self.extract_text(reader, buf, b"forename")?),
+                    b"surname" => {
+                        author.family_name = self.extract_text(reader, buf, b"surname")?
+                    }
+                    _ => (),
+                },
+                Ok(Event::End(e)) if e.local_name().as_ref() == b"persName" => break,
+                Ok(Event::Eof) => {
+                    return Err(CitationError::InvalidFormat(
+                        "Unexpected EOF while looking for closing tag 'persName'".into(),
+                    ))
+                }
+                Err(e) => return Err(CitationError::from(e)),
+                _ => (),
+            }
+            buf.clear();
+        }
+
+        author.given_name = forenames.join(" ");
+        Ok(author)
+    }
+
+    /// Parses a single `<biblStruct>` element into a Citation.
+    ///
+    /// Returns `None` if the element has no title.
+    fn parse_bibl_struct<B: BufRead>(
+        &self,
+        reader: &mut Reader<B>,
+        buf: &mut Vec<u8>,
+    ) -> Result<Option<Citation>> {
+        let mut citation = Citation {
+            id: nanoid!(),
+            source: self.options.source.clone(),
+            ..Default::default()
+        };
+        let mut section = Section::Other;
+        let mut analytic_authors = Vec::new();
+        let mut monogr_authors = Vec::new();
+        let mut monogr_title = None;
+        let mut monogr_level = None;
+
+        loop {
+            let (element, is_empty) = match reader.read_event_into(buf) {
+                Ok(Event::Start(e)) => (e.into_owned(), false),
+                Ok(Event::Empty(e)) => (e.into_owned(), true),
+                Ok(Event::End(e)) => {
+                    match e.local_name().as_ref() {
+                        b"biblStruct" => break,
+                        b"analytic" | b"monogr" => section = Section::Other,
+                        _ => (),
+                    }
+                    buf.clear();
+                    continue;
+                }
+                Ok(Event::Eof) => {
+                    return Err(CitationError::InvalidFormat(
+                        "Unexpected EOF while looking for closing tag 'biblStruct'".into(),
+                    ))
+                }
+                Err(e) => return Err(CitationError::from(e)),
+                _ => {
+                    buf.clear();
+                    continue;
+                }
+            };
+            buf.clear();
+
+            let name = element.local_name();
+            match name.as_ref() {
+                b"analytic" => section = Section::Analytic,
+                b"monogr" => section = Section::Monogr,
+                b"title" if !is_empty => {
+                    let level = Self::attribute(&element, b"level")?;
+                    let kind = Self::attribute(&element, b"type")?;
+                    let lang = Self::attribute(&element, b"lang")?;
+                    let title = self.extract_text(reader, buf, b"title")?;
+                    if title.is_empty() {
+                        continue;
+                    }
+                    match (section, level.as_deref()) {
+                        (Section::Analytic, _) if citation.title.is_empty() => {
+                            citation.title = title;
+                            citation.language = citation.language.take().or(lang);
+                        }
+                        (Section::Monogr, Some("j")) if kind.as_deref() == Some("abbrev") => {
+                            citation.journal_abbr.get_or_insert(title);
+                        }
+                        (Section::Monogr, Some("j")) => {
+                            citation.journal.get_or_insert(title);
+                            monogr_level = Some("j".to_string());
+                        }
+                        (Section::Monogr, level) if monogr_title.is_none() => {
+                            monogr_title = Some(title);
+                            monogr_level = level.map(String::from);
+                        }
+                        _ => (),
+                    }
+                }
+                b"persName" if !is_empty => {
+                    let author = self.parse_pers_name(reader, buf)?;
+                    match section {
+                        Section::Monogr => monogr_authors.push(author),
+                        _ => analytic_authors.push(author),
+                    }
+                }
+                b"idno" if !is_empty => {
+                    let kind = Self::attribute(&element, b"type")?.unwrap_or_default();
+                    let value = self.extract_text(reader, buf, b"idno")?;
+                    match kind.to_uppercase().as_str() {
+                        "DOI" => citation.add_doi(&value),
+                        "PMID" => {
+                            citation.pmid.get_or_insert(value);
+                        }
+                        "PMCID" => {
+                            citation.pmc_id.get_or_insert(value);
+                        }
+                        "ARXIV" if normalize_arxiv_id(&value).is_some() => {
+                            citation.arxiv_id = normalize_arxiv_id(&value);
+                        }
+                        "ISSN" | "EISSN" => citation.issn.push(value),
+                        _ => {
+                            let key = if kind.is_empty() {
+                                "idno".to_string()
+                            } else {
+                                kind
+                            };
+                            citation.extra_fields.entry(key).or_default().push(value);
+                        }
+                    }
+                }
+                b"date" => {
+                    let year = match Self::attribute(&element, b"when")? {
+                        Some(when) => extract_year(&when),
+                        None if !is_empty => {
+                            extract_year(&self.extract_text(reader, buf, b"date")?)
+                        }
+                        None => None,
+                    };
+                    if citation.year.is_none() {
+                        citation.year = year;
+                    }
+                }
+                b"biblScope" => {
+                    let unit = Self::attribute(&element, b"unit")?.unwrap_or_default();
+                    let from = Self::attribute(&element, b"from")?;
+                    let to = Self::attribute(&element, b"to")?;
+                    let text = if is_empty {
+                        String::new()
+                    } else {
+                        self.extract_text(reader, buf, b"biblScope")?
+                    };
+                    let value = match (from, to) {
+                        (Some(from), Some(to)) if unit == "page" => format!("{}-{}", from, to),
+                        (Some(from), _) if text.is_empty() => from,
+                        _ => text,
+                    };
+                    if value.is_empty() {
+                        continue;
+                    }
+                    match unit.as_str() {
+                        "volume" => {
+                            citation.volume.get_or_insert(value);
+                        }
+                        "issue" => {
+                            citation.issue.get_or_insert(value);
+                        }
+                        "page" => {
+                            citation
+                                .pages
+                                .get_or_insert_with(|| format_page_numbers(&value));
+                        }
+                        _ => (),
+                    }
+                }
+                b"textLang" => {
+                    let language = match Self::attribute(&element, b"mainLang")? {
+                        Some(lang) => lang,
+                        None if !is_empty => self.extract_text(reader, buf, b"textLang")?,
+                        None => continue,
+                    };
+                    if !language.is_empty() {
+                        citation.language = Some(language);
+                    }
+                }
+                b"publisher" if !is_empty => {
+                    let publisher = self.extract_text(reader, buf, b"publisher")?;
+                    citation.publisher.get_or_insert(publisher);
+                }
+                b"pubPlace" if !is_empty => {
+                    let place = self.extract_text(reader, buf, b"pubPlace")?;
+                    citation.place.get_or_insert(place);
+                }
+                b"ptr" => {
+                    if let Some(target) = Self::attribute(&element, b"target")? {
+                        citation.urls.push(target);
+                    }
+                }
+                b"note"
+                    if !is_empty
+                        && Self::attribute(&element, b"type")?.as_deref()
+                            == Some("raw_reference") =>
+                {
+                    let raw = self.extract_text(reader, buf, b"note")?;
+                    citation
+                        .extra_fields
+                        .entry("raw_reference".to_string())
+                        .or_default()
+                        .push(raw);
+                }
+                _ => (),
+            }
+        }
+
+        let has_analytic_title = !citation.title.is_empty();
+        if !has_analytic_title {
+            match monogr_title {
+                Some(title) => citation.title = title,
+                None => return Ok(None),
+            }
+        }
+
+        citation.authors = if analytic_authors.is_empty() {
+            monogr_authors
+        } else {
+            analytic_authors
+        };
+
+        let citation_type = match (monogr_level.as_deref(), has_analytic_title) {
+            (Some("j"), _) => Some("Journal Article"),
+            (Some("m"), true) => Some("Book Section"),
+            (Some("m"), false) => Some("Book"),
+            _ => None,
+        };
+        citation
+            .citation_type
+            .extend(citation_type.map(String::from));
+
+        Ok(Some(citation))
+    }
+}
+
+impl CitationParser for TeiParser {
+    fn parse(&self, input: &str) -> Result<Vec<Citation>> {
+        let input: &str = &clean_input(input);
+        if input.trim().is_empty() {
+            return Err(CitationError::InvalidFormat("Empty input".into()));
+        }
+
+        let limit = self.options.limit();
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let mut reader = Reader::from_str(input);
+        let mut citations = Vec::new();
+        let mut buf = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(ref e)) if e.local_name().as_ref() == b"biblStruct" => {
+                    buf.clear();
+                    citations.extend(self.parse_bibl_struct(&mut reader, &mut buf)?);
+                    if citations.len() >= limit {
+                        break;
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(CitationError::from(e)),
+                _ => (),
+            }
+            buf.clear();
+        }
+
+        if citations.is_empty() {
+            return Err(CitationError::InvalidFormat(
+                "No valid citations found".into(),
+            ));
+        }
+
+        self.options.finish(&mut citations);
+        Ok(citations)
+    }
+
+    fn format_name(&self) -> &'static str {
+        "TEI"
+    }
+
+    fn can_parse(&self, input: &str) -> bool {
+        let input: &str = &clean_input(input);
+        let trimmed = input.trim();
+        trimmed.starts_with('<') && trimmed.contains("biblStruct")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_grobid_references() {
+        let input = r#"<?xml version="1.0" encoding="UTF-8"?>
+<TEI xmlns="http://www.tei-c.org/ns/1.0">
+<text><back><div type="references"><listBibl>
+<biblStruct xml:id="b0">
+    <analytic>
+        <title level="a" type="main">Effects of <hi rend="italic">in vivo</hi> exposure</title>
+        <author>
+            <persName><forename type="first">John</forename><forename type="middle">A</forename><surname>Smith</surname></persName>
+        </author>
+        <author>
+            <persName><forename type="first">Jane</forename><surname>Doe</surname></persName>
+        </author>
+        <idno type="DOI">10.1000/Test.2019</idno>
+    </analytic>
+    <monogr>
+        <title level="j">Journal of Testing</title>
+        <title level="j" type="abbrev">J Test</title>
+        <imprint>
+            <biblScope unit="volume">12</biblScope>
+            <biblScope unit="issue">3</biblScope>
+            <biblScope unit="page" from="100" to="110" />
+            <date type="published" when="2019-03-01" />
+        </imprint>
+    </monogr>
+    <idno type="PMID">12345678</idno>
+    <idno type="arXiv">arXiv:1903.01234v2</idno>
+    <note type="raw_reference">Smith JA, Doe J. Effects of in vivo exposure. J Test. 2019;12(3):100-10.</note>
+</biblStruct>
+<biblStruct xml:id="b1">
+    <monogr>
+        <title level="m">A Book on Testing</title>
+        <author><persName><forename>Ann</forename><surname>Lee</surname></persName></author>
+        <imprint>
+            <publisher>Test Press</publisher>
+            <date>March 2015</date>
+        </imprint>
+    </monogr>
+</biblStruct>
+</listBibl></div></back></text>
+</TEI>"#;
+
+        let parser = TeiParser::new();
+        let citations = parser.parse(input).unwrap();
+        assert_eq!(citations.len(), 2);
+
+        let article = &citations[0];
+        assert_eq!(article.title, "Effects of in vivo exposure");
+        assert_eq!(article.citation_type, vec!["Journal Article"]);
+        assert_eq!(article.authors.len(), 2);
+        assert_eq!(article.authors[0].family_name, "Smith");
+        assert_eq!(article.authors[0].given_name, "John A");
+        assert_eq!(article.journal.as_deref(), Some("Journal of Testing"));
+        assert_eq!(article.journal_abbr.as_deref(), Some("J Test"));
+        assert_eq!(article.volume.as_deref(), Some("12"));
+        assert_eq!(article.issue.as_deref(), Some("3"));
+        assert_eq!(article.pages.as_deref(), Some("100-110"));
+        assert_eq!(article.year, Some(2019));
+        assert_eq!(article.doi.as_deref(), Some("10.1000/test.2019"));
+        assert_eq!(article.pmid.as_deref(), Some("12345678"));
+        assert_eq!(article.arxiv_id.as_deref(), Some("1903.01234v2"));
+        assert!(article.extra_first("raw_reference").is_some());
+
+        let book = &citations[1];
+        assert_eq!(book.title, "A Book on Testing");
+        assert_eq!(book.citation_type, vec!["Book"]);
+        assert_eq!(book.authors[0].family_name, "Lee");
+        assert_eq!(book.publisher.as_deref(), Some("Test Press"));
+        assert_eq!(book.year, Some(2015));
+        assert_eq!(book.arxiv_id, None);
+    }
+
+    #[test]
+    fn test_parse_prefixed_namespace() {
+        let input = r#"<tei:listBibl xmlns:tei="http://www.tei-c.org/ns/1.0">
+<tei:biblStruct>
+    <tei:analytic><tei:title level="a">Prefixed Title</tei:title></tei:analytic>
+    <tei:monogr><tei:title level="j">Journal</tei:title><tei:imprint><tei:date when="2021"/></tei:imprint></tei:monogr>
+</tei:biblStruct>
+</tei:listBibl>"#;
+
+        let parser = TeiParser::new().with_source("GROBID");
+        let citations = parser.parse(input).unwrap();
+        assert_eq!(citations[0].title, "Prefixed Title");
+        assert_eq!(citations[0].journal.as_deref(), Some("Journal"));
+        assert_eq!(citations[0].year, Some(2021));
+        assert_eq!(citations[0].source.as_deref(), Some("GROBID"));
+    }
+
+    #[test]
+    fn test_skip_untitled_and_malformed() {
+        let parser = TeiParser::new();
+        let untitled =
+            r#"<listBibl><biblStruct><monogr><imprint/></monogr></biblStruct></listBibl>"#;
+        assert!(parser.parse(untitled).is_err());
+
+        let malformed = r#"<listBibl><biblStruct><analytic><title>Open"#;
+        assert!(parser.parse(malformed).is_err());
+    }
+}
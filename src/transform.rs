@@ -0,0 +1,424 @@
+//! Bulk transformations applied to every citation in a collection.
+//!
+//! [`transform_citations`] applies any closure to each citation, and the
+//! functions in this module are common cleanup steps that can be passed to it.
+//!
+//! # Example
+//!
+//! ```
+//! use biblib::transform::{dedupe_keywords, normalize_dois, transform_citations, trim_all_fields};
+//! use biblib::Citation;
+//!
+//! let mut citations = vec![Citation {
+//!     title: "  Example Title ".to_string(),
+//!     doi: Some("https://doi.org/10.1000/ABC".to_string()),
+//!     keywords: vec!["Cancer".to_string(), "cancer".to_string()],
+//!     ..Default::default()
+//! }];
+//!
+//! transform_citations(&mut citations, trim_all_fields);
+//! transform_citations(&mut citations, normalize_dois);
+//! transform_citations(&mut citations, dedupe_keywords);
+//! transform_citations(&mut citations, |c| c.title = c.title.to_uppercase());
+//!
+//! assert_eq!(citations[0].title, "EXAMPLE TITLE");
+//! assert_eq!(citations[0].doi.as_deref(), Some("10.1000/abc"));
+//! assert_eq!(citations[0].keywords, vec!["Cancer"]);
+//! ```
+
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use crate::utils::{collapse_whitespace, format_doi, split_copyright, strip_trailing_period};
+use crate::{Citation, Grant};
+
+/// Applies `f` to every citation, in order.
+///
+/// # Arguments
+///
+/// * `citations` - The citations to transform
+/// * `f` - The transformation to apply to each citation
+pub fn transform_citations(citations: &mut [Citation], mut f: impl FnMut(&mut Citation)) {
+    for citation in citations {
+        f(citation);
+    }
+}
+
+/// Trims whitespace from every text field.
+///
+/// Optional fields that become empty are set to `None`, and empty entries are
+/// removed from lists.
+pub fn trim_all_fields(citation: &mut Citation) {
+    clean_all_fields(citation, &|value| Cow::Borrowed(value.trim()));
+}
+
+/// Collapses whitespace and strips zero-width characters in every text field.
+///
+/// Runs of spaces, tabs, line breaks and non-breaking spaces become a single
+/// space, zero-width characters and stray byte order marks left by copy and
+/// paste are removed, and the result is trimmed, so values compare equal
+/// however they were typed. Empty values are dropped as in
+/// [`trim_all_fields`]. Parsers apply this on request with their
+/// `with_whitespace_normalization` method.
+///
+/// See [`collapse_whitespace`].
+///
+/// # Examples
+///
+/// ```
+/// use biblib::transform::normalize_whitespace;
+/// use biblib::Citation;
+///
+/// let mut citation = Citation {
+///     title: "Effects of\u{a0}aspirin  on\u{200b} platelets".to_string(),
+///     ..Default::default()
+/// };
+/// normalize_whitespace(&mut citation);
+/// assert_eq!(citation.title, "Effects of aspirin on platelets");
+/// ```
+pub fn normalize_whitespace(citation: &mut Citation) {
+    clean_all_fields(citation, &collapse_whitespace);
+}
+
+/// Replaces every text field with `clean` applied to it, dropping values left
+/// empty.
+fn clean_all_fields(citation: &mut Citation, clean: &dyn Fn(&str) -> Cow<'_, str>) {
+    let text = |value: &mut String| {
+        let cleaned = clean(value);
+        if cleaned != value.as_str() {
+            *value = cleaned.into_owned();
+        }
+    };
+
+    let option = |value: &mut Option<String>| {
+        if let Some(content) = value {
+            text(content);
+            if content.is_empty() {
+                *value = None;
+            }
+        }
+    };
+
+    let list = |values: &mut Vec<String>| {
+        values.iter_mut().for_each(text);
+        values.retain(|value| !value.is_empty());
+    };
+
+    text(&mut citation.title);
+    for author in &mut citation.authors {
+        text(&mut author.family_name);
+        text(&mut author.given_name);
+        option(&mut author.affiliation);
+        option(&mut author.orcid);
+    }
+    for grant in &mut citation.grants {
+        option(&mut grant.id);
+        option(&mut grant.agency);
+        option(&mut grant.country);
+    }
+    citation.grants.retain(|grant| grant != &Grant::default());
+    for related in &mut citation.related {
+        text(&mut related.ref_type);
+        text(&mut related.pmid);
+    }
+    for field in [
+        &mut citation.journal,
+        &mut citation.journal_abbr,
+        &mut citation.volume,
+        &mut citation.issue,
+        &mut citation.pages,
+        &mut citation.status,
+        &mut citation.doi,
+        &mut citation.pmid,
+        &mut citation.pmc_id,
+        &mut citation.arxiv_id,
+        &mut citation.abstract_text,
+        &mut citation.copyright,
+        &mut citation.language,
+        &mut citation.publisher,
+        &mut citation.place,
+        &mut citation.conference_name,
+        &mut citation.series,
+        &mut citation.accession_number,
+    ] {
+        option(field);
+    }
+    for values in [
+        &mut citation.citation_type,
+        &mut citation.issn,
+        &mut citation.isbn,
+        &mut citation.all_dois,
+        &mut citation.keywords,
+        &mut citation.urls,
+        &mut citation.mesh_terms,
+    ] {
+        list(values);
+    }
+    citation.extra_fields.values_mut().for_each(list);
+}
+
+/// Collapses every author's given name to initials, e.g. `"John Michael"` to
+/// `"JM"`.
+///
+/// See [`Author::to_initials`](crate::Author::to_initials).
+pub fn given_names_to_initials(citation: &mut Citation) {
+    for author in &mut citation.authors {
+        author.given_name = author.to_initials();
+    }
+}
+
+/// Field used to order citations with [`sort_citations`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// [`Citation::cited_by_count`]
+    CitedBy,
+    /// [`Citation::reference_count`]
+    ReferenceCount,
+}
+
+/// Sorts citations from the highest to the lowest value of `key`.
+///
+/// Citations without a value come last. The sort is stable, so citations with
+/// equal values keep their order.
+///
+/// # Arguments
+///
+/// * `citations` - The citations to sort
+/// * `key` - The field to sort by
+///
+/// # Examples
+///
+/// ```
+/// use biblib::transform::{sort_citations, SortKey};
+/// use biblib::Citation;
+///
+/// let mut citations = vec![
+///     Citation { cited_by_count: Some(3), ..Default::default() },
+///     Citation::default(),
+///     Citation { cited_by_count: Some(40), ..Default::default() },
+/// ];
+/// sort_citations(&mut citations, SortKey::CitedBy);
+///
+/// let counts: Vec<_> = citations.iter().map(|c| c.cited_by_count).collect();
+/// assert_eq!(counts, [Some(40), Some(3), None]);
+/// ```
+pub fn sort_citations(citations: &mut [Citation], key: SortKey) {
+    let value = |citation: &Citation| match key {
+        SortKey::CitedBy => citation.cited_by_count,
+        SortKey::ReferenceCount => citation.reference_count,
+    };
+    citations.sort_by_key(|citation| std::cmp::Reverse(value(citation)));
+}
+
+/// Normalizes the DOI to its bare lowercase form, e.g. `10.1000/abc`.
+///
+/// URL prefixes and `[doi]` suffixes are removed, also from `all_dois`. A DOI
+/// that cannot be recognized is removed.
+pub fn normalize_dois(citation: &mut Citation) {
+    citation.doi = citation.doi.as_deref().and_then(format_doi);
+    let mut seen = HashSet::new();
+    citation.all_dois = citation
+        .all_dois
+        .iter()
+        .filter_map(|doi| format_doi(doi))
+        .filter(|doi| seen.insert(doi.clone()))
+        .collect();
+}
+
+/// Removes repeated keywords, ignoring case and surrounding whitespace.
+///
+/// The first occurrence of each keyword is kept.
+pub fn dedupe_keywords(citation: &mut Citation) {
+    let mut seen = HashSet::new();
+    citation
+        .keywords
+        .retain(|keyword| seen.insert(keyword.trim().to_lowercase()));
+}
+
+/// Removes a single period ending the title, as some databases add one.
+///
+/// Periods ending an abbreviation such as `"etc."` or `"et al."`, an initial,
+/// an acronym such as `"U.S."` or an ellipsis are kept. Deduplication already
+/// ignores punctuation, so this only affects exact comparisons and display.
+///
+/// # Examples
+///
+/// ```
+/// use biblib::transform::strip_title_period;
+/// use biblib::Citation;
+///
+/// let mut citation = Citation {
+///     title: "Effects of aspirin.".to_string(),
+///     ..Default::default()
+/// };
+/// strip_title_period(&mut citation);
+/// assert_eq!(citation.title, "Effects of aspirin");
+///
+/// citation.title = "Cats, dogs, etc.".to_string();
+/// strip_title_period(&mut citation);
+/// assert_eq!(citation.title, "Cats, dogs, etc.");
+/// ```
+pub fn strip_title_period(citation: &mut Citation) {
+    let stripped = strip_trailing_period(&citation.title);
+    if stripped.len() != citation.title.len() {
+        citation.title = stripped.to_string();
+    }
+}
+
+/// Moves a trailing copyright statement from the abstract to `copyright`.
+///
+/// Statements start with `"Copyright"`, `"©"` or `"(c)"` followed by a year, or
+/// are sentences containing `"All rights reserved"`, and run to the end of the
+/// abstract. An existing `copyright` is kept, and the abstract is set to `None`
+/// if nothing else remains. As a sentence mentioning copyright mid-abstract
+/// would also be cut, this is not applied by default.
+///
+/// # Examples
+///
+/// ```
+/// use biblib::transform::extract_copyright;
+/// use biblib::Citation;
+///
+/// let mut citation = Citation {
+///     abstract_text: Some("Results were good. © 2020 Elsevier Ltd.".to_string()),
+///     ..Default::default()
+/// };
+/// extract_copyright(&mut citation);
+/// assert_eq!(citation.abstract_text.as_deref(), Some("Results were good."));
+/// assert_eq!(citation.copyright.as_deref(), Some("© 2020 Elsevier Ltd."));
+/// ```
+pub fn extract_copyright(citation: &mut Citation) {
+    let Some(text) = &citation.abstract_text else {
+        return;
+    };
+    let Some((rest, statement)) = split_copyright(text) else {
+        return;
+    };
+    if citation.copyright.is_none() {
+        citation.copyright = Some(statement.to_string());
+    }
+    citation.abstract_text = (!rest.is_empty()).then(|| rest.to_string());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Author;
+
+    #[test]
+    fn test_trim_all_fields() {
+        let mut citation = Citation {
+            title: " Title\n".to_string(),
+            authors: vec![Author {
+                family_name: " Smith ".to_string(),
+                given_name: "J ".to_string(),
+                affiliation: Some("  ".to_string()),
+                ..Default::default()
+            }],
+            journal: Some(" Journal ".to_string()),
+            volume: Some(" ".to_string()),
+            keywords: vec![" one ".to_string(), "".to_string()],
+            ..Default::default()
+        };
+        citation
+            .extra_fields
+            .insert("N1".to_string(), vec![" note ".to_string()]);
+
+        trim_all_fields(&mut citation);
+        assert_eq!(citation.title, "Title");
+        assert_eq!(citation.authors[0].family_name, "Smith");
+        assert_eq!(citation.authors[0].given_name, "J");
+        assert_eq!(citation.authors[0].affiliation, None);
+        assert_eq!(citation.journal.as_deref(), Some("Journal"));
+        assert_eq!(citation.volume, None);
+        assert_eq!(citation.keywords, vec!["one"]);
+        assert_eq!(citation.extra_first("N1"), Some("note"));
+    }
+
+    #[test]
+    fn test_normalize_whitespace() {
+        let mut citation = Citation {
+            title: "Effects of\u{a0}aspirin  on\tplatelets\u{200b}".to_string(),
+            authors: vec![Author {
+                family_name: "\u{feff}Smith".to_string(),
+                given_name: "John\u{a0}\u{a0}A".to_string(),
+                ..Default::default()
+            }],
+            abstract_text: Some("First line.\n   Second line.".to_string()),
+            volume: Some("\u{200b}".to_string()),
+            keywords: vec!["platelet  aggregation".to_string(), "\u{a0}".to_string()],
+            ..Default::default()
+        };
+        citation
+            .extra_fields
+            .insert("N1".to_string(), vec!["a\u{2060}b  c".to_string()]);
+
+        normalize_whitespace(&mut citation);
+        assert_eq!(citation.title, "Effects of aspirin on platelets");
+        assert_eq!(citation.authors[0].family_name, "Smith");
+        assert_eq!(citation.authors[0].given_name, "John A");
+        assert_eq!(
+            citation.abstract_text.as_deref(),
+            Some("First line. Second line.")
+        );
+        assert_eq!(citation.volume, None);
+        assert_eq!(citation.keywords, vec!["platelet aggregation"]);
+        assert_eq!(citation.extra_first("N1"), Some("ab c"));
+    }
+
+    #[test]
+    fn test_given_names_to_initials() {
+        let author = |given: &str| Author {
+            family_name: "Smith".to_string(),
+            given_name: given.to_string(),
+            ..Default::default()
+        };
+        let mut citations = vec![Citation {
+            authors: vec![
+                author("John Michael"),
+                author("Jean-Paul"),
+                author(""),
+                author("JMK"),
+                author("EVA MARIA"),
+                author("ANN"),
+                author("J-P"),
+            ],
+            ..Default::default()
+        }];
+
+        transform_citations(&mut citations, given_names_to_initials);
+        let given: Vec<&str> = citations[0]
+            .authors
+            .iter()
+            .map(|a| a.given_name.as_str())
+            .collect();
+        assert_eq!(given, ["JM", "JP", "", "JMK", "EM", "A", "JP"]);
+    }
+
+    #[test]
+    fn test_normalize_dois_and_dedupe_keywords() {
+        let mut citations = vec![
+            Citation {
+                doi: Some("https://doi.org/10.1000/XYZ [doi]".to_string()),
+                keywords: vec![
+                    "Asthma".to_string(),
+                    " asthma".to_string(),
+                    "COPD".to_string(),
+                ],
+                ..Default::default()
+            },
+            Citation {
+                doi: Some("not a doi".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        transform_citations(&mut citations, |c| {
+            normalize_dois(c);
+            dedupe_keywords(c);
+        });
+        assert_eq!(citations[0].doi.as_deref(), Some("10.1000/xyz"));
+        assert_eq!(citations[0].keywords, vec!["Asthma", "COPD"]);
+        assert_eq!(citations[1].doi, None);
+    }
+}
@@ -1,17 +1,144 @@
+#[cfg(any(
+    feature = "csv",
+    feature = "pubmed",
+    feature = "xml",
+    feature = "ris",
+    feature = "refer",
+    feature = "tei",
+    feature = "crossref"
+))]
+use crate::{Citation, SOURCE_RECORD_KEY};
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
 use once_cell::sync::Lazy;
+#[cfg(feature = "std")]
 use regex::Regex;
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+#[cfg(any(
+    feature = "csv",
+    feature = "pubmed",
+    feature = "xml",
+    feature = "ris",
+    feature = "refer",
+    feature = "tei",
+    feature = "crossref",
+    feature = "dedupe"
+))]
+use std::{fmt, sync::Arc};
 
+#[cfg(feature = "std")]
 static DOI_URL_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"^https?://(?:dx\.)?doi\.org/(.+)$").unwrap());
 
+#[cfg(any(feature = "csv", feature = "xml", feature = "refer"))]
 static ISSN_SPLIT_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"\d{4}-\d{3}[\dX](?:\s*\([^)]+\))?").unwrap());
 
+#[cfg(feature = "std")]
+static MARKUP_TAG_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)</?(?:i|b|u|em|strong|sup|sub|inf)>").unwrap());
+
+/// A sentence of a copyright statement: a `©`, `(c)` or `Copyright` with a
+/// year, or `All rights reserved`
+#[cfg(feature = "std")]
+static COPYRIGHT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        r"(?i)©|\(c\)\s*(?:19|20)\d{2}|\bcopyright\s*(?:\(c\)\s*)?(?:19|20)\d{2}",
+        r"|\ball rights reserved\b",
+    ))
+    .unwrap()
+});
+
+/// The end of a sentence and the whitespace after it
+#[cfg(feature = "std")]
+static SENTENCE_END_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"[.!?]\s+").unwrap());
+
+#[cfg(feature = "std")]
+static CORRECTION_TITLE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        r"(?is)^\s*\[?\s*(?:published\s+)?",
+        r"(?:erratum|correction|corrigendum|retraction(?:\s+(?:notice|note|statement))?",
+        r"|retracted(?:\s+article)?|notice\s+of\s+retraction|expression\s+of\s+concern)",
+        r"(?:\s+(?:to|of|for|on|regarding))?(?:\s*[:\]]\s*(.*))?\s*$",
+    ))
+    .unwrap()
+});
+/// A volume with its issue in parentheses, e.g. `12(3)` or `45 (Suppl 1)`
+#[cfg(feature = "ris")]
+static VOLUME_ISSUE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^([0-9A-Za-z]+(?:[.-][0-9A-Za-z]+)*)\s*\(\s*([0-9A-Za-z][0-9A-Za-z .,/-]*?)\s*\)$")
+        .unwrap()
+});
+
+#[cfg(feature = "std")]
+static DIGIT_RUN_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
+
+/// Text ending just before a number that marks it as pages or a volume, e.g.
+/// `pp. `, `Vol `, the `12(3):` of a citation, or the start of a page range
+#[cfg(feature = "std")]
+static NUMBER_LABEL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?:\b(?:pp?|pages?|vol|volume)\.?|[\d)]\s*:)\s*(?:\d+\s*[-–]\s*)?$").unwrap()
+});
+
+/// A `PMID: 12345678` reference in free text
+#[cfg(feature = "std")]
+static INLINE_PMID_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)\bPMID\s*:?\s*(\d+)\b").unwrap());
+
+/// The start of a DOI, with its `doi:` or `doi.org` prefix
+#[cfg(feature = "ris")]
+static DOI_START_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)(?:https?://(?:dx\.)?doi\.org/|doi:\s*)?10\.\d{4,9}/").unwrap());
+
+/// A DOI in free text, running to the next whitespace or delimiter
+#[cfg(feature = "std")]
+static INLINE_DOI_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"\b10\.\d{4,9}/[^\s"<>]+"#).unwrap());
+
+/// An arXiv identifier in free text, after `arXiv:`, an `arxiv.org` link or
+/// the arXiv DOI prefix
+#[cfg(feature = "std")]
+static INLINE_ARXIV_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)(?:arxiv\.org/(?:abs|pdf)/|arxiv:\s*|10\.48550/arxiv\.)([a-z-]+(?:\.[a-z]{2})?/\d{7}(?:v\d+)?|\d{4}\.\d{4,5}(?:v\d+)?)\b",
+    )
+    .unwrap()
+});
+
+/// Earliest year accepted by [`extract_year`]
+#[cfg(feature = "std")]
+const MIN_YEAR: i32 = 1500;
+
+/// Markdown-style delimiters for inline markup tags
+#[cfg(feature = "pubmed")]
+const MARKUP_DELIMITERS: [(&str, &str); 7] = [
+    ("i", "*"),
+    ("em", "*"),
+    ("b", "**"),
+    ("strong", "**"),
+    ("sup", "^"),
+    ("sub", "~"),
+    ("inf", "~"),
+];
+
 /// Formats page numbers consistently, handling partial end page numbers
 ///
 /// # Arguments
 ///
 /// * `page_str` - The page string to format
+///
+/// # Examples
+///
+/// ```
+/// use biblib::format_page_numbers;
+///
+/// assert_eq!(format_page_numbers("123-30"), "123-130");
+/// assert_eq!(format_page_numbers("e12-e18"), "e12-e18");
+/// ```
 pub fn format_page_numbers(page_range: &str) -> String {
     // Handle non-hyphenated or empty input
     if !page_range.contains('-') {
@@ -82,11 +209,44 @@ fn split_prefix_and_number(input: &str) -> (String, Option<String>) {
     }
 }
 
+/// Finds the first page of a page range, normalized for comparison
+///
+/// `"123-130"`, `"123-30"`, `"pp. 123–130"` and `"123"` all give `"123"`.
+/// Letters before the number are kept, lowercased, so electronic locators
+/// such as `"e123"` and supplement pages such as `"S12"` only match the same
+/// locator. Returns `None` if there is no page.
+///
+/// # Arguments
+///
+/// * `pages` - The page range, e.g. from [`format_page_numbers`]
+#[cfg(feature = "dedupe")]
+pub fn first_page(pages: &str) -> Option<String> {
+    let pages = pages.trim().to_lowercase();
+    let pages = pages
+        .strip_prefix("pp.")
+        .or_else(|| pages.strip_prefix("p."))
+        .unwrap_or(&pages);
+    let first = pages.split(['-', '–', '—', ',']).next()?.trim();
+    let page = match split_prefix_and_number(first) {
+        (prefix, Some(number)) => {
+            let number = number.trim_start_matches('0');
+            format!(
+                "{}{}",
+                prefix.trim(),
+                if number.is_empty() { "0" } else { number }
+            )
+        }
+        (prefix, None) => prefix,
+    };
+    Some(page).filter(|page| !page.is_empty())
+}
+
 /// Formats a DOI string by removing URL prefixes and [doi] suffixes
 ///
 /// # Arguments
 ///
 /// * `doi_str` - The DOI string to format
+#[cfg(feature = "std")]
 pub fn format_doi(doi_str: &str) -> Option<String> {
     if doi_str.is_empty() {
         return None;
@@ -111,11 +271,94 @@ pub fn format_doi(doi_str: &str) -> Option<String> {
     }
 }
 
+/// DOI prefixes of data repositories, whose DOIs identify datasets rather than articles
+#[cfg(any(
+    feature = "csv",
+    feature = "pubmed",
+    feature = "xml",
+    feature = "ris",
+    feature = "refer",
+    feature = "tei",
+    feature = "crossref"
+))]
+const DATASET_DOI_PREFIXES: [&str; 7] = [
+    "10.5061/",  // Dryad
+    "10.5281/",  // Zenodo
+    "10.6084/",  // figshare
+    "10.17632/", // Mendeley Data
+    "10.7910/",  // Harvard Dataverse
+    "10.1594/",  // PANGAEA
+    "10.15468/", // GBIF
+];
+
+/// Selects the DOI that most likely identifies the article itself.
+///
+/// The first DOI that is not from a data repository is preferred, falling back
+/// to the first DOI.
+///
+/// # Arguments
+///
+/// * `dois` - Formatted DOIs, in the order they appear in the record
+#[cfg(any(
+    feature = "csv",
+    feature = "pubmed",
+    feature = "xml",
+    feature = "ris",
+    feature = "refer",
+    feature = "tei",
+    feature = "crossref"
+))]
+pub fn primary_doi(dois: &[String]) -> Option<&String> {
+    dois.iter()
+        .find(|doi| {
+            !DATASET_DOI_PREFIXES
+                .iter()
+                .any(|prefix| doi.starts_with(prefix))
+        })
+        .or_else(|| dois.first())
+}
+
+/// Splits a field listing several DOIs, separated by spaces or semicolons
+///
+/// A new DOI starts at each `10.` prefix, with its `doi:` or `doi.org` prefix
+/// if any, that follows a space or semicolon. Semicolons and spaces within a
+/// DOI, as in `10.1002/(SICI)...3.0.CO;2-2`, are kept. A field without such a
+/// separator is returned whole.
+///
+/// # Arguments
+///
+/// * `value` - The field listing one or more DOIs
+#[cfg(feature = "ris")]
+pub fn split_dois(value: &str) -> Vec<&str> {
+    let mut starts: Vec<usize> = DOI_START_REGEX
+        .find_iter(value)
+        .map(|m| m.start())
+        .filter(|&start| {
+            value[..start]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_whitespace() || c == ';')
+        })
+        .collect();
+    starts.insert(0, 0);
+    starts
+        .iter()
+        .zip(starts.iter().skip(1).chain([&value.len()]))
+        .map(|(&start, &end)| {
+            value[start..end]
+                .trim_end_matches(|c: char| c.is_whitespace() || c == ';')
+                .trim()
+        })
+        .filter(|doi| !doi.is_empty())
+        .collect()
+}
+
 /// Splits a string containing multiple ISSNs into a vector of individual ISSNs
 ///
 /// # Arguments
 ///
 /// * `issns` - String containing one or more ISSNs, possibly separated by newlines
+#[cfg(any(feature = "csv", feature = "xml", feature = "refer"))]
 pub fn split_issns(issns: &str) -> Vec<String> {
     let normalized = issns
         .replace("\\r\\n", "\n")
@@ -128,19 +371,1098 @@ pub fn split_issns(issns: &str) -> Vec<String> {
             continue;
         }
 
-        let matches: Vec<_> = ISSN_SPLIT_REGEX
-            .find_iter(line)
-            .map(|m| m.as_str().trim())
-            .collect();
+        let matches: Vec<_> = ISSN_SPLIT_REGEX
+            .find_iter(line)
+            .map(|m| m.as_str().trim())
+            .collect();
+
+        if !matches.is_empty() {
+            result.extend(matches.into_iter().map(String::from));
+        }
+    }
+    result
+}
+
+/// Normalizes an ISBN-10 or ISBN-13 and validates its check digit
+///
+/// Accepts hyphens and spaces, an `ISBN`, `ISBN-10:` or `ISBN-13:` prefix and
+/// a trailing qualifier such as `(pbk.)`. Returns the bare digits, e.g.
+/// `9780306406157`, or `None` if the value is not a valid ISBN.
+///
+/// # Arguments
+///
+/// * `isbn` - The ISBN to normalize
+///
+/// # Examples
+///
+/// ```
+/// use biblib::normalize_isbn;
+///
+/// assert_eq!(
+///     normalize_isbn("ISBN 978-0-306-40615-7 (pbk.)").as_deref(),
+///     Some("9780306406157")
+/// );
+/// assert_eq!(normalize_isbn("978-0-306-40615-8"), None);
+/// ```
+pub fn normalize_isbn(isbn: &str) -> Option<String> {
+    let chars = isbn_chars(isbn)?;
+    let digit = |c: &char| c.to_digit(10);
+    let valid = if chars.len() == 10 {
+        let total: u32 = chars[..9]
+            .iter()
+            .zip((2..=10).rev())
+            .map(|(c, weight)| digit(c).unwrap_or(0) * weight)
+            .sum();
+        let check = match (11 - total % 11) % 11 {
+            10 => 'X',
+            value => char::from_digit(value, 10)?,
+        };
+        chars[9] == check
+    } else {
+        let total: u32 = chars
+            .iter()
+            .zip([1, 3].into_iter().cycle())
+            .map(|(c, weight)| digit(c).unwrap_or(0) * weight)
+            .sum();
+        total % 10 == 0
+    };
+    valid.then(|| chars.into_iter().collect())
+}
+
+/// Whether a value is shaped like an ISBN-10 or ISBN-13, whatever its check
+/// digit, as accepted by [`normalize_isbn`]
+#[cfg(any(feature = "csv", feature = "xml", feature = "ris"))]
+pub fn is_isbn_shaped(value: &str) -> bool {
+    isbn_chars(value).is_some()
+}
+
+/// The digits of an ISBN-10 or ISBN-13, without prefix, qualifier or
+/// separators, and with an ISBN-10 check character `X` uppercased
+fn isbn_chars(isbn: &str) -> Option<Vec<char>> {
+    let trimmed = isbn.split('(').next().unwrap_or_default().trim();
+    // ASCII lowercasing keeps byte offsets valid in `trimmed`
+    let lower = trimmed.to_ascii_lowercase();
+    let start = lower
+        .strip_prefix("isbn")
+        .map(|rest| {
+            let rest = ["-10", "-13"]
+                .into_iter()
+                .find_map(|suffix| rest.strip_prefix(suffix))
+                .unwrap_or(rest);
+            trimmed.len() - rest.trim_start_matches([':', ' ']).len()
+        })
+        .unwrap_or(0);
+
+    let chars: Vec<char> = trimmed[start..]
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    let shaped = match chars.len() {
+        10 => chars[..9].iter().all(char::is_ascii_digit) && matches!(chars[9], '0'..='9' | 'X'),
+        13 => chars.iter().all(char::is_ascii_digit),
+        _ => false,
+    };
+    shaped.then_some(chars)
+}
+
+/// Splits a serial number field into ISSNs and ISBNs
+///
+/// Fields such as RIS `SN` hold ISSNs for journals and ISBNs for books. Each
+/// line or `;`-separated part shaped like an ISBN is returned as an ISBN, even
+/// with a wrong check digit, see [`crate::Citation::valid_isbns`]. ISSNs are
+/// taken from the rest as in [`split_issns`].
+///
+/// # Arguments
+///
+/// * `value` - The field value to split
+///
+/// # Returns
+///
+/// The ISSNs and the ISBNs, each as written in the input
+#[cfg(any(feature = "csv", feature = "xml"))]
+pub fn split_serial_numbers(value: &str) -> (Vec<String>, Vec<String>) {
+    let normalized = value
+        .replace("\\r\\n", "\n")
+        .replace("\\r", "\n")
+        .replace("\\n", "\n");
+    let (mut issns, mut isbns) = (Vec::new(), Vec::new());
+    for part in normalized.split(['\n', '\r', ';']) {
+        if is_isbn_shaped(part) {
+            isbns.push(part.trim().to_string());
+        } else {
+            issns.extend(split_issns(part));
+        }
+    }
+    (issns, isbns)
+}
+
+/// Normalizes an ORCID iD and validates its checksum
+///
+/// Accepts bare identifiers as well as `ORCID:` and `https://orcid.org/` prefixed
+/// forms, and returns the canonical `0000-0000-0000-0000` form. Returns `None` if
+/// the identifier is malformed or its ISO 7064 MOD 11-2 check character is wrong.
+///
+/// # Arguments
+///
+/// * `orcid` - The ORCID string to normalize
+///
+/// # Examples
+///
+/// ```
+/// use biblib::normalize_orcid;
+///
+/// assert_eq!(
+///     normalize_orcid("https://orcid.org/0000-0002-1825-0097").as_deref(),
+///     Some("0000-0002-1825-0097")
+/// );
+/// assert_eq!(normalize_orcid("0000-0002-1825-0098"), None);
+/// ```
+pub fn normalize_orcid(orcid: &str) -> Option<String> {
+    let trimmed = orcid.trim();
+    // ASCII lowercasing keeps byte offsets valid in `trimmed`
+    let lower = trimmed.to_ascii_lowercase();
+    let start = lower
+        .find("orcid.org/")
+        .map(|pos| pos + "orcid.org/".len())
+        .or_else(|| lower.strip_prefix("orcid:").map(|_| "orcid:".len()))
+        .unwrap_or(0);
+
+    let chars: Vec<char> = trimmed[start..]
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if chars.len() != 16 || !chars[..15].iter().all(char::is_ascii_digit) {
+        return None;
+    }
+
+    let total = chars[..15]
+        .iter()
+        .fold(0u32, |total, c| (total + c.to_digit(10).unwrap_or(0)) * 2);
+    let check = match (12 - total % 11) % 11 {
+        10 => 'X',
+        digit => char::from_digit(digit, 10)?,
+    };
+    if chars[15] != check {
+        return None;
+    }
+
+    let digits: String = chars.into_iter().collect();
+    Some(format!(
+        "{}-{}-{}-{}",
+        &digits[0..4],
+        &digits[4..8],
+        &digits[8..12],
+        &digits[12..16]
+    ))
+}
+
+/// Finds the PubMed IDs written as `PMID: 12345678` in free text.
+///
+/// The `PMID` label is matched in any case, with or without the colon. IDs
+/// with a leading zero or more than eight digits are not valid PMIDs and are
+/// skipped. Each ID is returned once, in the order it first appears.
+///
+/// # Examples
+///
+/// ```
+/// use biblib::extract_pmids;
+///
+/// let text = "As reported (PMID: 12345678; pmid 23456789), and again in PMID:12345678.";
+/// assert_eq!(extract_pmids(text), vec!["12345678", "23456789"]);
+/// ```
+#[cfg(feature = "std")]
+pub fn extract_pmids(text: &str) -> Vec<String> {
+    let mut pmids: Vec<String> = Vec::new();
+    for captures in INLINE_PMID_REGEX.captures_iter(text) {
+        let pmid = &captures[1];
+        if pmid.len() <= 8 && !pmid.starts_with('0') && !pmids.iter().any(|p| p == pmid) {
+            pmids.push(pmid.to_string());
+        }
+    }
+    pmids
+}
+
+/// Finds the DOIs in free text.
+///
+/// DOIs are found bare, after `doi:` or in `doi.org` links, and returned in
+/// the lowercase form of [`Citation::add_doi`](crate::Citation::add_doi).
+/// Punctuation ending the surrounding sentence is not part of the DOI, and a
+/// closing bracket is only kept when the DOI opens one. Each DOI is returned
+/// once, in the order it first appears.
+///
+/// # Examples
+///
+/// ```
+/// use biblib::extract_dois;
+///
+/// let text = "See doi:10.1000/ABC.123. The trial (https://doi.org/10.1016/S0140-6736(20)30183-5) \
+///             agrees with 10.1000/abc.123, as does 10.5555/x.";
+/// assert_eq!(
+///     extract_dois(text),
+///     vec!["10.1000/abc.123", "10.1016/s0140-6736(20)30183-5", "10.5555/x"]
+/// );
+/// ```
+#[cfg(feature = "std")]
+pub fn extract_dois(text: &str) -> Vec<String> {
+    let mut dois: Vec<String> = Vec::new();
+    for found in INLINE_DOI_REGEX.find_iter(text) {
+        let mut doi = found.as_str();
+        loop {
+            let trimmed = doi.trim_end_matches(['.', ',', ';', ':', '\'', '!', '?']);
+            let unbalanced = |open: char, close: char| {
+                trimmed.ends_with(close)
+                    && trimmed.matches(open).count() < trimmed.matches(close).count()
+            };
+            doi = if unbalanced('(', ')') || unbalanced('[', ']') || unbalanced('{', '}') {
+                &trimmed[..trimmed.len() - 1]
+            } else {
+                trimmed
+            };
+            if doi.len() == trimmed.len() {
+                break;
+            }
+        }
+        let Some(doi) = format_doi(doi) else {
+            continue;
+        };
+        if !doi.ends_with('/') && !dois.contains(&doi) {
+            dois.push(doi);
+        }
+    }
+    dois
+}
+
+/// Checks a four-digit `YYMM` arXiv date, where the month must be valid
+fn is_arxiv_month(yymm: &str) -> bool {
+    yymm.len() == 4
+        && yymm.bytes().all(|b| b.is_ascii_digit())
+        && matches!(yymm[2..].parse::<u8>(), Ok(1..=12))
+}
+
+/// Normalizes an arXiv identifier.
+///
+/// Accepts identifiers in the current `YYMM.NNNNN` scheme, with four digits
+/// after the dot for papers from before 2015, and in the old `archive/YYMMNNN`
+/// scheme, such as `hep-th/9901001` or `math.GT/0309136`. A version suffix such
+/// as `v2` is kept. `arXiv:` prefixes, `arxiv.org/abs/` and `arxiv.org/pdf/`
+/// links and the `10.48550/arXiv.` DOI prefix are removed. Returns `None` if
+/// the identifier is malformed.
+///
+/// # Arguments
+///
+/// * `id` - The arXiv identifier to normalize
+///
+/// # Examples
+///
+/// ```
+/// use biblib::normalize_arxiv_id;
+///
+/// assert_eq!(normalize_arxiv_id("arXiv:2101.12345v2").as_deref(), Some("2101.12345v2"));
+/// assert_eq!(
+///     normalize_arxiv_id("https://arxiv.org/abs/hep-th/9901001").as_deref(),
+///     Some("hep-th/9901001")
+/// );
+/// assert_eq!(
+///     normalize_arxiv_id("10.48550/arXiv.1706.03762").as_deref(),
+///     Some("1706.03762")
+/// );
+/// assert_eq!(normalize_arxiv_id("2113.12345"), None);
+/// assert_eq!(normalize_arxiv_id("1234.5678.9"), None);
+/// ```
+pub fn normalize_arxiv_id(id: &str) -> Option<String> {
+    let trimmed = id.trim();
+    let lower = trimmed.to_ascii_lowercase();
+    let start = [
+        "arxiv.org/abs/",
+        "arxiv.org/pdf/",
+        "10.48550/arxiv.",
+        "arxiv:",
+    ]
+    .iter()
+    .find_map(|prefix| lower.find(prefix).map(|pos| pos + prefix.len()))
+    .unwrap_or(0);
+    let rest = trimmed[start..].trim_start();
+    let rest = rest.strip_suffix(".pdf").unwrap_or(rest);
+
+    let (base, version) = match rest.rfind('v') {
+        Some(pos)
+            if pos + 1 < rest.len() && rest[pos + 1..].bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            rest.split_at(pos)
+        }
+        _ => (rest, ""),
+    };
+
+    let valid = if let Some((archive, number)) = base.split_once('/') {
+        let (name, subject) = archive.split_once('.').unwrap_or((archive, ""));
+        !name.is_empty()
+            && name.chars().all(|c| c.is_ascii_alphabetic() || c == '-')
+            && (subject.is_empty()
+                || subject.len() == 2 && subject.chars().all(|c| c.is_ascii_alphabetic()))
+            && number.len() == 7
+            && number.bytes().all(|b| b.is_ascii_digit())
+            && is_arxiv_month(&number[..4])
+    } else if let Some((yymm, number)) = base.split_once('.') {
+        let digits = number.len();
+        is_arxiv_month(yymm)
+            && number.bytes().all(|b| b.is_ascii_digit())
+            && (digits == 4 && ("0704"..="1412").contains(&yymm) || digits == 5 && yymm >= "1501")
+    } else {
+        false
+    };
+    valid.then(|| format!("{base}{version}"))
+}
+
+/// Finds the arXiv identifiers in free text.
+///
+/// Identifiers are found after `arXiv:`, in `arxiv.org` links and in arXiv
+/// DOIs, and returned in the form of [`normalize_arxiv_id`]. Bare numbers such
+/// as `2101.12345` are not taken for identifiers. Each identifier is returned
+/// once, in the order it first appears.
+///
+/// # Examples
+///
+/// ```
+/// use biblib::extract_arxiv_ids;
+///
+/// let text = "Preprint at arXiv:2101.12345v2 [hep-th], see also \
+///             https://arxiv.org/abs/hep-th/9901001 and 10.48550/arXiv.2101.12345v2.";
+/// assert_eq!(extract_arxiv_ids(text), vec!["2101.12345v2", "hep-th/9901001"]);
+/// ```
+#[cfg(feature = "std")]
+pub fn extract_arxiv_ids(text: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for captures in INLINE_ARXIV_REGEX.captures_iter(text) {
+        if let Some(id) = normalize_arxiv_id(&captures[1]) {
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+    }
+    ids
+}
+
+/// Keys of `extra_fields` holding an arXiv identifier, lowercase
+#[cfg(any(
+    feature = "csv",
+    feature = "pubmed",
+    feature = "xml",
+    feature = "ris",
+    feature = "refer",
+    feature = "tei",
+    feature = "crossref"
+))]
+const ARXIV_ID_KEYS: [&str; 4] = ["eprint", "arxiv", "arxivid", "arxiv id"];
+
+/// Sets the arXiv identifier of citations without one, from the fields that
+/// identify the record itself
+///
+/// These are its DOIs, URLs and accession number, `extra_fields` named like
+/// `eprint` or `arXiv`, and a journal naming the preprint, as in Google
+/// Scholar's `"arXiv preprint arXiv:1706.03762"`. Notes, abstracts and other
+/// free text are not searched, since they often cite other preprints.
+///
+/// # Arguments
+///
+/// * `citations` - The citations to complete
+#[cfg(any(
+    feature = "csv",
+    feature = "pubmed",
+    feature = "xml",
+    feature = "ris",
+    feature = "refer",
+    feature = "tei",
+    feature = "crossref"
+))]
+pub fn fill_arxiv_ids(citations: &mut [Citation]) {
+    for citation in citations.iter_mut().filter(|c| c.arxiv_id.is_none()) {
+        let preprint_journal = citation.journal.iter().filter(|journal| {
+            journal
+                .trim_start()
+                .to_ascii_lowercase()
+                .starts_with("arxiv")
+        });
+        let found = citation
+            .all_dois
+            .iter()
+            .chain(&citation.urls)
+            .chain(&citation.accession_number)
+            .chain(preprint_journal)
+            .find_map(|text| extract_arxiv_ids(text).into_iter().next())
+            .or_else(|| {
+                citation
+                    .extra_fields
+                    .iter()
+                    .filter(|(key, _)| ARXIV_ID_KEYS.contains(&key.to_lowercase().as_str()))
+                    .flat_map(|(_, values)| values)
+                    .find_map(|value| normalize_arxiv_id(value))
+            });
+        citation.arxiv_id = found;
+    }
+}
+
+/// Returns the title of the work a correction notice refers to.
+///
+/// Recognizes titles prefixed with `"Erratum:"`, `"Correction to:"`,
+/// `"Corrigendum:"`, `"Retraction of:"`, `"RETRACTED ARTICLE:"`,
+/// `"Expression of concern:"` and similar. Returns `None` for other titles.
+///
+/// # Arguments
+///
+/// * `title` - The title to check
+#[cfg(feature = "std")]
+pub fn correction_target(title: &str) -> Option<&str> {
+    CORRECTION_TITLE_REGEX
+        .captures(title)
+        .map(|captures| captures.get(1).map_or("", |m| m.as_str().trim()))
+}
+
+/// Splits a trailing copyright statement from an abstract
+///
+/// The statement is the run of sentences at the end of the text that each
+/// contain `"©"`, `"(c)"` or `"Copyright"` followed by a year, or `"All rights
+/// reserved"`. Sentences merely mentioning copyright, and statements followed
+/// by more text, stay in the abstract. Returns the text before the statement
+/// and the statement, or `None` if the text does not end with one.
+///
+/// # Arguments
+///
+/// * `text` - The abstract to split
+#[cfg(feature = "std")]
+pub fn split_copyright(text: &str) -> Option<(&str, &str)> {
+    let text = text.trim_end();
+    let mut starts: Vec<usize> = SENTENCE_END_REGEX
+        .find_iter(text)
+        .map(|m| m.end())
+        .collect();
+    starts.insert(0, 0);
+
+    let mut statement_start = None;
+    let mut end = text.len();
+    for &start in starts.iter().rev() {
+        if !COPYRIGHT_REGEX.is_match(&text[start..end]) {
+            break;
+        }
+        statement_start = Some(start);
+        end = start;
+    }
+    let start = statement_start?;
+    Some((text[..start].trim_end(), text[start..].trim()))
+}
+
+/// Abbreviations kept with their period at the end of a title, lowercase
+#[cfg(feature = "std")]
+const TRAILING_ABBREVIATIONS: [&str; 22] = [
+    "al.", "approx.", "cf.", "co.", "corp.", "dr.", "e.g.", "ed.", "eds.", "etc.", "i.e.", "inc.",
+    "jr.", "ltd.", "no.", "sp.", "spp.", "sr.", "st.", "var.", "vol.", "vs.",
+];
+
+/// Removes a single period ending a title
+///
+/// The period is kept when it ends an abbreviation, such as `"etc."`,
+/// `"et al."` or `"Inc."`, an initial such as `"J."`, an acronym written with
+/// periods such as `"U.S."`, or an ellipsis. Periods within the title are
+/// never removed.
+///
+/// # Arguments
+///
+/// * `title` - The title to strip
+#[cfg(feature = "std")]
+pub fn strip_trailing_period(title: &str) -> &str {
+    let trimmed = title.trim_end();
+    let Some(rest) = trimmed.strip_suffix('.') else {
+        return title;
+    };
+    let last_word = trimmed
+        .rsplit(|c: char| c.is_whitespace() || c == '(')
+        .next()
+        .unwrap_or_default();
+    let is_initial =
+        last_word.chars().count() == 2 && last_word.starts_with(|c: char| c.is_uppercase());
+    if rest.trim().is_empty()
+        || rest.ends_with('.')
+        || rest.ends_with('\u{2026}')
+        || is_initial
+        || last_word[..last_word.len() - 1].contains('.')
+        || TRAILING_ABBREVIATIONS.contains(&last_word.to_lowercase().as_str())
+    {
+        return title;
+    }
+    rest
+}
+
+/// Words kept lowercase inside a title-cased title, unless they start it or
+/// follow a colon
+const TITLE_SMALL_WORDS: [&str; 24] = [
+    "a", "an", "and", "as", "at", "but", "by", "for", "from", "in", "into", "nor", "of", "on",
+    "or", "over", "per", "the", "to", "up", "versus", "via", "vs", "with",
+];
+
+/// Acronyms and mixed-case terms restored when title-casing, keyed by their
+/// uppercase form
+const TITLE_ACRONYMS: [(&str, &str); 42] = [
+    ("ADHD", "ADHD"),
+    ("AI", "AI"),
+    ("AIDS", "AIDS"),
+    ("BMI", "BMI"),
+    ("CD", "CD"),
+    ("COPD", "COPD"),
+    ("COV", "CoV"),
+    ("COVID", "COVID"),
+    ("CRISPR", "CRISPR"),
+    ("CT", "CT"),
+    ("DNA", "DNA"),
+    ("ECG", "ECG"),
+    ("EEG", "EEG"),
+    ("EU", "EU"),
+    ("GP", "GP"),
+    ("HBA1C", "HbA1c"),
+    ("HIV", "HIV"),
+    ("HPV", "HPV"),
+    ("ICU", "ICU"),
+    ("IGA", "IgA"),
+    ("IGE", "IgE"),
+    ("IGG", "IgG"),
+    ("IGM", "IgM"),
+    ("IL", "IL"),
+    ("IVF", "IVF"),
+    ("MERS", "MERS"),
+    ("MRI", "MRI"),
+    ("MRNA", "mRNA"),
+    ("MRSA", "MRSA"),
+    ("NHS", "NHS"),
+    ("NSAID", "NSAID"),
+    ("NSAIDS", "NSAIDs"),
+    ("PCR", "PCR"),
+    ("PTSD", "PTSD"),
+    ("RCT", "RCT"),
+    ("RCTS", "RCTs"),
+    ("RNA", "RNA"),
+    ("SARS", "SARS"),
+    ("TB", "TB"),
+    ("TNF", "TNF"),
+    ("UK", "UK"),
+    ("USA", "USA"),
+];
+
+/// Name particles kept lowercase when re-casing a family name, unless they are
+/// its last word
+const NAME_PARTICLES: [&str; 18] = [
+    "da", "das", "de", "del", "della", "den", "der", "des", "di", "do", "dos", "du", "la", "le",
+    "ten", "ter", "van", "von",
+];
+
+/// Returns `true` if `text` is written entirely in capitals
+///
+/// Text counts as all caps when it has uppercase letters, no lowercase
+/// letters and at least two words, so single acronyms are left alone.
+fn is_all_caps(text: &str) -> bool {
+    let words = text
+        .split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphabetic))
+        .count();
+    words >= 2 && !text.chars().any(char::is_lowercase)
+}
+
+/// Uppercases the first letter of `word` and lowercases the rest
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+/// Returns `true` for Roman numerals up to XXXIX, as in `"Part II"`
+fn is_roman_numeral(word: &str) -> bool {
+    let tens = word.trim_start_matches('X');
+    word.len() - tens.len() <= 3
+        && ["I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX", ""].contains(&tens)
+        && !word.is_empty()
+}
+
+/// Title-cases one hyphen-free piece of an all-caps title word
+fn title_case_piece(piece: &str, small_allowed: bool) -> String {
+    let start = piece.find(char::is_alphanumeric).unwrap_or(piece.len());
+    let end = piece.rfind(char::is_alphanumeric).map_or(start, |i| {
+        i + piece[i..].chars().next().map_or(0, char::len_utf8)
+    });
+    let (lead, core, trail) = (&piece[..start], &piece[start..end], &piece[end..]);
+    if core.is_empty() {
+        return piece.to_string();
+    }
+
+    let lower = core.to_lowercase();
+    let cased = if let Some((_, acronym)) = TITLE_ACRONYMS.iter().find(|(upper, _)| *upper == core)
+    {
+        (*acronym).to_string()
+    } else if core.chars().any(|c| c.is_ascii_digit()) || is_roman_numeral(core) {
+        core.to_string()
+    } else if small_allowed && TITLE_SMALL_WORDS.contains(&lower.as_str()) {
+        lower
+    } else {
+        capitalize(core)
+    };
+    format!("{lead}{cased}{trail}")
+}
+
+/// Title-cases a title written entirely in capitals
+///
+/// Each word is capitalized, except small words such as `"of"` and `"the"`
+/// within the title. Known acronyms (`"DNA"`, `"COVID"`, `"mRNA"`), Roman
+/// numerals and words containing digits (`"H1N1"`, `"IL6"`) keep their
+/// capitals. Titles that are already in mixed or lower case are returned
+/// unchanged.
+///
+/// # Arguments
+///
+/// * `title` - The title to re-case
+pub fn title_case(title: &str) -> String {
+    if !is_all_caps(title) {
+        return title.to_string();
+    }
+
+    let mut result = String::with_capacity(title.len());
+    let mut starts_phrase = true;
+    for (i, word) in title.split(' ').enumerate() {
+        if i > 0 {
+            result.push(' ');
+        }
+        let mut first_piece = true;
+        for piece in word.split_inclusive(['-', '/']) {
+            let (piece, separator) = match piece.strip_suffix(['-', '/']) {
+                Some(stripped) => (stripped, &piece[stripped.len()..]),
+                None => (piece, ""),
+            };
+            result.push_str(&title_case_piece(piece, !(starts_phrase && first_piece)));
+            result.push_str(separator);
+            first_piece = false;
+        }
+        if word.chars().any(char::is_alphanumeric) {
+            starts_phrase = word.ends_with([':', '.', '?', '!']);
+        } else if !word.is_empty() {
+            starts_phrase = true;
+        }
+    }
+    result
+}
+
+/// Whether a word of a given name written in capitals is initials, e.g.
+/// `"JM"`: it has at most two letters or no vowels
+pub fn is_capital_initials(word: &str) -> bool {
+    let letters: Vec<char> = word.chars().filter(|c| c.is_alphabetic()).collect();
+    letters.len() <= 2
+        || !letters
+            .iter()
+            .any(|c| matches!(c, 'A' | 'E' | 'I' | 'O' | 'U' | 'Y'))
+}
+
+/// Re-cases one word of a personal name written in capitals
+fn name_case_word(word: &str, is_given: bool) -> String {
+    if is_given && is_capital_initials(word) {
+        return word.to_string();
+    }
+
+    let mut result = String::with_capacity(word.len());
+    for piece in word.split_inclusive(['-', '\'', '\u{2019}']) {
+        let cased = capitalize(piece);
+        match cased.strip_prefix("Mc") {
+            Some(rest) if rest.chars().count() > 1 => {
+                result.push_str("Mc");
+                result.push_str(&capitalize(rest));
+            }
+            _ => result.push_str(&cased),
+        }
+    }
+    result
+}
+
+/// Re-cases a family or given name written entirely in capitals
+///
+/// Each word is capitalized, including after hyphens and apostrophes, so
+/// `"O'BRIEN-SMITH"` becomes `"O'Brien-Smith"` and `"MCDONALD"` becomes
+/// `"McDonald"`. In family names, particles such as `"van"` and `"de"` are
+/// lowercased unless they end the name. In given names, words of at most two
+/// letters or without vowels, such as `"JM"`, are taken to be initials and kept.
+/// Names containing lowercase letters are returned unchanged.
+///
+/// # Arguments
+///
+/// * `name` - The name to re-case
+/// * `is_given` - Whether `name` is a given name rather than a family name
+pub fn name_case(name: &str, is_given: bool) -> String {
+    if name.chars().any(char::is_lowercase) {
+        return name.to_string();
+    }
+
+    let words: Vec<&str> = name.split(' ').collect();
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            let lower = word.to_lowercase();
+            if !is_given && i + 1 < words.len() && NAME_PARTICLES.contains(&lower.as_str()) {
+                lower
+            } else {
+                name_case_word(word, is_given)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Hashes bytes with 64-bit FNV-1a, which unlike `std`'s hashers is stable
+/// across Rust versions and platforms
+///
+/// # Arguments
+///
+/// * `bytes` - The bytes to hash
+#[cfg(feature = "std")]
+pub fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Normalizes a title for comparison
+///
+/// Removes inline markup, lowercases, replaces punctuation with spaces and
+/// collapses whitespace, so `"<i>In vivo</i> study: Part I."` becomes
+/// `"in vivo study part i"`.
+///
+/// # Arguments
+///
+/// * `title` - The title to normalize
+#[cfg(feature = "std")]
+pub fn normalize_title(title: &str) -> String {
+    strip_markup(title)
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Finds a plausible publication year in a date string
+///
+/// Handles dates such as `2019 Mar-Apr`, `Spring 2020`, `2019-03-01` and
+/// `2019///`. Only standalone runs of exactly four digits between 1500 and two
+/// years from now are accepted, so short volume and page numbers and compact
+/// dates like `20190301` are not mistaken for years. Numbers labeled as pages
+/// or volumes, as in `pp. 1850-1862`, `Vol 2001` or `12(3):1999-2004`, are
+/// skipped too. Returns the first match.
+///
+/// # Arguments
+///
+/// * `date` - The date string to search
+///
+/// # Examples
+///
+/// ```
+/// use biblib::extract_year;
+///
+/// assert_eq!(extract_year("Spring 2020"), Some(2020));
+/// assert_eq!(extract_year("Ann Surg 12(3):1850-1862, 1998"), Some(1998));
+/// assert_eq!(extract_year("20190301"), None);
+/// ```
+#[cfg(feature = "std")]
+pub fn extract_year(date: &str) -> Option<i32> {
+    let max_year = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(2100, |elapsed| {
+            1970 + (elapsed.as_secs() / 31_556_952) as i32 + 2
+        });
+
+    DIGIT_RUN_REGEX
+        .find_iter(date)
+        .filter(|run| run.len() == 4 && !NUMBER_LABEL_REGEX.is_match(&date[..run.start()]))
+        .filter_map(|run| run.as_str().parse::<i32>().ok())
+        .find(|year| (MIN_YEAR..=max_year).contains(year))
+}
+
+/// Normalizes a publication status to PubMed's `PST` codes.
+///
+/// The status is lowercased and spaces, hyphens and underscores are removed,
+/// so `Ahead of Print` and `ahead-of-print` both become `aheadofprint`.
+/// Common spellings are mapped to their code: `Epub ahead of print` to
+/// `aheadofprint`, `Epub` and `Published online` to `epublish`, and
+/// `Published in print` to `ppublish`. Other statuses keep their compacted form.
+/// Returns `None` for an empty status.
+///
+/// # Examples
+///
+/// ```
+/// use biblib::normalize_publication_status;
+///
+/// assert_eq!(
+///     normalize_publication_status("[Epub ahead of print]").as_deref(),
+///     Some("aheadofprint")
+/// );
+/// assert_eq!(normalize_publication_status("ppublish").as_deref(), Some("ppublish"));
+/// assert_eq!(normalize_publication_status(" "), None);
+/// ```
+pub fn normalize_publication_status(status: &str) -> Option<String> {
+    let compact: String = status
+        .chars()
+        .filter(|c| c.is_alphanumeric() || *c == '+')
+        .flat_map(char::to_lowercase)
+        .collect();
+    let normalized = match compact.as_str() {
+        "" => return None,
+        "epubaheadofprint" => "aheadofprint",
+        "epub" | "publishedonline" | "epubpublish" => "epublish",
+        "print" | "publishedinprint" => "ppublish",
+        other => other,
+    };
+    Some(normalized.to_string())
+}
+
+/// Removes inline markup tags such as `<i>` and `<sub>`, keeping their content
+///
+/// # Arguments
+///
+/// * `text` - The text to clean
+#[cfg(feature = "std")]
+pub fn strip_markup(text: &str) -> String {
+    MARKUP_TAG_REGEX.replace_all(text, "").into_owned()
+}
+
+/// Converts inline markup tags to markdown-style delimiters
+///
+/// Italic becomes `*text*`, bold `**text**`, superscript `^text^` and
+/// subscript `~text~`. Underline tags are removed.
+///
+/// # Arguments
+///
+/// * `text` - The text to convert
+#[cfg(feature = "pubmed")]
+pub fn markup_to_markdown(text: &str) -> String {
+    MARKUP_TAG_REGEX
+        .replace_all(text, |caps: &regex::Captures| {
+            let tag = caps[0]
+                .trim_start_matches('<')
+                .trim_start_matches('/')
+                .trim_end_matches('>')
+                .to_lowercase();
+            MARKUP_DELIMITERS
+                .iter()
+                .find(|(name, _)| *name == tag)
+                .map_or("", |(_, delimiter)| delimiter)
+                .to_string()
+        })
+        .into_owned()
+}
+
+/// Removes byte order marks and null bytes from parser input, and normalizes
+/// line endings to `\n`
+///
+/// Files saved on Windows often start with a UTF-8 byte order mark, which would
+/// otherwise garble the first tag, and concatenated exports can carry one per
+/// file. Null bytes are dropped wherever they occur. Windows `\r\n` and the
+/// lone `\r` of classic Mac OS exports both become `\n`, so parsers can split
+/// lines on `\n` alone. The input is borrowed unchanged when there is nothing
+/// to change.
+///
+/// # Arguments
+///
+/// * `input` - The raw parser input
+#[cfg(any(
+    feature = "csv",
+    feature = "pubmed",
+    feature = "xml",
+    feature = "ris",
+    feature = "refer",
+    feature = "tei",
+    feature = "crossref"
+))]
+pub fn clean_input(input: &str) -> Cow<'_, str> {
+    if input.contains(['\u{feff}', '\0', '\r']) {
+        Cow::Owned(
+            input
+                .replace(['\u{feff}', '\0'], "")
+                .replace("\r\n", "\n")
+                .replace('\r', "\n"),
+        )
+    } else {
+        Cow::Borrowed(input)
+    }
+}
+
+/// Collapses runs of whitespace to a single space and strips zero-width
+/// characters
+///
+/// Non-breaking spaces, tabs and line breaks all count as whitespace, and the
+/// result is trimmed. Zero-width spaces and joiners, word joiners and stray
+/// byte order marks are removed. The value is borrowed unchanged when there is
+/// nothing to change.
+///
+/// # Arguments
+///
+/// * `value` - The text to normalize
+///
+/// # Examples
+///
+/// ```
+/// use biblib::collapse_whitespace;
+///
+/// assert_eq!(
+///     collapse_whitespace(" Effects of\u{a0}aspirin \u{200b}\n  on platelets"),
+///     "Effects of aspirin on platelets"
+/// );
+/// ```
+pub fn collapse_whitespace(value: &str) -> Cow<'_, str> {
+    let mut collapsed = String::with_capacity(value.len());
+    let mut pending_space = false;
+    for c in value.chars() {
+        if matches!(c, '\u{200b}'..='\u{200d}' | '\u{2060}' | '\u{feff}') {
+            continue;
+        }
+        if c.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+        if pending_space && !collapsed.is_empty() {
+            collapsed.push(' ');
+        }
+        pending_space = false;
+        collapsed.push(c);
+    }
+    if collapsed == value {
+        Cow::Borrowed(value)
+    } else {
+        Cow::Owned(collapsed)
+    }
+}
+
+/// Splits a combined volume and issue such as `12(3)` into `("12", "3")`
+///
+/// Returns `None` unless the whole value has that shape.
+///
+/// # Arguments
+///
+/// * `value` - The volume to split
+#[cfg(feature = "ris")]
+pub fn split_volume_issue(value: &str) -> Option<(&str, &str)> {
+    let caps = VOLUME_ISSUE_REGEX.captures(value.trim())?;
+    Some((caps.get(1)?.as_str(), caps.get(2)?.as_str()))
+}
+
+/// Characters joining the letters or digits on either side into one word
+const WORD_JOINERS: [char; 4] = ['\'', '\u{2019}', '-', '.'];
+
+/// Checks whether a character is a Chinese or Japanese ideograph or kana,
+/// which are written without spaces between words
+fn is_ideographic(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'
+        | '\u{3400}'..='\u{4DBF}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{F900}'..='\u{FAFF}'
+        | '\u{20000}'..='\u{2FFFF}')
+}
+
+/// Splits text into words
+///
+/// A word is a run of letters and digits, which may contain apostrophes,
+/// hyphens and periods between them, as in `"don't"`, `"well-known"` and
+/// `"3.5"`. Other punctuation separates words and is skipped. Each ideograph
+/// or kana is a word of its own, as there are no spaces to go by.
+///
+/// # Arguments
+///
+/// * `text` - The text to split
+pub fn words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|&(_, next)| next);
+        let joins = WORD_JOINERS.contains(&c)
+            && next.is_some_and(|next| next.is_alphanumeric() && !is_ideographic(next));
+        if c.is_alphanumeric() && !is_ideographic(c) {
+            start.get_or_insert(i);
+        } else if start.is_none() || !joins {
+            if let Some(start) = start.take() {
+                words.push(&text[start..i]);
+            }
+            if is_ideographic(c) {
+                words.push(&text[i..i + c.len_utf8()]);
+            }
+        }
+    }
+    if let Some(start) = start {
+        words.push(&text[start..]);
+    }
+    words
+}
+
+/// Counts the sentences in text, at least one
+///
+/// A sentence ends with `.`, `!` or `?` before whitespace or the end of the
+/// text, possibly followed by closing quotes or brackets, or with a full-width
+/// `。`, `！` or `？`.
+///
+/// # Arguments
+///
+/// * `text` - The text to count sentences in
+pub fn count_sentences(text: &str) -> usize {
+    let ends_sentence = |token: &str| {
+        token
+            .trim_end_matches(['"', '\'', ')', ']', '\u{201D}', '\u{2019}'])
+            .ends_with(['.', '!', '?'])
+    };
+    let latin = text.split_whitespace().filter(|t| ends_sentence(t)).count();
+    let full_width = text.matches(['\u{3002}', '\u{FF01}', '\u{FF1F}']).count();
+    let unterminated = text
+        .split_whitespace()
+        .last()
+        .is_some_and(|t| !ends_sentence(t) && !t.ends_with(['\u{3002}', '\u{FF01}', '\u{FF1F}']));
+    (latin + full_width + usize::from(unterminated)).max(1)
+}
 
-        if !matches.is_empty() {
-            result.extend(matches.into_iter().map(String::from));
+/// Estimates the number of syllables in an English word
+///
+/// Counts groups of vowels, ignoring a silent final `e`, with at least one
+/// syllable per word.
+///
+/// # Arguments
+///
+/// * `word` - The word to count syllables in
+pub fn count_syllables(word: &str) -> usize {
+    let word = word.to_lowercase();
+    let is_vowel = |c: char| "aeiouy".contains(c);
+    let mut count = 0;
+    let mut previous_vowel = false;
+    for c in word.chars() {
+        let vowel = is_vowel(c);
+        if vowel && !previous_vowel {
+            count += 1;
         }
+        previous_vowel = vowel;
     }
-    result
+    let silent_e = word.ends_with('e')
+        && !word.ends_with("le")
+        && word.chars().rev().nth(1).is_some_and(|c| !is_vowel(c));
+    if silent_e {
+        count -= 1;
+    }
+    count.max(1)
 }
 
 /// Helper function to parse author names in various formats
+#[cfg(any(
+    feature = "csv",
+    feature = "pubmed",
+    feature = "xml",
+    feature = "ris",
+    feature = "refer"
+))]
 pub fn parse_author_name(name: &str) -> (String, String) {
     // Handle formats like "Lastname, Firstname", "Lastname, FN", or "Lastname FN"
     let parts: Vec<&str> = if name.contains(',') {
@@ -165,10 +1487,368 @@ pub fn parse_author_name(name: &str) -> (String, String) {
     }
 }
 
+/// Records the parsed record of each citation under [`SOURCE_RECORD_KEY`]
+///
+/// Does nothing without a parser source. A citation's own `source`, e.g. from
+/// a RIS `DB` tag, takes precedence over the parser's.
+///
+/// # Arguments
+///
+/// * `citations` - The parsed citations
+/// * `source` - The source set with the parser's `with_source` method
+#[cfg(any(
+    feature = "csv",
+    feature = "pubmed",
+    feature = "xml",
+    feature = "ris",
+    feature = "refer",
+    feature = "tei",
+    feature = "crossref"
+))]
+pub fn stamp_source_records(citations: &mut [Citation], source: Option<&str>) {
+    let Some(source) = source else {
+        return;
+    };
+    for citation in citations {
+        let source = citation.source.as_deref().unwrap_or(source);
+        let record = format!("{source}:{}", citation.id);
+        citation
+            .extra_fields
+            .insert(SOURCE_RECORD_KEY.to_string(), vec![record]);
+    }
+}
+
+/// Receives warnings from parsers and the deduplicator; ignores them if unset
+#[cfg(any(
+    feature = "csv",
+    feature = "pubmed",
+    feature = "xml",
+    feature = "ris",
+    feature = "refer",
+    feature = "tei",
+    feature = "crossref",
+    feature = "dedupe"
+))]
+#[derive(Clone, Default)]
+pub struct WarningHandler(Option<Arc<WarningFn>>);
+
+#[cfg(any(
+    feature = "csv",
+    feature = "pubmed",
+    feature = "xml",
+    feature = "ris",
+    feature = "refer",
+    feature = "tei",
+    feature = "crossref",
+    feature = "dedupe"
+))]
+type WarningFn = dyn Fn(&str) + Send + Sync;
+
+#[cfg(any(
+    feature = "csv",
+    feature = "pubmed",
+    feature = "xml",
+    feature = "ris",
+    feature = "refer",
+    feature = "tei",
+    feature = "crossref",
+    feature = "dedupe"
+))]
+impl WarningHandler {
+    /// Wraps the function set by a `with_warning_handler` builder method
+    pub fn new(handler: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        Self(Some(Arc::new(handler)))
+    }
+
+    pub fn warn(&self, message: &str) {
+        if let Some(handler) = &self.0 {
+            handler(message);
+        }
+    }
+}
+
+#[cfg(any(
+    feature = "csv",
+    feature = "pubmed",
+    feature = "xml",
+    feature = "ris",
+    feature = "refer",
+    feature = "tei",
+    feature = "crossref",
+    feature = "dedupe"
+))]
+impl fmt::Debug for WarningHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.0.is_some() {
+            "WarningHandler(Some(..))"
+        } else {
+            "WarningHandler(None)"
+        })
+    }
+}
+
+/// Appends `more` to a field value being accumulated, keeping it within `max`
+/// bytes
+///
+/// What does not fit is dropped, cutting at the last character boundary within
+/// the limit. Returns whether this call cut the value short; appending to a
+/// value already at the limit returns `false`, so callers warn once per field.
+#[cfg(any(
+    feature = "pubmed",
+    feature = "xml",
+    feature = "ris",
+    feature = "refer",
+    feature = "tei"
+))]
+pub fn push_capped(value: &mut String, more: &str, max: usize) -> bool {
+    let room = max.saturating_sub(value.len());
+    if more.len() <= room {
+        value.push_str(more);
+        return false;
+    }
+    let mut end = room;
+    while !more.is_char_boundary(end) {
+        end -= 1;
+    }
+    value.push_str(&more[..end]);
+    room > 0
+}
+
+/// Truncates every text field longer than `max` bytes, with a warning
+///
+/// Fields are cut at the last character boundary within the limit. Parsers
+/// also cap fields while accumulating them, with [`push_capped`]; this catches
+/// the values that were read whole.
+///
+/// # Arguments
+///
+/// * `citations` - The parsed citations to check
+/// * `max` - The longest field length, in bytes, to keep
+/// * `warnings` - Receives a warning for each truncated field
+#[cfg(any(
+    feature = "csv",
+    feature = "pubmed",
+    feature = "xml",
+    feature = "ris",
+    feature = "refer",
+    feature = "tei",
+    feature = "crossref"
+))]
+pub fn truncate_long_fields(citations: &mut [Citation], max: usize, warnings: &WarningHandler) {
+    let truncate = |value: &mut String, field: &str, id: &str| {
+        if value.len() <= max {
+            return;
+        }
+        let mut end = max;
+        while !value.is_char_boundary(end) {
+            end -= 1;
+        }
+        warnings.warn(&format!(
+            "truncated {field} of citation {id} from {} to {max} bytes",
+            value.len()
+        ));
+        value.truncate(end);
+        value.shrink_to_fit();
+    };
+
+    for citation in citations {
+        let id = &citation.id;
+        truncate(&mut citation.title, "title", id);
+        for author in &mut citation.authors {
+            truncate(&mut author.family_name, "author family name", id);
+            truncate(&mut author.given_name, "author given name", id);
+            for value in [&mut author.affiliation, &mut author.orcid]
+                .into_iter()
+                .flatten()
+            {
+                truncate(value, "author field", id);
+            }
+        }
+        for grant in &mut citation.grants {
+            for value in [&mut grant.id, &mut grant.agency, &mut grant.country]
+                .into_iter()
+                .flatten()
+            {
+                truncate(value, "grant field", id);
+            }
+        }
+        for (field, value) in [
+            ("journal", &mut citation.journal),
+            ("journal abbreviation", &mut citation.journal_abbr),
+            ("volume", &mut citation.volume),
+            ("issue", &mut citation.issue),
+            ("pages", &mut citation.pages),
+            ("status", &mut citation.status),
+            ("doi", &mut citation.doi),
+            ("pmid", &mut citation.pmid),
+            ("pmc id", &mut citation.pmc_id),
+            ("arxiv id", &mut citation.arxiv_id),
+            ("abstract", &mut citation.abstract_text),
+            ("copyright", &mut citation.copyright),
+            ("language", &mut citation.language),
+            ("publisher", &mut citation.publisher),
+            ("place", &mut citation.place),
+            ("conference name", &mut citation.conference_name),
+            ("series", &mut citation.series),
+            ("accession number", &mut citation.accession_number),
+            ("source", &mut citation.source),
+        ] {
+            if let Some(value) = value {
+                truncate(value, field, id);
+            }
+        }
+        for (field, values) in [
+            ("citation type", &mut citation.citation_type),
+            ("issn", &mut citation.issn),
+            ("isbn", &mut citation.isbn),
+            ("doi", &mut citation.all_dois),
+            ("keyword", &mut citation.keywords),
+            ("url", &mut citation.urls),
+            ("mesh term", &mut citation.mesh_terms),
+        ] {
+            for value in values {
+                truncate(value, field, id);
+            }
+        }
+        for (tag, values) in &mut citation.extra_fields {
+            for value in values {
+                truncate(value, tag, id);
+            }
+        }
+    }
+}
+
+/// Options every parser takes, set through its `with_*` builder methods
+#[cfg(any(
+    feature = "csv",
+    feature = "pubmed",
+    feature = "xml",
+    feature = "ris",
+    feature = "refer",
+    feature = "tei",
+    feature = "crossref"
+))]
+#[derive(Debug, Default, Clone)]
+pub struct ParserOptions {
+    /// Source name set with `with_source`
+    pub source: Option<String>,
+    /// Most citations to parse, set with `with_limit`
+    pub limit: Option<usize>,
+    /// Longest field kept, set with `with_max_field_length`
+    pub max_field_length: Option<usize>,
+    /// Receives the warnings of `with_warning_handler`
+    pub warning_handler: WarningHandler,
+    /// Whether whitespace is normalized, set with `with_whitespace_normalization`
+    pub normalize_whitespace: bool,
+}
+
+#[cfg(any(
+    feature = "csv",
+    feature = "pubmed",
+    feature = "xml",
+    feature = "ris",
+    feature = "refer",
+    feature = "tei",
+    feature = "crossref"
+))]
+impl ParserOptions {
+    /// The limit, or `usize::MAX` without one
+    pub fn limit(&self) -> usize {
+        self.limit.unwrap_or(usize::MAX)
+    }
+
+    /// The longest field kept, [`crate::DEFAULT_MAX_FIELD_LENGTH`] by default
+    pub fn max_field_length(&self) -> usize {
+        self.max_field_length
+            .unwrap_or(crate::DEFAULT_MAX_FIELD_LENGTH)
+    }
+
+    /// Completes parsed citations the same way in every parser
+    ///
+    /// Normalizes whitespace if enabled, fills in arXiv IDs, records each
+    /// citation's source record and truncates fields over the length limit.
+    pub fn finish(&self, citations: &mut [Citation]) {
+        if self.normalize_whitespace {
+            citations
+                .iter_mut()
+                .for_each(crate::transform::normalize_whitespace);
+        }
+        fill_arxiv_ids(citations);
+        stamp_source_records(citations, self.source.as_deref());
+        truncate_long_fields(citations, self.max_field_length(), &self.warning_handler);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_clean_input() {
+        assert!(matches!(clean_input("TY  - JOUR"), Cow::Borrowed(_)));
+        assert_eq!(clean_input("\u{feff}TY  - JOUR\0\n"), "TY  - JOUR\n");
+        assert_eq!(clean_input("a\n\u{feff}b"), "a\nb");
+        assert_eq!(clean_input("a\r\nb\rc\r\r\nd"), "a\nb\nc\n\nd");
+    }
+
+    #[test]
+    fn test_split_volume_issue() {
+        assert_eq!(split_volume_issue("12(3)"), Some(("12", "3")));
+        assert_eq!(
+            split_volume_issue(" 45 (Suppl 1) "),
+            Some(("45", "Suppl 1"))
+        );
+        assert_eq!(split_volume_issue("7(3-4)"), Some(("7", "3-4")));
+        assert_eq!(split_volume_issue("12"), None);
+        assert_eq!(split_volume_issue("12()"), None);
+        assert_eq!(split_volume_issue("12(3) 2020"), None);
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(
+            words("Well-known effects (n=3.5): don't \"panic\" -- ok?"),
+            vec!["Well-known", "effects", "n", "3.5", "don't", "panic", "ok"]
+        );
+        assert_eq!(
+            words("睡眠と記憶 study"),
+            vec!["睡", "眠", "と", "記", "憶", "study"]
+        );
+        assert!(words(" ... ").is_empty());
+    }
+
+    #[test]
+    fn test_count_sentences() {
+        assert_eq!(count_sentences("One. Two (really)! Three?"), 3);
+        assert_eq!(count_sentences("One. Two without a stop"), 2);
+        assert_eq!(count_sentences("No stop"), 1);
+        assert_eq!(count_sentences("The value was 3.5 mg. Done."), 2);
+        assert_eq!(count_sentences("睡眠。記憶。"), 2);
+    }
+
+    #[test]
+    fn test_count_syllables() {
+        assert_eq!(count_syllables("the"), 1);
+        assert_eq!(count_syllables("cake"), 1);
+        assert_eq!(count_syllables("table"), 2);
+        assert_eq!(count_syllables("reading"), 2);
+        assert_eq!(count_syllables("Memory"), 3);
+        assert_eq!(count_syllables("3"), 1);
+    }
+
+    #[test]
+    fn test_first_page() {
+        for pages in ["123-130", "123-30", "123", "pp. 123–130", " 0123 "] {
+            assert_eq!(first_page(pages).as_deref(), Some("123"), "{pages}");
+        }
+        assert_eq!(first_page("e123").as_deref(), Some("e123"));
+        assert_eq!(first_page("E123-e130").as_deref(), Some("e123"));
+        assert_eq!(first_page("S12-S15").as_deref(), Some("s12"));
+        assert_eq!(first_page("xii-xv").as_deref(), Some("xii"));
+        assert_eq!(first_page(" "), None);
+        assert_eq!(first_page("-"), None);
+    }
+
     #[test]
     fn test_format_page_numbers() {
         assert_eq!(format_page_numbers("1234-45"), "1234-1245");
@@ -322,4 +2002,440 @@ mod tests {
         // Test empty page_str
         assert_eq!(split_issns(""), Vec::<String>::new());
     }
+
+    #[test]
+    fn test_strip_markup() {
+        assert_eq!(strip_markup("H<sub>2</sub>O"), "H2O");
+        assert_eq!(
+            strip_markup("<i>In vivo</i> uptake of <SUP>11</SUP>C"),
+            "In vivo uptake of 11C"
+        );
+        assert_eq!(strip_markup("a < b and c > d"), "a < b and c > d");
+    }
+
+    #[test]
+    fn test_markup_to_markdown() {
+        assert_eq!(markup_to_markdown("H<sub>2</sub>O"), "H~2~O");
+        assert_eq!(
+            markup_to_markdown("<i>E. coli</i> and <b>bold</b> x<sup>2</sup>"),
+            "*E. coli* and **bold** x^2^"
+        );
+        assert_eq!(markup_to_markdown("<u>under</u>"), "under");
+    }
+
+    #[test]
+    fn test_normalize_arxiv_id() {
+        assert_eq!(
+            normalize_arxiv_id("https://arxiv.org/pdf/2101.12345v3.pdf").as_deref(),
+            Some("2101.12345v3")
+        );
+        assert_eq!(
+            normalize_arxiv_id("arXiv: math.GT/0309136").as_deref(),
+            Some("math.GT/0309136")
+        );
+        assert_eq!(
+            normalize_arxiv_id("solv-int/9901001v2").as_deref(),
+            Some("solv-int/9901001v2")
+        );
+        assert_eq!(
+            normalize_arxiv_id("0704.0001").as_deref(),
+            Some("0704.0001")
+        );
+        // Four digits after the dot until 2014, five since 2015
+        assert_eq!(normalize_arxiv_id("1501.1234"), None);
+        assert_eq!(normalize_arxiv_id("1412.12345"), None);
+        assert_eq!(normalize_arxiv_id("0703.1234"), None);
+        assert_eq!(normalize_arxiv_id("hep-th/9913001"), None);
+        assert_eq!(normalize_arxiv_id("hep-th/990100"), None);
+        assert_eq!(normalize_arxiv_id("10.1000/xyz"), None);
+        assert_eq!(normalize_arxiv_id(""), None);
+    }
+
+    #[test]
+    fn test_fill_arxiv_ids() {
+        let mut citations = vec![
+            Citation {
+                urls: vec!["http://arxiv.org/abs/2101.12345v1".to_string()],
+                journal: Some("arXiv preprint arXiv:2101.12345".to_string()),
+                ..Default::default()
+            },
+            Citation {
+                extra_fields: [("eprint".to_string(), vec!["hep-th/9901001".to_string()])]
+                    .into_iter()
+                    .collect(),
+                ..Default::default()
+            },
+            Citation {
+                arxiv_id: Some("1706.03762".to_string()),
+                all_dois: vec!["10.48550/arXiv.2101.12345".to_string()],
+                ..Default::default()
+            },
+            Citation {
+                abstract_text: Some("Compare arXiv:2101.12345.".to_string()),
+                journal: Some("Physical Review D".to_string()),
+                extra_fields: [(
+                    "N1".to_string(),
+                    vec!["Extends arXiv:1706.03762".to_string()],
+                )]
+                .into_iter()
+                .collect(),
+                ..Default::default()
+            },
+        ];
+        fill_arxiv_ids(&mut citations);
+        assert_eq!(citations[0].arxiv_id.as_deref(), Some("2101.12345v1"));
+        assert_eq!(citations[1].arxiv_id.as_deref(), Some("hep-th/9901001"));
+        assert_eq!(citations[2].arxiv_id.as_deref(), Some("1706.03762"));
+        assert_eq!(citations[3].arxiv_id, None);
+    }
+
+    #[test]
+    fn test_normalize_orcid() {
+        assert_eq!(
+            normalize_orcid("0000-0002-1825-0097"),
+            Some("0000-0002-1825-0097".to_string())
+        );
+        assert_eq!(
+            normalize_orcid("https://orcid.org/0000-0002-1694-233x"),
+            Some("0000-0002-1694-233X".to_string())
+        );
+        assert_eq!(
+            normalize_orcid("ORCID: 0000000218250097"),
+            Some("0000-0002-1825-0097".to_string())
+        );
+        // Wrong check digit
+        assert_eq!(normalize_orcid("0000-0002-1825-0098"), None);
+        assert_eq!(normalize_orcid("0000-0002-1825"), None);
+        assert_eq!(normalize_orcid(""), None);
+        // Characters whose lowercase form has a different length
+        assert_eq!(
+            normalize_orcid("\u{130}\u{130}orcid.org/0000-0002-1825-0097").as_deref(),
+            Some("0000-0002-1825-0097")
+        );
+        assert_eq!(normalize_orcid("\u{130} 0000-0002-1825-0097"), None);
+    }
+
+    #[test]
+    fn test_strip_trailing_period() {
+        assert_eq!(
+            strip_trailing_period("Effects of aspirin."),
+            "Effects of aspirin"
+        );
+        assert_eq!(
+            strip_trailing_period("Effects of aspirin. "),
+            "Effects of aspirin"
+        );
+        assert_eq!(strip_trailing_period("Part I. Methods"), "Part I. Methods");
+        assert_eq!(
+            strip_trailing_period("Effects of aspirin"),
+            "Effects of aspirin"
+        );
+        assert_eq!(strip_trailing_period("Why?"), "Why?");
+        assert_eq!(strip_trailing_period("."), ".");
+        for title in [
+            "Cats, dogs, etc.",
+            "A reply to Smith et al.",
+            "Acme Inc.",
+            "A profile of Smith J.",
+            "Health care in the U.S.",
+            "To be continued...",
+            "Vaccines (vs.",
+        ] {
+            assert_eq!(strip_trailing_period(title), title);
+        }
+    }
+
+    #[test]
+    fn test_normalize_title() {
+        assert_eq!(
+            normalize_title("<i>In vivo</i> study: Part I."),
+            "in vivo study part i"
+        );
+        assert_eq!(normalize_title("  "), "");
+    }
+
+    #[test]
+    fn test_normalize_publication_status() {
+        assert_eq!(
+            normalize_publication_status("Ahead of Print").as_deref(),
+            Some("aheadofprint")
+        );
+        assert_eq!(
+            normalize_publication_status("epub ahead of print").as_deref(),
+            Some("aheadofprint")
+        );
+        assert_eq!(
+            normalize_publication_status("Published online").as_deref(),
+            Some("epublish")
+        );
+        assert_eq!(
+            normalize_publication_status("ppublish+epublish").as_deref(),
+            Some("ppublish+epublish")
+        );
+        assert_eq!(
+            normalize_publication_status("pubmed-not-MEDLINE").as_deref(),
+            Some("pubmednotmedline")
+        );
+        assert_eq!(normalize_publication_status("[]"), None);
+    }
+
+    #[test]
+    fn test_extract_year() {
+        assert_eq!(extract_year("2019 Mar-Apr"), Some(2019));
+        assert_eq!(extract_year("Spring 2020"), Some(2020));
+        assert_eq!(extract_year("2019-03-01"), Some(2019));
+        assert_eq!(extract_year("2023///"), Some(2023));
+        assert_eq!(extract_year("Vol 12, 2001"), Some(2001));
+        // Compact dates, volumes and out of range numbers are rejected
+        assert_eq!(extract_year("20190301"), None);
+        assert_eq!(extract_year("123"), None);
+        assert_eq!(extract_year("1234"), None);
+        assert_eq!(extract_year("9999"), None);
+        assert_eq!(extract_year(""), None);
+        // Page ranges and volumes are not years, but a labeled date is
+        assert_eq!(extract_year("pp. 1850-1862"), None);
+        assert_eq!(extract_year("Pages 1999 – 2004"), None);
+        assert_eq!(extract_year("Vol. 2001, 2015"), Some(2015));
+        assert_eq!(extract_year("12(3):1999-2004. 2010 Jan"), Some(2010));
+        assert_eq!(extract_year("Published: 2019 Mar"), Some(2019));
+        assert_eq!(extract_year("2019-2020 season"), Some(2019));
+    }
+
+    #[test]
+    fn test_primary_doi() {
+        let dois = vec![
+            "10.5061/dryad.abc".to_string(),
+            "10.1000/article".to_string(),
+        ];
+        assert_eq!(
+            primary_doi(&dois).map(String::as_str),
+            Some("10.1000/article")
+        );
+        let datasets = vec!["10.5281/zenodo.1".to_string()];
+        assert_eq!(
+            primary_doi(&datasets).map(String::as_str),
+            Some("10.5281/zenodo.1")
+        );
+        assert_eq!(primary_doi(&[]), None);
+    }
+
+    #[test]
+    fn test_correction_target() {
+        assert_eq!(
+            correction_target("Erratum: Sleep and health"),
+            Some("Sleep and health")
+        );
+        assert_eq!(
+            correction_target("Correction to: Sleep and health"),
+            Some("Sleep and health")
+        );
+        assert_eq!(
+            correction_target("Retraction of: Sleep and health"),
+            Some("Sleep and health")
+        );
+        assert_eq!(
+            correction_target("RETRACTED ARTICLE: Sleep and health"),
+            Some("Sleep and health")
+        );
+        assert_eq!(
+            correction_target("[Retracted] Sleep and health"),
+            Some("Sleep and health")
+        );
+        assert_eq!(correction_target("Corrigendum"), Some(""));
+        assert_eq!(correction_target("Corrigendum:"), Some(""));
+        assert_eq!(correction_target("Correction of scoliosis: a review"), None);
+        assert_eq!(correction_target("Sleep and health"), None);
+    }
+
+    #[test]
+    fn test_truncate_long_fields() {
+        let mut citations = vec![Citation {
+            title: "a".repeat(10),
+            journal: Some("Short".to_string()),
+            extra_fields: [("N1".to_string(), vec!["ü".repeat(5)])].into(),
+            ..Default::default()
+        }];
+        let warnings = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = Arc::clone(&warnings);
+        let handler =
+            WarningHandler::new(move |message| sink.lock().unwrap().push(message.to_string()));
+        truncate_long_fields(&mut citations, 5, &handler);
+        assert_eq!(citations[0].title, "aaaaa");
+        assert_eq!(citations[0].journal.as_deref(), Some("Short"));
+        assert_eq!(citations[0].extra_first("N1"), Some("üü"));
+        assert_eq!(warnings.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_push_capped() {
+        let mut value = "Caf".to_string();
+        assert!(!push_capped(&mut value, "e", 5));
+        assert!(push_capped(&mut value, " é", 6));
+        // The two-byte "é" would end past the limit, so it is dropped whole
+        assert_eq!(value, "Cafe ");
+        assert!(!push_capped(&mut value, "more", 5));
+        assert_eq!(value, "Cafe ");
+    }
+
+    #[test]
+    fn test_split_copyright() {
+        assert_eq!(
+            split_copyright(
+                "Results were good. Copyright © 2020 Elsevier Ltd. All rights reserved."
+            ),
+            Some((
+                "Results were good.",
+                "Copyright © 2020 Elsevier Ltd. All rights reserved."
+            ))
+        );
+        assert_eq!(
+            split_copyright("Results were good. (c) 2019 Wiley Periodicals, Inc."),
+            Some(("Results were good.", "(c) 2019 Wiley Periodicals, Inc."))
+        );
+        assert_eq!(
+            split_copyright("Results. Published by Elsevier Ltd. All rights reserved."),
+            Some((
+                "Results. Published by Elsevier Ltd.",
+                "All rights reserved."
+            ))
+        );
+        assert_eq!(
+            split_copyright("The copyright of images (c) is discussed."),
+            None
+        );
+        // A sentence about copyright that does not end the abstract
+        let text = "Background: music sampling is litigated. Copyright law differs \
+                    between countries. Results: courts disagree.";
+        assert_eq!(split_copyright(text), None);
+        assert_eq!(
+            split_copyright(&format!("{} © 2021 Elsevier Ltd.", text)),
+            Some((text, "© 2021 Elsevier Ltd."))
+        );
+    }
+
+    #[test]
+    fn test_fnv1a_hash() {
+        assert_eq!(fnv1a_hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn test_normalize_isbn() {
+        assert_eq!(
+            normalize_isbn("978-0-306-40615-7").as_deref(),
+            Some("9780306406157")
+        );
+        assert_eq!(
+            normalize_isbn("ISBN-13: 978 0 306 40615 7").as_deref(),
+            Some("9780306406157")
+        );
+        assert_eq!(
+            normalize_isbn("0-306-40615-2 (pbk.)").as_deref(),
+            Some("0306406152")
+        );
+        assert_eq!(
+            normalize_isbn("ISBN 080442957x").as_deref(),
+            Some("080442957X")
+        );
+        assert_eq!(normalize_isbn("978-0-306-40615-8"), None);
+        assert_eq!(normalize_isbn("0-306-40615-3"), None);
+        assert_eq!(normalize_isbn("1234-5678"), None);
+        // The Kelvin sign lowercases to a one-byte `k`
+        assert_eq!(normalize_isbn("ISBN \u{212A}"), None);
+        assert_eq!(normalize_isbn("\u{212A}ISBN 0-306-40615-2"), None);
+    }
+
+    #[test]
+    fn test_split_serial_numbers() {
+        assert_eq!(
+            split_serial_numbers(
+                "1234-5678 (Print)\n978-0-306-40615-7; 0-306-40615-2\n978-3-16-148410-1"
+            ),
+            (
+                vec!["1234-5678 (Print)".to_string()],
+                vec![
+                    "978-0-306-40615-7".to_string(),
+                    "0-306-40615-2".to_string(),
+                    "978-3-16-148410-1".to_string()
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn test_extract_pmids() {
+        let text = "PMID: 12345678. Also PMID 1, pmid:23456789 and PMID: 12345678 again.";
+        assert_eq!(extract_pmids(text), vec!["12345678", "1", "23456789"]);
+        assert!(extract_pmids("PMID: 0123 and PMID: 123456789").is_empty());
+        assert!(extract_pmids("SPMID: 123 and PMIDS 456").is_empty());
+    }
+
+    #[test]
+    fn test_extract_dois() {
+        let text = "Earlier work (doi:10.1000/xyz) and [10.1000/abc], \
+                    cited as https://dx.doi.org/10.1000/XYZ; see also 10.1000/182.";
+        assert_eq!(
+            extract_dois(text),
+            vec!["10.1000/xyz", "10.1000/abc", "10.1000/182"]
+        );
+        assert!(extract_dois("Version 10.2 of the 10.1000/ guide").is_empty());
+    }
+
+    #[test]
+    fn test_title_case() {
+        assert_eq!(
+            title_case("LONG-TERM OUTCOMES OF HIV/AIDS IN CHILDREN'S HOSPITALS"),
+            "Long-Term Outcomes of HIV/AIDS in Children's Hospitals"
+        );
+        assert_eq!(
+            title_case("SARS-COV-2 AND IL6: THE ROLE OF H1N1 CO-INFECTION."),
+            "SARS-CoV-2 and IL6: The Role of H1N1 Co-Infection."
+        );
+        assert_eq!(
+            title_case("STATE-OF-THE-ART (PART III)"),
+            "State-of-the-Art (Part III)"
+        );
+        assert_eq!(
+            title_case("A REVIEW -- OF THE EVIDENCE"),
+            "A Review -- Of the Evidence"
+        );
+        assert_eq!(title_case("COVID-19"), "COVID-19");
+        assert_eq!(title_case("DNA"), "DNA");
+        assert_eq!(title_case("the pH of BLOOD"), "the pH of BLOOD");
+    }
+
+    #[test]
+    fn test_name_case() {
+        assert_eq!(name_case("SMITH", false), "Smith");
+        assert_eq!(name_case("D'ANGELO-SMITH", false), "D'Angelo-Smith");
+        assert_eq!(name_case("MCDONALD", false), "McDonald");
+        assert_eq!(name_case("DE LA CRUZ", false), "de la Cruz");
+        assert_eq!(name_case("VAN", false), "Van");
+        assert_eq!(name_case("MacDonald", false), "MacDonald");
+        assert_eq!(name_case("JOHN", true), "John");
+        assert_eq!(name_case("JEAN-PAUL", true), "Jean-Paul");
+        assert_eq!(name_case("J", true), "J");
+        assert_eq!(name_case("JMK", true), "JMK");
+        assert_eq!(name_case("J. M.", true), "J. M.");
+        assert_eq!(name_case("ANN", true), "Ann");
+    }
+
+    #[test]
+    fn test_split_dois() {
+        assert_eq!(split_dois("10.1000/a"), vec!["10.1000/a"]);
+        assert_eq!(
+            split_dois("10.1000/a 10.1000/b; doi:10.1000/c;https://doi.org/10.1000/d"),
+            vec![
+                "10.1000/a",
+                "10.1000/b",
+                "doi:10.1000/c",
+                "https://doi.org/10.1000/d"
+            ]
+        );
+        assert_eq!(
+            split_dois("10.1002/(SICI)1097-0258(19980815)17:15<1661::AID-SIM968>3.0.CO;2-2"),
+            vec!["10.1002/(SICI)1097-0258(19980815)17:15<1661::AID-SIM968>3.0.CO;2-2"]
+        );
+        assert_eq!(split_dois("10.1000/ wrapped"), vec!["10.1000/ wrapped"]);
+    }
 }